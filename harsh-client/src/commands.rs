@@ -14,15 +14,65 @@ pub fn parse(input: &str) -> Option<Command> {
             let content = rest.join(" ");
             ClientRequest::new_ping(content)
         }
-        "chanls" => ClientRequest::new_channel_list(),
+        "login" => {
+            let name = parts.next()?;
+            let pass = parts.next()?;
+            ClientRequest::new_authenticate_by_name(name, pass)
+        }
+        "chanls" => {
+            let guild_id = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_channel_list(guild_id)
+        }
         "chanadd" => {
             let name = parts.next()?;
-            ClientRequest::new_channel_create(name)
+            let guild_id = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_channel_create(name, guild_id)
+        }
+        "catadd" => {
+            let name = parts.next()?;
+            let guild_id = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_category_create(name, guild_id)
+        }
+        "changparent" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_get_parent(id)
+        }
+        "chansparent" => {
+            let id = parts.next()?.parse().ok()?;
+            let parent = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_channel_set_parent(id, parent)
+        }
+        "chanorder" => {
+            let ids = parts.map(|s| s.parse().ok()).collect::<Option<Vec<_>>>()?;
+            ClientRequest::new_channel_reorder(ids)
+        }
+        "chaninfo" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_get_info(id)
+        }
+        "chantopic" => {
+            let id = parts.next()?.parse().ok()?;
+            let topic = parts.collect::<Box<[_]>>().join(" ");
+            ClientRequest::new_channel_set_topic(id, topic)
+        }
+        "chandesc" => {
+            let id = parts.next()?.parse().ok()?;
+            let description = parts.collect::<Box<[_]>>().join(" ");
+            ClientRequest::new_channel_set_description(id, description)
+        }
+        "chankind" => {
+            let id = parts.next()?.parse().ok()?;
+            let kind = parts.next()?;
+            ClientRequest::new_channel_set_kind(id, kind)
         }
         "chandel" => {
             let id = parts.next()?.parse().ok()?;
             ClientRequest::new_channel_delete(id)
         }
+        "chanrestore" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_restore(id)
+        }
         "changname" => {
             let id = parts.next()?.parse().ok()?;
             ClientRequest::new_channel_get_name(id)
@@ -32,6 +82,34 @@ pub fn parse(input: &str) -> Option<Command> {
             let name = parts.next()?;
             ClientRequest::new_channel_set_name(id, name)
         }
+        "changslow" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_get_slow_mode(id)
+        }
+        "chansslow" => {
+            let id = parts.next()?.parse().ok()?;
+            let seconds = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_set_slow_mode(id, seconds)
+        }
+        "pinls" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_list_pins(id)
+        }
+        "pinlimit" => {
+            let id = parts.next()?.parse().ok()?;
+            let limit = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_channel_set_pin_limit(id, limit)
+        }
+        "pinadd" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_message_pin(channel_id, id)
+        }
+        "pindel" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_message_unpin(channel_id, id)
+        }
         "msgls" => {
             let channel_id = parts.next()?.parse().ok()?;
             ClientRequest::new_message_list(channel_id)
@@ -41,11 +119,22 @@ pub fn parse(input: &str) -> Option<Command> {
             let content = parts.next()?;
             ClientRequest::new_message_create(channel_id, content)
         }
+        "msgreply" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let reply_to = parts.next()?.parse().ok()?;
+            let content = parts.next()?;
+            ClientRequest::new_message_create_linked(channel_id, content, Some(reply_to), None)
+        }
         "msgdel" => {
             let channel_id = parts.next()?.parse().ok()?;
             let id = parts.next()?.parse().ok()?;
             ClientRequest::new_message_delete(channel_id, id)
         }
+        "msgrestore" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_message_restore(channel_id, id)
+        }
         "msggcont" => {
             let channel_id = parts.next()?.parse().ok()?;
             let id = parts.next()?.parse().ok()?;
@@ -57,11 +146,183 @@ pub fn parse(input: &str) -> Option<Command> {
             let content = parts.next()?;
             ClientRequest::new_message_set_content(channel_id, id, content)
         }
+        "msghist" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_message_get_history(channel_id, id)
+        }
+        "msgsearch" => {
+            let query = parts.next()?;
+            let channel_id = parts.next().and_then(|s| s.parse().ok());
+            let author_id = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_message_search(query, channel_id, author_id, None, None, None, None)
+        }
+        "msgreindex" => ClientRequest::new_message_reindex(),
+        "reactadd" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            let emoji = parts.next()?;
+            ClientRequest::new_reaction_add(channel_id, id, emoji)
+        }
+        "reactdel" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            let emoji = parts.next()?;
+            ClientRequest::new_reaction_remove(channel_id, id, emoji)
+        }
+        "reactls" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            let emoji = parts.next()?;
+            ClientRequest::new_reaction_list_users(channel_id, id, emoji)
+        }
+        "thradd" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let thread_id = parts.next()?.parse().ok()?;
+            let content = parts.next()?;
+            ClientRequest::new_message_create_linked(channel_id, content, None, Some(thread_id))
+        }
+        "thrls" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let thread_id = parts.next()?.parse().ok()?;
+            let before = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_thread_message_list(channel_id, thread_id, before, None)
+        }
+        "thrsub" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let thread_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_thread_subscribe(channel_id, thread_id)
+        }
+        "thrunsub" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let thread_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_thread_unsubscribe(channel_id, thread_id)
+        }
+        "ack" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_ack(channel_id, id)
+        }
+        "readls" => ClientRequest::new_read_state_list(),
+        "chanprivate" => {
+            let id = parts.next()?.parse().ok()?;
+            let private = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_set_private(id, private)
+        }
+        "memadd" => {
+            let id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_member_add(id, user_id)
+        }
+        "memdel" => {
+            let id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_member_remove(id, user_id)
+        }
+        "memls" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_member_list(id)
+        }
+        "dm" => {
+            let users = parts.map(|s| s.parse().ok()).collect::<Option<Vec<_>>>()?;
+            ClientRequest::new_direct_open(users)
+        }
+        "dmls" => ClientRequest::new_direct_list(),
+        "mentionls" => {
+            let after = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_mentions_list(after, None)
+        }
+        "roleadd" => {
+            let name = parts.next()?;
+            let permissions = parts.next()?.parse().ok()?;
+            let guild_id = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_role_create(name, permissions, guild_id)
+        }
+        "roleset" => {
+            let id = parts.next()?.parse().ok()?;
+            let name = parts.next()?;
+            let permissions = parts.next()?.parse().ok()?;
+            ClientRequest::new_role_update(id, name, permissions)
+        }
+        "roledel" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_role_delete(id)
+        }
+        "rolels" => {
+            let guild_id = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_role_list(guild_id)
+        }
+        "rolegive" => {
+            let role_id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_role_assign(role_id, user_id)
+        }
+        "roletake" => {
+            let role_id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_role_unassign(role_id, user_id)
+        }
+        "usrroles" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_user_list_roles(id)
+        }
+        "owrole" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let role_id = parts.next()?.parse().ok()?;
+            let allow = parts.next()?.parse().ok()?;
+            let deny = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_set_role_overwrite(channel_id, role_id, allow, deny)
+        }
+        "owuser" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            let allow = parts.next()?.parse().ok()?;
+            let deny = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_set_user_overwrite(channel_id, user_id, allow, deny)
+        }
+        "owls" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_channel_list_overwrites(channel_id)
+        }
+        "perms" => {
+            let channel_id = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_permissions_get(channel_id)
+        }
+        "guildadd" => {
+            let name = parts.next()?;
+            ClientRequest::new_guild_create(name)
+        }
+        "guilddel" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_guild_delete(id)
+        }
+        "guildls" => ClientRequest::new_guild_list(),
+        "guildjoin" => {
+            let id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_guild_member_add(id, user_id)
+        }
+        "guildkick" => {
+            let id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_guild_member_remove(id, user_id)
+        }
+        "guildmembers" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_guild_member_list(id)
+        }
+        "guildop" => {
+            let id = parts.next()?.parse().ok()?;
+            let user_id = parts.next()?.parse().ok()?;
+            let op = parts.next()?.parse().ok()?;
+            ClientRequest::new_guild_set_op(id, user_id, op)
+        }
         "usrls" => ClientRequest::new_user_list(),
         "usradd" => {
             let name = parts.next()?;
             let pass = parts.next()?;
-            ClientRequest::new_user_create(name, pass)
+            let invite = parts.next();
+            ClientRequest::new_user_create(name, pass, invite)
         }
         "usrdel" => {
             let id = parts.next()?.parse().ok()?;
@@ -79,7 +340,97 @@ pub fn parse(input: &str) -> Option<Command> {
         "usrspass" => {
             let id = parts.next()?.parse().ok()?;
             let pass = parts.next()?;
-            ClientRequest::new_user_set_pass(id, pass)
+            let current = parts.next();
+            ClientRequest::new_user_set_pass(id, pass, current)
+        }
+        "usrfind" => {
+            let name = parts.next()?;
+            ClientRequest::new_user_find_by_name(name)
+        }
+        "prof" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_profile_get(id)
+        }
+        "profset" => {
+            let id = parts.next()?.parse().ok()?;
+            let field = parts.next()?;
+            let value = Some(parts.collect::<Vec<_>>().join(" "));
+            let (mut display_name, mut avatar, mut bio, mut custom_status) =
+                (None, None, None, None);
+            match field.as_str() {
+                "name" => display_name = value,
+                "avatar" => avatar = value,
+                "bio" => bio = value,
+                "status" => custom_status = value,
+                _ => return None,
+            }
+            ClientRequest::new_profile_update(id, display_name, avatar, bio, custom_status)
+        }
+        "presence" => {
+            let status = parts.next()?;
+            ClientRequest::new_presence_set(status)
+        }
+        "presencels" => {
+            let ids = parts.map(|id| id.parse().ok()).collect::<Option<_>>()?;
+            ClientRequest::new_presence_get(ids)
+        }
+        "typing" => {
+            let channel_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_typing_start(channel_id)
+        }
+        "lockls" => ClientRequest::new_lockout_list(),
+        "invadd" => {
+            let max_uses = parts.next().and_then(|s| s.parse().ok());
+            let expires_in = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_invite_create(max_uses, expires_in, None)
+        }
+        "invls" => ClientRequest::new_invite_list(None),
+        "invdel" => {
+            let code = parts.next()?;
+            ClientRequest::new_invite_revoke(code)
+        }
+        "ban" => {
+            let id = parts.next()?.parse().ok()?;
+            let duration = parts.next().and_then(|s| s.parse().ok());
+            let purge_secs = parts.next().and_then(|s| s.parse().ok());
+            let reason = parts.next();
+            ClientRequest::new_ban_create(id, reason, duration, purge_secs)
+        }
+        "unban" => {
+            let id = parts.next()?.parse().ok()?;
+            ClientRequest::new_ban_remove(id)
+        }
+        "banls" => ClientRequest::new_ban_list(),
+        "kick" => {
+            let id = parts.next()?.parse().ok()?;
+            let reason = parts.next();
+            ClientRequest::new_user_kick(id, reason)
+        }
+        "timeout" => {
+            let id = parts.next()?.parse().ok()?;
+            let duration = parts.next()?.parse().ok()?;
+            ClientRequest::new_user_timeout(id, duration)
+        }
+        "guildinvadd" => {
+            let guild_id = parts.next()?.parse().ok()?;
+            let max_uses = parts.next().and_then(|s| s.parse().ok());
+            let expires_in = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_invite_create(max_uses, expires_in, Some(guild_id))
+        }
+        "guildinvls" => {
+            let guild_id = parts.next()?.parse().ok()?;
+            ClientRequest::new_invite_list(Some(guild_id))
+        }
+        "guildinvuse" => {
+            let code = parts.next()?;
+            ClientRequest::new_invite_use(code)
+        }
+        "auditls" => {
+            let action = parts.next().filter(|action| action != "-");
+            let actor_id = parts.next().and_then(|s| s.parse().ok());
+            let target_id = parts.next().and_then(|s| s.parse().ok());
+            let before = parts.next().and_then(|s| s.parse().ok());
+            ClientRequest::new_audit_log_list(before, None, actor_id, action, target_id)
         }
         _ => return None,
     };
@@ -95,26 +446,264 @@ pub const CMDS: &[Description] = &[
         &["content"],
         "sends a ping with the specified content",
     ),
-    Description::new("chanls", &[], "list channels"),
-    Description::new("chanadd", &["name"], "creates a new channel"),
+    Description::new("login", &["name", "pass"], "authenticate as a user"),
+    Description::new("chanls", &["[guild_id]"], "list channels"),
+    Description::new("chanadd", &["name", "[guild_id]"], "creates a new channel"),
+    Description::new("catadd", &["name", "[guild_id]"], "creates a new category"),
+    Description::new("changparent", &["id"], "get the category of a channel"),
+    Description::new(
+        "chansparent",
+        &["id", "[category_id]"],
+        "move a channel into a category, or to the top level",
+    ),
+    Description::new("chanorder", &["id", "[id...]"], "reorder channels"),
+    Description::new(
+        "chaninfo",
+        &["id"],
+        "get a channel's topic, description, kind and creation",
+    ),
+    Description::new("chantopic", &["id", "topic"], "set a channel's topic"),
+    Description::new(
+        "chandesc",
+        &["id", "description"],
+        "set a channel's description",
+    ),
+    Description::new(
+        "chankind",
+        &["id", "text|announcement|read_only"],
+        "set a channel's kind",
+    ),
     Description::new("chandel", &["id"], "delete a channel by its id"),
+    Description::new("chanrestore", &["id"], "restore a deleted channel"),
     Description::new("changname", &["id"], "get a channel's name"),
     Description::new("chansname", &["id", "name"], "set a channel's name"),
+    Description::new("changslow", &["id"], "get a channel's slow mode delay"),
+    Description::new(
+        "chansslow",
+        &["id", "seconds"],
+        "set a channel's slow mode delay, 0 to disable",
+    ),
+    Description::new("pinls", &["id"], "list the pinned messages of a channel"),
+    Description::new(
+        "pinlimit",
+        &["id", "[limit]"],
+        "set the maximum number of pinned messages, none for the default",
+    ),
+    Description::new("pinadd", &["channel_id", "id"], "pin a message"),
+    Description::new("pindel", &["channel_id", "id"], "unpin a message"),
     Description::new("msgls", &["channel_id"], "list messages"),
     Description::new("msgadd", &["channel_id", "content"], "create a message"),
+    Description::new(
+        "msgreply",
+        &["channel_id", "reply_to", "content"],
+        "answer a message",
+    ),
     Description::new("msgdel", &["channel_id", "id"], "delete a message"),
+    Description::new(
+        "msgrestore",
+        &["channel_id", "id"],
+        "restore a deleted message",
+    ),
     Description::new("msggcont", &["channel_id", "id"], "get a message's content"),
     Description::new(
         "msgscont",
         &["channel_id", "id", "content"],
         "set a message's content",
     ),
+    Description::new(
+        "msghist",
+        &["channel_id", "id"],
+        "get the past contents of a message",
+    ),
+    Description::new(
+        "msgsearch",
+        &["query", "[channel_id]", "[author_id]"],
+        "search messages, quoting phrases",
+    ),
+    Description::new("msgreindex", &[], "rebuild the message search index"),
+    Description::new(
+        "reactadd",
+        &["channel_id", "id", "emoji"],
+        "react to a message",
+    ),
+    Description::new(
+        "reactdel",
+        &["channel_id", "id", "emoji"],
+        "remove a reaction",
+    ),
+    Description::new(
+        "reactls",
+        &["channel_id", "id", "emoji"],
+        "list the users who reacted with an emoji",
+    ),
+    Description::new(
+        "thradd",
+        &["channel_id", "thread_id", "content"],
+        "post a message in the thread of a message",
+    ),
+    Description::new(
+        "thrls",
+        &["channel_id", "thread_id", "[before]"],
+        "list the messages of a thread",
+    ),
+    Description::new(
+        "thrsub",
+        &["channel_id", "thread_id"],
+        "receive the messages of a thread",
+    ),
+    Description::new(
+        "thrunsub",
+        &["channel_id", "thread_id"],
+        "stop receiving the messages of a thread",
+    ),
+    Description::new(
+        "ack",
+        &["channel_id", "id"],
+        "mark a channel as read up to a message",
+    ),
+    Description::new(
+        "readls",
+        &[],
+        "list unread messages and mentions by channel",
+    ),
+    Description::new(
+        "chanprivate",
+        &["id", "true|false"],
+        "restrict a channel to its members",
+    ),
+    Description::new("memadd", &["id", "user_id"], "add a member to a channel"),
+    Description::new(
+        "memdel",
+        &["id", "user_id"],
+        "remove a member from a channel",
+    ),
+    Description::new("memls", &["id"], "list the members of a channel"),
+    Description::new(
+        "dm",
+        &["user_id", "[user_id...]"],
+        "open a direct channel with other users",
+    ),
+    Description::new("dmls", &[], "list your direct channels"),
+    Description::new(
+        "mentionls",
+        &["[after]"],
+        "list the messages mentioning you",
+    ),
+    Description::new(
+        "roleadd",
+        &["name", "permissions", "[guild_id]"],
+        "create a role with a permission bitset",
+    ),
+    Description::new(
+        "roleset",
+        &["id", "name", "permissions"],
+        "edit a role, the everyone role sharing the id of its guild",
+    ),
+    Description::new("roledel", &["id"], "delete a role"),
+    Description::new("rolels", &["[guild_id]"], "list the roles of a guild"),
+    Description::new("rolegive", &["role_id", "user_id"], "give a role to a user"),
+    Description::new(
+        "roletake",
+        &["role_id", "user_id"],
+        "take a role from a user",
+    ),
+    Description::new("usrroles", &["id"], "list the roles of a user"),
+    Description::new(
+        "owrole",
+        &["channel_id", "role_id", "allow", "deny"],
+        "overwrite the permissions of a role in a channel",
+    ),
+    Description::new(
+        "owuser",
+        &["channel_id", "user_id", "allow", "deny"],
+        "overwrite the permissions of a user in a channel",
+    ),
+    Description::new("owls", &["channel_id"], "list the overwrites of a channel"),
+    Description::new(
+        "perms",
+        &["[channel_id]"],
+        "get your permissions, in a channel if given",
+    ),
+    Description::new("guildadd", &["name"], "create a guild"),
+    Description::new("guilddel", &["id"], "delete a guild you own"),
+    Description::new("guildls", &[], "list your guilds"),
+    Description::new("guildjoin", &["id", "user_id"], "add a user to a guild"),
+    Description::new(
+        "guildkick",
+        &["id", "user_id"],
+        "remove a user from a guild, or leave it",
+    ),
+    Description::new("guildmembers", &["id"], "list the members of a guild"),
+    Description::new(
+        "guildop",
+        &["id", "user_id", "true|false"],
+        "make a member an operator of a guild",
+    ),
     Description::new("usrls", &[], "list users"),
-    Description::new("usradd", &["name", "pass"], "add a user"),
+    Description::new(
+        "usradd",
+        &["name", "pass", "[invite]"],
+        "add a user, with an invite code if required",
+    ),
     Description::new("usrdel", &["id"], "delete a user"),
     Description::new("usrgname", &["id"], "get a user name"),
     Description::new("usrsname", &["id", "name"], "set a user name"),
-    Description::new("usrspass", &["id", "pass"], "set a user pass"),
+    Description::new(
+        "usrspass",
+        &["id", "pass", "[current]"],
+        "set a user pass, the current one being required for one's own",
+    ),
+    Description::new("usrfind", &["name"], "find a user by its name"),
+    Description::new("prof", &["id"], "get the profile of a user"),
+    Description::new(
+        "profset",
+        &["id", "name|avatar|bio|status", "[value]"],
+        "set a field of a profile, clearing it without a value",
+    ),
+    Description::new(
+        "presence",
+        &["online|idle|dnd|offline"],
+        "set one's status, online letting idleness be detected",
+    ),
+    Description::new("presencels", &["ids..."], "get the presence of users"),
+    Description::new(
+        "typing",
+        &["channel_id"],
+        "tell the channel one is typing for a few seconds",
+    ),
+    Description::new("lockls", &[], "list authentication lockouts"),
+    Description::new(
+        "invadd",
+        &["[max_uses]", "[expires_in]"],
+        "create a registration invite, expiring after a number of seconds",
+    ),
+    Description::new("invls", &[], "list registration invites"),
+    Description::new("invdel", &["code"], "revoke an invite"),
+    Description::new(
+        "ban",
+        &["id", "[duration]", "[purge_secs]", "[reason]"],
+        "ban a user for a number of seconds, trashing its messages of the last seconds",
+    ),
+    Description::new("unban", &["id"], "lift the ban of a user"),
+    Description::new("banls", &[], "list banned users"),
+    Description::new("kick", &["id", "[reason]"], "close every session of a user"),
+    Description::new(
+        "timeout",
+        &["id", "duration"],
+        "mute a user for a number of seconds, 0 lifting it",
+    ),
+    Description::new(
+        "guildinvadd",
+        &["guild_id", "[max_uses]", "[expires_in]"],
+        "create an invite to a guild, expiring after a number of seconds",
+    ),
+    Description::new("guildinvls", &["guild_id"], "list the invites to a guild"),
+    Description::new("guildinvuse", &["code"], "join a guild with an invite"),
+    Description::new(
+        "auditls",
+        &["[action|-]", "[actor_id]", "[target_id]", "[before]"],
+        "list the audit log, newest entries first",
+    ),
 ];
 
 pub fn smart_split(input: &str) -> Vec<String> {
@@ -3,18 +3,29 @@ pub struct Ping {
     pub content: String,
 }
 
+#[derive(Debug)]
+pub enum Login {
+    Id(u64),
+    Name(String),
+}
+
 #[derive(Debug)]
 pub struct Authenticate {
-    pub id: u64,
+    pub login: Login,
     pub pass: String,
 }
 
 #[derive(Debug)]
-pub struct ChannelList {}
+pub struct ChannelList {
+    /// the default guild when not given.
+    pub guild_id: Option<u64>,
+}
 
 #[derive(Debug)]
 pub struct ChannelCreate {
     pub name: String,
+    /// the default guild when not given.
+    pub guild_id: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -22,6 +33,32 @@ pub struct ChannelDelete {
     pub id: u64,
 }
 
+#[derive(Debug)]
+pub struct ChannelRestore {
+    pub id: u64,
+}
+/// creates a category, grouping the channels whose parent it is.
+#[derive(Debug)]
+pub struct CategoryCreate {
+    pub name: String,
+    /// the default guild when not given.
+    pub guild_id: Option<u64>,
+}
+#[derive(Debug)]
+pub struct ChannelGetParent {
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelSetParent {
+    pub id: u64,
+    /// a category, `None` moving the channel to the top level.
+    pub parent: Option<u64>,
+}
+#[derive(Debug)]
+pub struct ChannelReorder {
+    /// channels in their new order, the others keeping their position.
+    pub ids: Vec<u64>,
+}
 #[derive(Debug)]
 pub struct ChannelGetName {
     pub id: u64,
@@ -33,6 +70,68 @@ pub struct ChannelSetName {
     pub name: String,
 }
 
+#[derive(Debug)]
+pub struct ChannelGetInfo {
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelSetTopic {
+    pub id: u64,
+    pub topic: String,
+}
+#[derive(Debug)]
+pub struct ChannelSetDescription {
+    pub id: u64,
+    pub description: String,
+}
+/// channels can not be turned into categories, nor categories into channels.
+#[derive(Debug)]
+pub struct ChannelSetKind {
+    pub id: u64,
+    /// one of `text`, `announcement`, `read_only` or `category`.
+    pub kind: String,
+}
+#[derive(Debug)]
+pub struct ChannelGetSlowMode {
+    pub id: u64,
+}
+
+#[derive(Debug)]
+pub struct ChannelSetSlowMode {
+    pub id: u64,
+    pub seconds: u64,
+}
+
+/// restricts a channel to its members, or opens it to everyone.
+#[derive(Debug)]
+pub struct ChannelSetPrivate {
+    pub id: u64,
+    pub private: bool,
+}
+#[derive(Debug)]
+pub struct ChannelMemberAdd {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelMemberRemove {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelMemberList {
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelListPins {
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelSetPinLimit {
+    pub id: u64,
+    /// `None` to fall back to the server default.
+    pub limit: Option<u32>,
+}
 #[derive(Debug)]
 pub struct MessageList {
     pub channel_id: u64,
@@ -41,6 +140,9 @@ pub struct MessageList {
 pub struct MessageCreate {
     pub channel_id: u64,
     pub content: String,
+    pub reply_to: Option<u64>,
+    /// parent of the thread to post the message in.
+    pub thread_id: Option<u64>,
 }
 #[derive(Debug)]
 pub struct MessageDelete {
@@ -48,6 +150,21 @@ pub struct MessageDelete {
     pub id: u64,
 }
 #[derive(Debug)]
+pub struct MessageRestore {
+    pub channel_id: u64,
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct MessagePin {
+    pub channel_id: u64,
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct MessageUnpin {
+    pub channel_id: u64,
+    pub id: u64,
+}
+#[derive(Debug)]
 pub struct MessageGetContent {
     pub channel_id: u64,
     pub id: u64,
@@ -58,6 +175,179 @@ pub struct MessageSetContent {
     pub id: u64,
     pub content: String,
 }
+#[derive(Debug)]
+pub struct MessageGetHistory {
+    pub channel_id: u64,
+    pub id: u64,
+}
+
+#[derive(Debug)]
+pub struct MessageSearch {
+    pub query: String,
+    pub channel_id: Option<u64>,
+    pub author_id: Option<u64>,
+    pub after: Option<i64>,
+    pub before: Option<i64>,
+    pub offset: Option<u32>,
+    pub limit: Option<u32>,
+}
+#[derive(Debug)]
+pub struct MessageReindex {}
+
+#[derive(Debug)]
+pub struct ReactionAdd {
+    pub channel_id: u64,
+    pub id: u64,
+    pub emoji: String,
+}
+#[derive(Debug)]
+pub struct ReactionRemove {
+    pub channel_id: u64,
+    pub id: u64,
+    pub emoji: String,
+}
+#[derive(Debug)]
+pub struct ReactionListUsers {
+    pub channel_id: u64,
+    pub id: u64,
+    pub emoji: String,
+}
+#[derive(Debug)]
+pub struct ThreadMessageList {
+    pub channel_id: u64,
+    pub thread_id: u64,
+    pub before: Option<u64>,
+    pub limit: Option<u32>,
+}
+#[derive(Debug)]
+pub struct ThreadSubscribe {
+    pub channel_id: u64,
+    pub thread_id: u64,
+}
+#[derive(Debug)]
+pub struct ThreadUnsubscribe {
+    pub channel_id: u64,
+    pub thread_id: u64,
+}
+/// opens the direct channel between the authenticated user and others, creating it if needed.
+#[derive(Debug)]
+pub struct DirectOpen {
+    /// the other participants.
+    pub users: Vec<u64>,
+}
+#[derive(Debug)]
+pub struct DirectList {}
+/// mentions of the authenticated user, oldest first.
+#[derive(Debug)]
+pub struct MentionsList {
+    /// only mentions in messages posted after this one.
+    pub after: Option<u64>,
+    pub limit: Option<u32>,
+}
+/// marks a channel as read up to a message.
+#[derive(Debug)]
+pub struct ChannelAck {
+    pub channel_id: u64,
+    /// last message read in the channel.
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct ReadStateList {}
+#[derive(Debug)]
+pub struct RoleCreate {
+    pub name: String,
+    /// bitset of permissions: 1 send messages, 2 manage messages, 4 manage channels,
+    /// 8 manage users, 16 manage roles, 32 mention everyone, 64 administrator.
+    pub permissions: u64,
+    /// the default guild when not given.
+    pub guild_id: Option<u64>,
+}
+#[derive(Debug)]
+pub struct RoleUpdate {
+    pub id: u64,
+    pub name: String,
+    pub permissions: u64,
+}
+#[derive(Debug)]
+pub struct RoleDelete {
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct RoleList {
+    /// the default guild when not given.
+    pub guild_id: Option<u64>,
+}
+#[derive(Debug)]
+pub struct RoleAssign {
+    pub role_id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct RoleUnassign {
+    pub role_id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct UserListRoles {
+    pub id: u64,
+}
+/// an empty overwrite removes it.
+#[derive(Debug)]
+pub struct ChannelSetRoleOverwrite {
+    pub channel_id: u64,
+    pub role_id: u64,
+    pub allow: u64,
+    pub deny: u64,
+}
+/// an empty overwrite removes it.
+#[derive(Debug)]
+pub struct ChannelSetUserOverwrite {
+    pub channel_id: u64,
+    pub user_id: u64,
+    pub allow: u64,
+    pub deny: u64,
+}
+#[derive(Debug)]
+pub struct ChannelListOverwrites {
+    pub channel_id: u64,
+}
+/// the permissions of the requester, in a channel if one is given.
+#[derive(Debug)]
+pub struct PermissionsGet {
+    pub channel_id: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct GuildCreate {
+    pub name: String,
+}
+#[derive(Debug)]
+pub struct GuildDelete {
+    pub id: u64,
+}
+/// lists the guilds the user is a member of.
+#[derive(Debug)]
+pub struct GuildList {}
+#[derive(Debug)]
+pub struct GuildMemberAdd {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct GuildMemberRemove {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct GuildMemberList {
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct GuildSetOp {
+    pub id: u64,
+    pub user_id: u64,
+    pub op: bool,
+}
 
 #[derive(Debug)]
 pub struct UserList {}
@@ -66,6 +356,7 @@ pub struct UserList {}
 pub struct UserCreate {
     pub name: String,
     pub pass: String,
+    pub invite: Option<String>,
 }
 
 #[derive(Debug)]
@@ -89,10 +380,120 @@ pub struct UserGetPass {
     pub id: u64,
 }
 
+#[derive(Debug)]
+pub struct UserFindByName {
+    pub name: String,
+}
+
 #[derive(Debug)]
 pub struct UserSetPass {
     pub id: u64,
     pub pass: String,
+    /// required when changing one's own password.
+    pub current: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ProfileGet {
+    pub id: u64,
+}
+
+/// sets the fields given, empty ones being cleared.
+#[derive(Debug)]
+pub struct ProfileUpdate {
+    pub id: u64,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    pub bio: Option<String>,
+    pub custom_status: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct PresenceSet {
+    /// "online", "idle", "dnd" or "offline", online letting the server detect idleness.
+    pub status: String,
+}
+
+#[derive(Debug)]
+pub struct PresenceGet {
+    pub ids: Vec<u64>,
+}
+
+#[derive(Debug)]
+pub struct TypingStart {
+    pub channel_id: u64,
+}
+
+#[derive(Debug)]
+pub struct LockoutList {}
+
+#[derive(Debug)]
+pub struct InviteCreate {
+    pub max_uses: Option<u32>,
+    pub expires_in: Option<u64>,
+    /// a registration invite when not given.
+    pub guild_id: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct InviteList {
+    /// the registration invites when not given.
+    pub guild_id: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct InviteRevoke {
+    pub code: String,
+}
+
+/// joins the guild of an invite.
+#[derive(Debug)]
+pub struct InviteUse {
+    pub code: String,
+}
+
+#[derive(Debug)]
+pub struct BanCreate {
+    pub id: u64,
+    pub reason: Option<String>,
+    /// in seconds, the ban being permanent when not given.
+    pub duration: Option<u64>,
+    /// trashes the messages the user posted during this many seconds.
+    pub purge_secs: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct BanRemove {
+    pub id: u64,
+}
+
+#[derive(Debug)]
+pub struct BanList {}
+
+/// closes every session of the user.
+#[derive(Debug)]
+pub struct UserKick {
+    pub id: u64,
+    pub reason: Option<String>,
+}
+
+/// prevents the user from speaking for a while.
+#[derive(Debug)]
+pub struct UserTimeout {
+    pub id: u64,
+    /// in seconds, 0 lifting the timeout.
+    pub duration: u64,
+}
+
+/// lists the audit log, newest entries first, keeping those matching every given criterion.
+#[derive(Debug)]
+pub struct AuditLogList {
+    /// only entries older than this one.
+    pub before: Option<u64>,
+    pub limit: Option<u32>,
+    pub actor_id: Option<u64>,
+    pub action: Option<String>,
+    pub target_id: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -103,14 +504,75 @@ pub enum ClientRequest {
     ChannelList(ChannelList),
     ChannelCreate(ChannelCreate),
     ChannelDelete(ChannelDelete),
+    ChannelRestore(ChannelRestore),
+    CategoryCreate(CategoryCreate),
+    ChannelGetParent(ChannelGetParent),
+    ChannelSetParent(ChannelSetParent),
+    ChannelReorder(ChannelReorder),
     ChannelGetName(ChannelGetName),
     ChannelSetName(ChannelSetName),
+    ChannelGetInfo(ChannelGetInfo),
+    ChannelSetTopic(ChannelSetTopic),
+    ChannelSetDescription(ChannelSetDescription),
+    ChannelSetKind(ChannelSetKind),
+    ChannelGetSlowMode(ChannelGetSlowMode),
+    ChannelSetSlowMode(ChannelSetSlowMode),
+
+    ChannelSetPrivate(ChannelSetPrivate),
+    ChannelMemberAdd(ChannelMemberAdd),
+    ChannelMemberRemove(ChannelMemberRemove),
+    ChannelMemberList(ChannelMemberList),
+
+    ChannelListPins(ChannelListPins),
+    ChannelSetPinLimit(ChannelSetPinLimit),
 
     MessageList(MessageList),
     MessageCreate(MessageCreate),
     MessageDelete(MessageDelete),
+    MessageRestore(MessageRestore),
+    MessagePin(MessagePin),
+    MessageUnpin(MessageUnpin),
     MessageGetContent(MessageGetContent),
     MessageSetContent(MessageSetContent),
+    MessageGetHistory(MessageGetHistory),
+    MessageSearch(MessageSearch),
+    MessageReindex(MessageReindex),
+
+    ReactionAdd(ReactionAdd),
+    ReactionRemove(ReactionRemove),
+    ReactionListUsers(ReactionListUsers),
+
+    ThreadMessageList(ThreadMessageList),
+    ThreadSubscribe(ThreadSubscribe),
+    ThreadUnsubscribe(ThreadUnsubscribe),
+
+    DirectOpen(DirectOpen),
+    DirectList(DirectList),
+
+    MentionsList(MentionsList),
+
+    ChannelAck(ChannelAck),
+    ReadStateList(ReadStateList),
+
+    RoleCreate(RoleCreate),
+    RoleUpdate(RoleUpdate),
+    RoleDelete(RoleDelete),
+    RoleList(RoleList),
+    RoleAssign(RoleAssign),
+    RoleUnassign(RoleUnassign),
+    UserListRoles(UserListRoles),
+    ChannelSetRoleOverwrite(ChannelSetRoleOverwrite),
+    ChannelSetUserOverwrite(ChannelSetUserOverwrite),
+    ChannelListOverwrites(ChannelListOverwrites),
+    PermissionsGet(PermissionsGet),
+
+    GuildCreate(GuildCreate),
+    GuildDelete(GuildDelete),
+    GuildList(GuildList),
+    GuildMemberAdd(GuildMemberAdd),
+    GuildMemberRemove(GuildMemberRemove),
+    GuildMemberList(GuildMemberList),
+    GuildSetOp(GuildSetOp),
 
     UserList(UserList),
     UserCreate(UserCreate),
@@ -118,6 +580,29 @@ pub enum ClientRequest {
     UserGetName(UserGetName),
     UserSetName(UserSetName),
     UserSetPass(UserSetPass),
+    UserFindByName(UserFindByName),
+
+    ProfileGet(ProfileGet),
+    ProfileUpdate(ProfileUpdate),
+    PresenceSet(PresenceSet),
+    PresenceGet(PresenceGet),
+
+    TypingStart(TypingStart),
+
+    LockoutList(LockoutList),
+
+    InviteCreate(InviteCreate),
+    InviteList(InviteList),
+    InviteRevoke(InviteRevoke),
+    InviteUse(InviteUse),
+
+    BanCreate(BanCreate),
+    BanRemove(BanRemove),
+    BanList(BanList),
+    UserKick(UserKick),
+    UserTimeout(UserTimeout),
+
+    AuditLogList(AuditLogList),
 }
 
 impl ClientRequest {
@@ -125,72 +610,447 @@ impl ClientRequest {
         Self::Ping(Ping { content })
     }
 
-    pub fn new_authenticate(id: u64, pass: String) -> Self {
-        Self::Authenticate(Authenticate { id, pass })
+    pub fn new_authenticate(id: u64, pass: String) -> Self {
+        let login = Login::Id(id);
+        Self::Authenticate(Authenticate { login, pass })
+    }
+
+    pub fn new_authenticate_by_name(name: String, pass: String) -> Self {
+        let login = Login::Name(name);
+        Self::Authenticate(Authenticate { login, pass })
+    }
+
+    pub fn new_channel_list(guild_id: Option<u64>) -> Self {
+        Self::ChannelList(ChannelList { guild_id })
+    }
+
+    pub fn new_channel_create(name: String, guild_id: Option<u64>) -> Self {
+        Self::ChannelCreate(ChannelCreate { name, guild_id })
+    }
+
+    pub fn new_channel_delete(channel_id: u64) -> Self {
+        Self::ChannelDelete(ChannelDelete { id: channel_id })
+    }
+
+    pub fn new_channel_restore(id: u64) -> Self {
+        Self::ChannelRestore(ChannelRestore { id })
+    }
+
+    pub fn new_category_create(name: String, guild_id: Option<u64>) -> Self {
+        Self::CategoryCreate(CategoryCreate { name, guild_id })
+    }
+
+    pub fn new_channel_get_parent(id: u64) -> Self {
+        Self::ChannelGetParent(ChannelGetParent { id })
+    }
+
+    pub fn new_channel_set_parent(id: u64, parent: Option<u64>) -> Self {
+        Self::ChannelSetParent(ChannelSetParent { id, parent })
+    }
+
+    pub fn new_channel_reorder(ids: Vec<u64>) -> Self {
+        Self::ChannelReorder(ChannelReorder { ids })
+    }
+
+    pub fn new_channel_get_name(channel_id: u64) -> Self {
+        Self::ChannelGetName(ChannelGetName { id: channel_id })
+    }
+
+    pub fn new_channel_set_name(channel_id: u64, name: String) -> Self {
+        Self::ChannelSetName(ChannelSetName {
+            id: channel_id,
+            name,
+        })
+    }
+
+    pub fn new_channel_get_info(id: u64) -> Self {
+        Self::ChannelGetInfo(ChannelGetInfo { id })
+    }
+
+    pub fn new_channel_set_topic(id: u64, topic: String) -> Self {
+        Self::ChannelSetTopic(ChannelSetTopic { id, topic })
+    }
+
+    pub fn new_channel_set_description(id: u64, description: String) -> Self {
+        Self::ChannelSetDescription(ChannelSetDescription { id, description })
+    }
+
+    pub fn new_channel_set_kind(id: u64, kind: String) -> Self {
+        Self::ChannelSetKind(ChannelSetKind { id, kind })
+    }
+
+    pub fn new_channel_get_slow_mode(channel_id: u64) -> Self {
+        Self::ChannelGetSlowMode(ChannelGetSlowMode { id: channel_id })
+    }
+
+    pub fn new_channel_set_slow_mode(channel_id: u64, seconds: u64) -> Self {
+        Self::ChannelSetSlowMode(ChannelSetSlowMode {
+            id: channel_id,
+            seconds,
+        })
+    }
+
+    pub fn new_channel_set_private(id: u64, private: bool) -> Self {
+        Self::ChannelSetPrivate(ChannelSetPrivate { id, private })
+    }
+
+    pub fn new_channel_member_add(id: u64, user_id: u64) -> Self {
+        Self::ChannelMemberAdd(ChannelMemberAdd { id, user_id })
+    }
+
+    pub fn new_channel_member_remove(id: u64, user_id: u64) -> Self {
+        Self::ChannelMemberRemove(ChannelMemberRemove { id, user_id })
+    }
+
+    pub fn new_channel_member_list(id: u64) -> Self {
+        Self::ChannelMemberList(ChannelMemberList { id })
+    }
+
+    pub fn new_channel_list_pins(id: u64) -> Self {
+        Self::ChannelListPins(ChannelListPins { id })
+    }
+
+    pub fn new_channel_set_pin_limit(id: u64, limit: Option<u32>) -> Self {
+        Self::ChannelSetPinLimit(ChannelSetPinLimit { id, limit })
+    }
+
+    pub fn new_message_list(channel_id: u64) -> Self {
+        Self::MessageList(MessageList { channel_id })
+    }
+    pub fn new_message_create(channel_id: u64, content: String) -> Self {
+        Self::new_message_create_linked(channel_id, content, None, None)
+    }
+    pub fn new_message_create_linked(
+        channel_id: u64,
+        content: String,
+        reply_to: Option<u64>,
+        thread_id: Option<u64>,
+    ) -> Self {
+        Self::MessageCreate(MessageCreate {
+            channel_id,
+            content,
+            reply_to,
+            thread_id,
+        })
+    }
+    pub fn new_message_delete(channel_id: u64, id: u64) -> Self {
+        Self::MessageDelete(MessageDelete { channel_id, id })
+    }
+    pub fn new_message_restore(channel_id: u64, id: u64) -> Self {
+        Self::MessageRestore(MessageRestore { channel_id, id })
+    }
+    pub fn new_message_pin(channel_id: u64, id: u64) -> Self {
+        Self::MessagePin(MessagePin { channel_id, id })
+    }
+    pub fn new_message_unpin(channel_id: u64, id: u64) -> Self {
+        Self::MessageUnpin(MessageUnpin { channel_id, id })
+    }
+    pub fn new_message_get_content(channel_id: u64, id: u64) -> Self {
+        Self::MessageGetContent(MessageGetContent { channel_id, id })
+    }
+    pub fn new_message_set_content(channel_id: u64, id: u64, content: String) -> Self {
+        Self::MessageSetContent(MessageSetContent {
+            channel_id,
+            id,
+            content,
+        })
+    }
+    pub fn new_message_get_history(channel_id: u64, id: u64) -> Self {
+        Self::MessageGetHistory(MessageGetHistory { channel_id, id })
+    }
+    pub fn new_message_search(
+        query: String,
+        channel_id: Option<u64>,
+        author_id: Option<u64>,
+        after: Option<i64>,
+        before: Option<i64>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+    ) -> Self {
+        Self::MessageSearch(MessageSearch {
+            query,
+            channel_id,
+            author_id,
+            after,
+            before,
+            offset,
+            limit,
+        })
+    }
+    pub fn new_message_reindex() -> Self {
+        Self::MessageReindex(MessageReindex {})
+    }
+    pub fn new_reaction_add(channel_id: u64, id: u64, emoji: String) -> Self {
+        Self::ReactionAdd(ReactionAdd {
+            channel_id,
+            id,
+            emoji,
+        })
+    }
+    pub fn new_reaction_remove(channel_id: u64, id: u64, emoji: String) -> Self {
+        Self::ReactionRemove(ReactionRemove {
+            channel_id,
+            id,
+            emoji,
+        })
+    }
+    pub fn new_reaction_list_users(channel_id: u64, id: u64, emoji: String) -> Self {
+        Self::ReactionListUsers(ReactionListUsers {
+            channel_id,
+            id,
+            emoji,
+        })
+    }
+    pub fn new_thread_message_list(
+        channel_id: u64,
+        thread_id: u64,
+        before: Option<u64>,
+        limit: Option<u32>,
+    ) -> Self {
+        Self::ThreadMessageList(ThreadMessageList {
+            channel_id,
+            thread_id,
+            before,
+            limit,
+        })
+    }
+    pub fn new_thread_subscribe(channel_id: u64, thread_id: u64) -> Self {
+        Self::ThreadSubscribe(ThreadSubscribe {
+            channel_id,
+            thread_id,
+        })
+    }
+    pub fn new_thread_unsubscribe(channel_id: u64, thread_id: u64) -> Self {
+        Self::ThreadUnsubscribe(ThreadUnsubscribe {
+            channel_id,
+            thread_id,
+        })
+    }
+    pub fn new_direct_open(users: Vec<u64>) -> Self {
+        Self::DirectOpen(DirectOpen { users })
+    }
+    pub fn new_direct_list() -> Self {
+        Self::DirectList(DirectList {})
+    }
+    pub fn new_mentions_list(after: Option<u64>, limit: Option<u32>) -> Self {
+        Self::MentionsList(MentionsList { after, limit })
+    }
+    pub fn new_channel_ack(channel_id: u64, id: u64) -> Self {
+        Self::ChannelAck(ChannelAck { channel_id, id })
+    }
+    pub fn new_read_state_list() -> Self {
+        Self::ReadStateList(ReadStateList {})
+    }
+    pub fn new_role_create(name: String, permissions: u64, guild_id: Option<u64>) -> Self {
+        Self::RoleCreate(RoleCreate {
+            name,
+            permissions,
+            guild_id,
+        })
+    }
+    pub fn new_role_update(id: u64, name: String, permissions: u64) -> Self {
+        Self::RoleUpdate(RoleUpdate {
+            id,
+            name,
+            permissions,
+        })
+    }
+    pub fn new_role_delete(id: u64) -> Self {
+        Self::RoleDelete(RoleDelete { id })
+    }
+    pub fn new_role_list(guild_id: Option<u64>) -> Self {
+        Self::RoleList(RoleList { guild_id })
+    }
+    pub fn new_role_assign(role_id: u64, user_id: u64) -> Self {
+        Self::RoleAssign(RoleAssign { role_id, user_id })
+    }
+    pub fn new_role_unassign(role_id: u64, user_id: u64) -> Self {
+        Self::RoleUnassign(RoleUnassign { role_id, user_id })
+    }
+    pub fn new_user_list_roles(id: u64) -> Self {
+        Self::UserListRoles(UserListRoles { id })
+    }
+    pub fn new_channel_set_role_overwrite(
+        channel_id: u64,
+        role_id: u64,
+        allow: u64,
+        deny: u64,
+    ) -> Self {
+        Self::ChannelSetRoleOverwrite(ChannelSetRoleOverwrite {
+            channel_id,
+            role_id,
+            allow,
+            deny,
+        })
+    }
+    pub fn new_channel_set_user_overwrite(
+        channel_id: u64,
+        user_id: u64,
+        allow: u64,
+        deny: u64,
+    ) -> Self {
+        Self::ChannelSetUserOverwrite(ChannelSetUserOverwrite {
+            channel_id,
+            user_id,
+            allow,
+            deny,
+        })
+    }
+    pub fn new_channel_list_overwrites(channel_id: u64) -> Self {
+        Self::ChannelListOverwrites(ChannelListOverwrites { channel_id })
+    }
+    pub fn new_permissions_get(channel_id: Option<u64>) -> Self {
+        Self::PermissionsGet(PermissionsGet { channel_id })
+    }
+    pub fn new_guild_create(name: String) -> Self {
+        Self::GuildCreate(GuildCreate { name })
+    }
+    pub fn new_guild_delete(id: u64) -> Self {
+        Self::GuildDelete(GuildDelete { id })
+    }
+    pub fn new_guild_list() -> Self {
+        Self::GuildList(GuildList {})
+    }
+    pub fn new_guild_member_add(id: u64, user_id: u64) -> Self {
+        Self::GuildMemberAdd(GuildMemberAdd { id, user_id })
+    }
+    pub fn new_guild_member_remove(id: u64, user_id: u64) -> Self {
+        Self::GuildMemberRemove(GuildMemberRemove { id, user_id })
+    }
+    pub fn new_guild_member_list(id: u64) -> Self {
+        Self::GuildMemberList(GuildMemberList { id })
+    }
+    pub fn new_guild_set_op(id: u64, user_id: u64, op: bool) -> Self {
+        Self::GuildSetOp(GuildSetOp { id, user_id, op })
+    }
+    pub fn new_user_list() -> Self {
+        Self::UserList(UserList {})
+    }
+    pub fn new_user_create(name: String, pass: String, invite: Option<String>) -> Self {
+        Self::UserCreate(UserCreate { name, pass, invite })
+    }
+    pub fn new_user_delete(id: u64) -> Self {
+        Self::UserDelete(UserDelete { id })
+    }
+    pub fn new_user_get_name(id: u64) -> Self {
+        Self::UserGetName(UserGetName { id })
+    }
+    pub fn new_user_set_name(id: u64, name: String) -> Self {
+        Self::UserSetName(UserSetName { id, name })
+    }
+    pub fn new_user_set_pass(id: u64, pass: String, current: Option<String>) -> Self {
+        Self::UserSetPass(UserSetPass { id, pass, current })
+    }
+
+    pub fn new_user_find_by_name(name: String) -> Self {
+        Self::UserFindByName(UserFindByName { name })
     }
 
-    pub fn new_channel_list() -> Self {
-        Self::ChannelList(ChannelList {})
+    pub fn new_profile_get(id: u64) -> Self {
+        Self::ProfileGet(ProfileGet { id })
     }
 
-    pub fn new_channel_create(name: String) -> Self {
-        Self::ChannelCreate(ChannelCreate { name })
+    pub fn new_profile_update(
+        id: u64,
+        display_name: Option<String>,
+        avatar: Option<String>,
+        bio: Option<String>,
+        custom_status: Option<String>,
+    ) -> Self {
+        Self::ProfileUpdate(ProfileUpdate {
+            id,
+            display_name,
+            avatar,
+            bio,
+            custom_status,
+        })
     }
 
-    pub fn new_channel_delete(channel_id: u64) -> Self {
-        Self::ChannelDelete(ChannelDelete { id: channel_id })
+    pub fn new_presence_set(status: String) -> Self {
+        Self::PresenceSet(PresenceSet { status })
     }
 
-    pub fn new_channel_get_name(channel_id: u64) -> Self {
-        Self::ChannelGetName(ChannelGetName { id: channel_id })
+    pub fn new_presence_get(ids: Vec<u64>) -> Self {
+        Self::PresenceGet(PresenceGet { ids })
     }
 
-    pub fn new_channel_set_name(channel_id: u64, name: String) -> Self {
-        Self::ChannelSetName(ChannelSetName {
-            id: channel_id,
-            name,
-        })
+    pub fn new_typing_start(channel_id: u64) -> Self {
+        Self::TypingStart(TypingStart { channel_id })
     }
 
-    pub fn new_message_list(channel_id: u64) -> Self {
-        Self::MessageList(MessageList { channel_id })
+    pub fn new_lockout_list() -> Self {
+        Self::LockoutList(LockoutList {})
     }
-    pub fn new_message_create(channel_id: u64, content: String) -> Self {
-        Self::MessageCreate(MessageCreate {
-            channel_id,
-            content,
+
+    /// `expires_in` is in seconds.
+    pub fn new_invite_create(
+        max_uses: Option<u32>,
+        expires_in: Option<u64>,
+        guild_id: Option<u64>,
+    ) -> Self {
+        Self::InviteCreate(InviteCreate {
+            max_uses,
+            expires_in,
+            guild_id,
         })
     }
-    pub fn new_message_delete(channel_id: u64, id: u64) -> Self {
-        Self::MessageDelete(MessageDelete { channel_id, id })
+
+    pub fn new_invite_list(guild_id: Option<u64>) -> Self {
+        Self::InviteList(InviteList { guild_id })
     }
-    pub fn new_message_get_content(channel_id: u64, id: u64) -> Self {
-        Self::MessageGetContent(MessageGetContent { channel_id, id })
+
+    pub fn new_invite_revoke(code: String) -> Self {
+        Self::InviteRevoke(InviteRevoke { code })
     }
-    pub fn new_message_set_content(channel_id: u64, id: u64, content: String) -> Self {
-        Self::MessageSetContent(MessageSetContent {
-            channel_id,
+
+    pub fn new_invite_use(code: String) -> Self {
+        Self::InviteUse(InviteUse { code })
+    }
+
+    pub fn new_ban_create(
+        id: u64,
+        reason: Option<String>,
+        duration: Option<u64>,
+        purge_secs: Option<u64>,
+    ) -> Self {
+        Self::BanCreate(BanCreate {
             id,
-            content,
+            reason,
+            duration,
+            purge_secs,
         })
     }
-    pub fn new_user_list() -> Self {
-        Self::UserList(UserList {})
-    }
-    pub fn new_user_create(name: String, pass: String) -> Self {
-        Self::UserCreate(UserCreate { name, pass })
+
+    pub fn new_ban_remove(id: u64) -> Self {
+        Self::BanRemove(BanRemove { id })
     }
-    pub fn new_user_delete(id: u64) -> Self {
-        Self::UserDelete(UserDelete { id })
+
+    pub fn new_ban_list() -> Self {
+        Self::BanList(BanList {})
     }
-    pub fn new_user_get_name(id: u64) -> Self {
-        Self::UserGetName(UserGetName { id })
+
+    pub fn new_user_kick(id: u64, reason: Option<String>) -> Self {
+        Self::UserKick(UserKick { id, reason })
     }
-    pub fn new_user_set_name(id: u64, name: String) -> Self {
-        Self::UserSetName(UserSetName { id, name })
+
+    pub fn new_user_timeout(id: u64, duration: u64) -> Self {
+        Self::UserTimeout(UserTimeout { id, duration })
     }
-    pub fn new_user_set_pass(id: u64, pass: String) -> Self {
-        Self::UserSetPass(UserSetPass { id, pass })
+
+    pub fn new_audit_log_list(
+        before: Option<u64>,
+        limit: Option<u32>,
+        actor_id: Option<u64>,
+        action: Option<String>,
+        target_id: Option<u64>,
+    ) -> Self {
+        Self::AuditLogList(AuditLogList {
+            before,
+            limit,
+            actor_id,
+            action,
+            target_id,
+        })
     }
 
     pub fn try_parse(line: &str) -> Option<Self> {
@@ -198,30 +1058,185 @@ impl ClientRequest {
         let command: repr::Command = serde_json::from_str(line).ok()?;
         let mapped = match command {
             ping { content } => Self::new_ping(content),
-            authenticate { id, pass } => Self::new_authenticate(id, pass),
-            channel_list {} => Self::new_channel_list(),
-            channel_create { name } => Self::new_channel_create(name),
+            authenticate {
+                id: Some(id),
+                name: None,
+                pass,
+            } => Self::new_authenticate(id, pass),
+            authenticate {
+                id: None,
+                name: Some(name),
+                pass,
+            } => Self::new_authenticate_by_name(name, pass),
+            authenticate { .. } => return None,
+            channel_list { guild_id } => Self::new_channel_list(guild_id),
+            channel_create { name, guild_id } => Self::new_channel_create(name, guild_id),
             channel_delete { id } => Self::new_channel_delete(id),
+            channel_restore { id } => Self::new_channel_restore(id),
+            category_create { name, guild_id } => Self::new_category_create(name, guild_id),
+            channel_get_parent { id } => Self::new_channel_get_parent(id),
+            channel_set_parent { id, parent } => Self::new_channel_set_parent(id, parent),
+            channel_reorder { ids } => Self::new_channel_reorder(ids),
             channel_get_name { id } => Self::new_channel_get_name(id),
             channel_set_name { id, name } => Self::new_channel_set_name(id, name),
+            channel_get_info { id } => Self::new_channel_get_info(id),
+            channel_set_topic { id, topic } => Self::new_channel_set_topic(id, topic),
+            channel_set_description { id, description } => {
+                Self::new_channel_set_description(id, description)
+            }
+            channel_set_kind { id, kind } => Self::new_channel_set_kind(id, kind),
+            channel_get_slow_mode { id } => Self::new_channel_get_slow_mode(id),
+            channel_set_slow_mode { id, seconds } => Self::new_channel_set_slow_mode(id, seconds),
+            channel_set_private { id, private } => Self::new_channel_set_private(id, private),
+            channel_member_add { id, user_id } => Self::new_channel_member_add(id, user_id),
+            channel_member_remove { id, user_id } => Self::new_channel_member_remove(id, user_id),
+            channel_member_list { id } => Self::new_channel_member_list(id),
+            channel_list_pins { id } => Self::new_channel_list_pins(id),
+            channel_set_pin_limit { id, limit } => Self::new_channel_set_pin_limit(id, limit),
             message_list { channel_id } => Self::new_message_list(channel_id),
             message_create {
                 channel_id,
                 content,
-            } => Self::new_message_create(channel_id, content),
+                reply_to,
+                thread_id,
+            } => Self::new_message_create_linked(channel_id, content, reply_to, thread_id),
             message_delete { id, channel_id } => Self::new_message_delete(channel_id, id),
+            message_restore { channel_id, id } => Self::new_message_restore(channel_id, id),
+            message_pin { channel_id, id } => Self::new_message_pin(channel_id, id),
+            message_unpin { channel_id, id } => Self::new_message_unpin(channel_id, id),
             message_get_content { id, channel_id } => Self::new_message_get_content(channel_id, id),
             message_set_content {
                 id,
                 channel_id,
                 content,
             } => Self::new_message_set_content(channel_id, id, content),
+            message_get_history { id, channel_id } => Self::new_message_get_history(channel_id, id),
+            message_search {
+                query,
+                channel_id,
+                author_id,
+                after,
+                before,
+                offset,
+                limit,
+            } => {
+                Self::new_message_search(query, channel_id, author_id, after, before, offset, limit)
+            }
+            message_reindex {} => Self::new_message_reindex(),
+            reaction_add {
+                channel_id,
+                id,
+                emoji,
+            } => Self::new_reaction_add(channel_id, id, emoji),
+            reaction_remove {
+                channel_id,
+                id,
+                emoji,
+            } => Self::new_reaction_remove(channel_id, id, emoji),
+            reaction_list_users {
+                channel_id,
+                id,
+                emoji,
+            } => Self::new_reaction_list_users(channel_id, id, emoji),
+            thread_message_list {
+                channel_id,
+                thread_id,
+                before,
+                limit,
+            } => Self::new_thread_message_list(channel_id, thread_id, before, limit),
+            thread_subscribe {
+                channel_id,
+                thread_id,
+            } => Self::new_thread_subscribe(channel_id, thread_id),
+            thread_unsubscribe {
+                channel_id,
+                thread_id,
+            } => Self::new_thread_unsubscribe(channel_id, thread_id),
+            direct_open { users } => Self::new_direct_open(users),
+            direct_list {} => Self::new_direct_list(),
+            mentions_list { after, limit } => Self::new_mentions_list(after, limit),
+            channel_ack { channel_id, id } => Self::new_channel_ack(channel_id, id),
+            read_state_list {} => Self::new_read_state_list(),
+            role_create {
+                name,
+                permissions,
+                guild_id,
+            } => Self::new_role_create(name, permissions, guild_id),
+            role_update {
+                id,
+                name,
+                permissions,
+            } => Self::new_role_update(id, name, permissions),
+            role_delete { id } => Self::new_role_delete(id),
+            role_list { guild_id } => Self::new_role_list(guild_id),
+            role_assign { role_id, user_id } => Self::new_role_assign(role_id, user_id),
+            role_unassign { role_id, user_id } => Self::new_role_unassign(role_id, user_id),
+            user_list_roles { id } => Self::new_user_list_roles(id),
+            channel_set_role_overwrite {
+                channel_id,
+                role_id,
+                allow,
+                deny,
+            } => Self::new_channel_set_role_overwrite(channel_id, role_id, allow, deny),
+            channel_set_user_overwrite {
+                channel_id,
+                user_id,
+                allow,
+                deny,
+            } => Self::new_channel_set_user_overwrite(channel_id, user_id, allow, deny),
+            channel_list_overwrites { channel_id } => Self::new_channel_list_overwrites(channel_id),
+            permissions_get { channel_id } => Self::new_permissions_get(channel_id),
+            guild_create { name } => Self::new_guild_create(name),
+            guild_delete { id } => Self::new_guild_delete(id),
+            guild_list {} => Self::new_guild_list(),
+            guild_member_add { id, user_id } => Self::new_guild_member_add(id, user_id),
+            guild_member_remove { id, user_id } => Self::new_guild_member_remove(id, user_id),
+            guild_member_list { id } => Self::new_guild_member_list(id),
+            guild_set_op { id, user_id, op } => Self::new_guild_set_op(id, user_id, op),
             user_list {} => Self::new_user_list(),
-            user_create { name, pass } => Self::new_user_create(name, pass),
+            user_create { name, pass, invite } => Self::new_user_create(name, pass, invite),
             user_delete { id } => Self::new_user_delete(id),
             user_get_name { id } => Self::new_user_get_name(id),
             user_set_name { id, name } => Self::new_user_set_name(id, name),
-            user_set_pass { id, pass } => Self::new_user_set_pass(id, pass),
+            user_set_pass { id, pass, current } => Self::new_user_set_pass(id, pass, current),
+            user_find_by_name { name } => Self::new_user_find_by_name(name),
+            profile_get { id } => Self::new_profile_get(id),
+            profile_update {
+                id,
+                display_name,
+                avatar,
+                bio,
+                custom_status,
+            } => Self::new_profile_update(id, display_name, avatar, bio, custom_status),
+            presence_set { status } => Self::new_presence_set(status),
+            presence_get { ids } => Self::new_presence_get(ids),
+            typing_start { channel_id } => Self::new_typing_start(channel_id),
+            lockout_list {} => Self::new_lockout_list(),
+            invite_create {
+                max_uses,
+                expires_in,
+                guild_id,
+            } => Self::new_invite_create(max_uses, expires_in, guild_id),
+            invite_list { guild_id } => Self::new_invite_list(guild_id),
+            invite_revoke { code } => Self::new_invite_revoke(code),
+            invite_use { code } => Self::new_invite_use(code),
+            ban_create {
+                id,
+                reason,
+                duration,
+                purge_secs,
+            } => Self::new_ban_create(id, reason, duration, purge_secs),
+            ban_remove { id } => Self::new_ban_remove(id),
+            ban_list {} => Self::new_ban_list(),
+            user_kick { id, reason } => Self::new_user_kick(id, reason),
+            user_timeout { id, duration } => Self::new_user_timeout(id, duration),
+            audit_log_list {
+                before,
+                limit,
+                actor_id,
+                action,
+                target_id,
+            } => Self::new_audit_log_list(before, limit, actor_id, action, target_id),
         };
         Some(mapped)
     }
@@ -230,12 +1245,29 @@ impl ClientRequest {
         use repr::Command::*;
         let mapped = match self {
             Self::Ping(Ping { content }) => ping { content },
-            Self::Authenticate(Authenticate { id, pass }) => authenticate { id, pass },
-            Self::ChannelList(ChannelList {}) => repr::Command::channel_list {},
-            Self::ChannelCreate(ChannelCreate { name }) => channel_create { name },
+            Self::Authenticate(Authenticate { login, pass }) => {
+                let (id, name) = match login {
+                    Login::Id(id) => (Some(id), None),
+                    Login::Name(name) => (None, Some(name)),
+                };
+                authenticate { id, name, pass }
+            }
+            Self::ChannelList(ChannelList { guild_id }) => channel_list { guild_id },
+            Self::ChannelCreate(ChannelCreate { name, guild_id }) => {
+                channel_create { name, guild_id }
+            }
             Self::ChannelDelete(ChannelDelete { id: channel_id }) => {
                 channel_delete { id: channel_id }
             }
+            Self::ChannelRestore(ChannelRestore { id }) => channel_restore { id },
+            Self::CategoryCreate(CategoryCreate { name, guild_id }) => {
+                category_create { name, guild_id }
+            }
+            Self::ChannelGetParent(ChannelGetParent { id }) => channel_get_parent { id },
+            Self::ChannelSetParent(ChannelSetParent { id, parent }) => {
+                channel_set_parent { id, parent }
+            }
+            Self::ChannelReorder(ChannelReorder { ids }) => channel_reorder { ids },
             Self::ChannelGetName(ChannelGetName { id: channel_id }) => {
                 channel_get_name { id: channel_id }
             }
@@ -246,17 +1278,50 @@ impl ClientRequest {
                 id: channel_id,
                 name,
             },
+            Self::ChannelGetInfo(ChannelGetInfo { id }) => channel_get_info { id },
+            Self::ChannelSetTopic(ChannelSetTopic { id, topic }) => channel_set_topic { id, topic },
+            Self::ChannelSetDescription(ChannelSetDescription { id, description }) => {
+                channel_set_description { id, description }
+            }
+            Self::ChannelSetKind(ChannelSetKind { id, kind }) => channel_set_kind { id, kind },
+            Self::ChannelGetSlowMode(ChannelGetSlowMode { id }) => channel_get_slow_mode { id },
+            Self::ChannelSetSlowMode(ChannelSetSlowMode { id, seconds }) => {
+                channel_set_slow_mode { id, seconds }
+            }
+            Self::ChannelSetPrivate(ChannelSetPrivate { id, private }) => {
+                channel_set_private { id, private }
+            }
+            Self::ChannelMemberAdd(ChannelMemberAdd { id, user_id }) => {
+                channel_member_add { id, user_id }
+            }
+            Self::ChannelMemberRemove(ChannelMemberRemove { id, user_id }) => {
+                channel_member_remove { id, user_id }
+            }
+            Self::ChannelMemberList(ChannelMemberList { id }) => channel_member_list { id },
+            Self::ChannelListPins(ChannelListPins { id }) => channel_list_pins { id },
+            Self::ChannelSetPinLimit(ChannelSetPinLimit { id, limit }) => {
+                channel_set_pin_limit { id, limit }
+            }
             Self::MessageList(MessageList { channel_id }) => message_list { channel_id },
             Self::MessageCreate(MessageCreate {
                 channel_id,
                 content,
+                reply_to,
+                thread_id,
             }) => message_create {
                 channel_id,
                 content,
+                reply_to,
+                thread_id,
             },
             Self::MessageDelete(MessageDelete { id, channel_id }) => {
                 message_delete { id, channel_id }
             }
+            Self::MessageRestore(MessageRestore { channel_id, id }) => {
+                message_restore { channel_id, id }
+            }
+            Self::MessagePin(MessagePin { channel_id, id }) => message_pin { channel_id, id },
+            Self::MessageUnpin(MessageUnpin { channel_id, id }) => message_unpin { channel_id, id },
             Self::MessageGetContent(MessageGetContent { id, channel_id }) => {
                 message_get_content { id, channel_id }
             }
@@ -269,12 +1334,215 @@ impl ClientRequest {
                 channel_id,
                 content,
             },
+            Self::MessageGetHistory(MessageGetHistory { id, channel_id }) => {
+                message_get_history { id, channel_id }
+            }
+            Self::MessageSearch(MessageSearch {
+                query,
+                channel_id,
+                author_id,
+                after,
+                before,
+                offset,
+                limit,
+            }) => message_search {
+                query,
+                channel_id,
+                author_id,
+                after,
+                before,
+                offset,
+                limit,
+            },
+            Self::MessageReindex(MessageReindex {}) => message_reindex {},
+            Self::ReactionAdd(ReactionAdd {
+                channel_id,
+                id,
+                emoji,
+            }) => reaction_add {
+                channel_id,
+                id,
+                emoji,
+            },
+            Self::ReactionRemove(ReactionRemove {
+                channel_id,
+                id,
+                emoji,
+            }) => reaction_remove {
+                channel_id,
+                id,
+                emoji,
+            },
+            Self::ReactionListUsers(ReactionListUsers {
+                channel_id,
+                id,
+                emoji,
+            }) => reaction_list_users {
+                channel_id,
+                id,
+                emoji,
+            },
+            Self::ThreadMessageList(ThreadMessageList {
+                channel_id,
+                thread_id,
+                before,
+                limit,
+            }) => thread_message_list {
+                channel_id,
+                thread_id,
+                before,
+                limit,
+            },
+            Self::ThreadSubscribe(ThreadSubscribe {
+                channel_id,
+                thread_id,
+            }) => thread_subscribe {
+                channel_id,
+                thread_id,
+            },
+            Self::ThreadUnsubscribe(ThreadUnsubscribe {
+                channel_id,
+                thread_id,
+            }) => thread_unsubscribe {
+                channel_id,
+                thread_id,
+            },
+            Self::DirectOpen(DirectOpen { users }) => direct_open { users },
+            Self::DirectList(DirectList {}) => direct_list {},
+            Self::MentionsList(MentionsList { after, limit }) => mentions_list { after, limit },
+            Self::ChannelAck(ChannelAck { channel_id, id }) => channel_ack { channel_id, id },
+            Self::ReadStateList(ReadStateList {}) => read_state_list {},
+            Self::RoleCreate(RoleCreate {
+                name,
+                permissions,
+                guild_id,
+            }) => role_create {
+                name,
+                permissions,
+                guild_id,
+            },
+            Self::RoleUpdate(RoleUpdate {
+                id,
+                name,
+                permissions,
+            }) => role_update {
+                id,
+                name,
+                permissions,
+            },
+            Self::RoleDelete(RoleDelete { id }) => role_delete { id },
+            Self::RoleList(RoleList { guild_id }) => role_list { guild_id },
+            Self::RoleAssign(RoleAssign { role_id, user_id }) => role_assign { role_id, user_id },
+            Self::RoleUnassign(RoleUnassign { role_id, user_id }) => {
+                role_unassign { role_id, user_id }
+            }
+            Self::UserListRoles(UserListRoles { id }) => user_list_roles { id },
+            Self::ChannelSetRoleOverwrite(ChannelSetRoleOverwrite {
+                channel_id,
+                role_id,
+                allow,
+                deny,
+            }) => channel_set_role_overwrite {
+                channel_id,
+                role_id,
+                allow,
+                deny,
+            },
+            Self::ChannelSetUserOverwrite(ChannelSetUserOverwrite {
+                channel_id,
+                user_id,
+                allow,
+                deny,
+            }) => channel_set_user_overwrite {
+                channel_id,
+                user_id,
+                allow,
+                deny,
+            },
+            Self::ChannelListOverwrites(ChannelListOverwrites { channel_id }) => {
+                channel_list_overwrites { channel_id }
+            }
+            Self::PermissionsGet(PermissionsGet { channel_id }) => permissions_get { channel_id },
+            Self::GuildCreate(GuildCreate { name }) => guild_create { name },
+            Self::GuildDelete(GuildDelete { id }) => guild_delete { id },
+            Self::GuildList(GuildList {}) => guild_list {},
+            Self::GuildMemberAdd(GuildMemberAdd { id, user_id }) => {
+                guild_member_add { id, user_id }
+            }
+            Self::GuildMemberRemove(GuildMemberRemove { id, user_id }) => {
+                guild_member_remove { id, user_id }
+            }
+            Self::GuildMemberList(GuildMemberList { id }) => guild_member_list { id },
+            Self::GuildSetOp(GuildSetOp { id, user_id, op }) => guild_set_op { id, user_id, op },
             Self::UserList(UserList {}) => user_list {},
-            Self::UserCreate(UserCreate { name, pass }) => user_create { name, pass },
+            Self::UserCreate(UserCreate { name, pass, invite }) => {
+                user_create { name, pass, invite }
+            }
             Self::UserDelete(UserDelete { id }) => user_delete { id },
             Self::UserGetName(UserGetName { id }) => user_get_name { id },
             Self::UserSetName(UserSetName { id, name }) => user_set_name { id, name },
-            Self::UserSetPass(UserSetPass { id, pass }) => user_set_pass { id, pass },
+            Self::UserSetPass(UserSetPass { id, pass, current }) => {
+                user_set_pass { id, pass, current }
+            }
+            Self::UserFindByName(UserFindByName { name }) => user_find_by_name { name },
+            Self::ProfileGet(ProfileGet { id }) => profile_get { id },
+            Self::ProfileUpdate(ProfileUpdate {
+                id,
+                display_name,
+                avatar,
+                bio,
+                custom_status,
+            }) => profile_update {
+                id,
+                display_name,
+                avatar,
+                bio,
+                custom_status,
+            },
+            Self::PresenceSet(PresenceSet { status }) => presence_set { status },
+            Self::PresenceGet(PresenceGet { ids }) => presence_get { ids },
+            Self::TypingStart(TypingStart { channel_id }) => typing_start { channel_id },
+            Self::LockoutList(LockoutList {}) => lockout_list {},
+            Self::InviteCreate(InviteCreate {
+                max_uses,
+                expires_in,
+                guild_id,
+            }) => invite_create {
+                max_uses,
+                expires_in,
+                guild_id,
+            },
+            Self::InviteList(InviteList { guild_id }) => invite_list { guild_id },
+            Self::InviteRevoke(InviteRevoke { code }) => invite_revoke { code },
+            Self::InviteUse(InviteUse { code }) => invite_use { code },
+            Self::BanCreate(BanCreate {
+                id,
+                reason,
+                duration,
+                purge_secs,
+            }) => ban_create {
+                id,
+                reason,
+                duration,
+                purge_secs,
+            },
+            Self::BanRemove(BanRemove { id }) => ban_remove { id },
+            Self::BanList(BanList {}) => ban_list {},
+            Self::UserKick(UserKick { id, reason }) => user_kick { id, reason },
+            Self::UserTimeout(UserTimeout { id, duration }) => user_timeout { id, duration },
+            Self::AuditLogList(AuditLogList {
+                before,
+                limit,
+                actor_id,
+                action,
+                target_id,
+            }) => audit_log_list {
+                before,
+                limit,
+                actor_id,
+                action,
+                target_id,
+            },
         };
         serde_json::to_string(&mapped).unwrap()
     }
@@ -292,16 +1560,43 @@ mod repr {
             content: String,
         },
         authenticate {
-            id: u64,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            id: Option<u64>,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            name: Option<String>,
             pass: String,
         },
-        channel_list {},
+        channel_list {
+            #[serde(default)]
+            guild_id: Option<u64>,
+        },
         channel_create {
             name: String,
+            #[serde(default)]
+            guild_id: Option<u64>,
         },
         channel_delete {
             id: u64,
         },
+        channel_restore {
+            id: u64,
+        },
+        category_create {
+            name: String,
+            #[serde(default)]
+            guild_id: Option<u64>,
+        },
+        channel_get_parent {
+            id: u64,
+        },
+        channel_set_parent {
+            id: u64,
+            #[serde(default)]
+            parent: Option<u64>,
+        },
+        channel_reorder {
+            ids: Vec<u64>,
+        },
         channel_get_name {
             id: u64,
         },
@@ -309,17 +1604,78 @@ mod repr {
             id: u64,
             name: String,
         },
+        channel_get_info {
+            id: u64,
+        },
+        channel_set_topic {
+            id: u64,
+            topic: String,
+        },
+        channel_set_description {
+            id: u64,
+            description: String,
+        },
+        channel_set_kind {
+            id: u64,
+            kind: String,
+        },
+        channel_get_slow_mode {
+            id: u64,
+        },
+        channel_set_slow_mode {
+            id: u64,
+            seconds: u64,
+        },
+        channel_set_private {
+            id: u64,
+            private: bool,
+        },
+        channel_member_add {
+            id: u64,
+            user_id: u64,
+        },
+        channel_member_remove {
+            id: u64,
+            user_id: u64,
+        },
+        channel_member_list {
+            id: u64,
+        },
+        channel_list_pins {
+            id: u64,
+        },
+        channel_set_pin_limit {
+            id: u64,
+            #[serde(default)]
+            limit: Option<u32>,
+        },
         message_list {
             channel_id: u64,
         },
         message_create {
             channel_id: u64,
             content: String,
+            #[serde(default)]
+            reply_to: Option<u64>,
+            #[serde(default)]
+            thread_id: Option<u64>,
         },
         message_delete {
             channel_id: u64,
             id: u64,
         },
+        message_restore {
+            channel_id: u64,
+            id: u64,
+        },
+        message_pin {
+            channel_id: u64,
+            id: u64,
+        },
+        message_unpin {
+            channel_id: u64,
+            id: u64,
+        },
         message_get_content {
             channel_id: u64,
             id: u64,
@@ -329,10 +1685,153 @@ mod repr {
             id: u64,
             content: String,
         },
+        message_get_history {
+            channel_id: u64,
+            id: u64,
+        },
+        message_search {
+            query: String,
+            #[serde(default)]
+            channel_id: Option<u64>,
+            #[serde(default)]
+            author_id: Option<u64>,
+            #[serde(default)]
+            after: Option<i64>,
+            #[serde(default)]
+            before: Option<i64>,
+            #[serde(default)]
+            offset: Option<u32>,
+            #[serde(default)]
+            limit: Option<u32>,
+        },
+        message_reindex {},
+        reaction_add {
+            channel_id: u64,
+            id: u64,
+            emoji: String,
+        },
+        reaction_remove {
+            channel_id: u64,
+            id: u64,
+            emoji: String,
+        },
+        reaction_list_users {
+            channel_id: u64,
+            id: u64,
+            emoji: String,
+        },
+        thread_message_list {
+            channel_id: u64,
+            thread_id: u64,
+            #[serde(default)]
+            before: Option<u64>,
+            #[serde(default)]
+            limit: Option<u32>,
+        },
+        thread_subscribe {
+            channel_id: u64,
+            thread_id: u64,
+        },
+        thread_unsubscribe {
+            channel_id: u64,
+            thread_id: u64,
+        },
+        direct_open {
+            users: Vec<u64>,
+        },
+        direct_list {},
+        mentions_list {
+            #[serde(default)]
+            after: Option<u64>,
+            #[serde(default)]
+            limit: Option<u32>,
+        },
+        channel_ack {
+            channel_id: u64,
+            id: u64,
+        },
+        read_state_list {},
+        role_create {
+            name: String,
+            permissions: u64,
+            #[serde(default)]
+            guild_id: Option<u64>,
+        },
+        role_update {
+            id: u64,
+            name: String,
+            permissions: u64,
+        },
+        role_delete {
+            id: u64,
+        },
+        role_list {
+            #[serde(default)]
+            guild_id: Option<u64>,
+        },
+        role_assign {
+            role_id: u64,
+            user_id: u64,
+        },
+        role_unassign {
+            role_id: u64,
+            user_id: u64,
+        },
+        user_list_roles {
+            id: u64,
+        },
+        channel_set_role_overwrite {
+            channel_id: u64,
+            role_id: u64,
+            #[serde(default)]
+            allow: u64,
+            #[serde(default)]
+            deny: u64,
+        },
+        channel_set_user_overwrite {
+            channel_id: u64,
+            user_id: u64,
+            #[serde(default)]
+            allow: u64,
+            #[serde(default)]
+            deny: u64,
+        },
+        channel_list_overwrites {
+            channel_id: u64,
+        },
+        permissions_get {
+            #[serde(default)]
+            channel_id: Option<u64>,
+        },
+        guild_create {
+            name: String,
+        },
+        guild_delete {
+            id: u64,
+        },
+        guild_list {},
+        guild_member_add {
+            id: u64,
+            user_id: u64,
+        },
+        guild_member_remove {
+            id: u64,
+            user_id: u64,
+        },
+        guild_member_list {
+            id: u64,
+        },
+        guild_set_op {
+            id: u64,
+            user_id: u64,
+            op: bool,
+        },
         user_list {},
         user_create {
             name: String,
             pass: String,
+            #[serde(default)]
+            invite: Option<String>,
         },
         user_delete {
             id: u64,
@@ -347,6 +1846,87 @@ mod repr {
         user_set_pass {
             id: u64,
             pass: String,
+            #[serde(default)]
+            current: Option<String>,
+        },
+        user_find_by_name {
+            name: String,
+        },
+        profile_get {
+            id: u64,
+        },
+        profile_update {
+            id: u64,
+            #[serde(default)]
+            display_name: Option<String>,
+            #[serde(default)]
+            avatar: Option<String>,
+            #[serde(default)]
+            bio: Option<String>,
+            #[serde(default)]
+            custom_status: Option<String>,
+        },
+        presence_set {
+            status: String,
+        },
+        presence_get {
+            ids: Vec<u64>,
+        },
+        typing_start {
+            channel_id: u64,
+        },
+        lockout_list {},
+        invite_create {
+            #[serde(default)]
+            max_uses: Option<u32>,
+            #[serde(default)]
+            expires_in: Option<u64>,
+            #[serde(default)]
+            guild_id: Option<u64>,
+        },
+        invite_list {
+            #[serde(default)]
+            guild_id: Option<u64>,
+        },
+        invite_revoke {
+            code: String,
+        },
+        invite_use {
+            code: String,
+        },
+        ban_create {
+            id: u64,
+            #[serde(default)]
+            reason: Option<String>,
+            #[serde(default)]
+            duration: Option<u64>,
+            #[serde(default)]
+            purge_secs: Option<u64>,
+        },
+        ban_remove {
+            id: u64,
+        },
+        ban_list {},
+        user_kick {
+            id: u64,
+            #[serde(default)]
+            reason: Option<String>,
+        },
+        user_timeout {
+            id: u64,
+            duration: u64,
+        },
+        audit_log_list {
+            #[serde(default)]
+            before: Option<u64>,
+            #[serde(default)]
+            limit: Option<u32>,
+            #[serde(default)]
+            actor_id: Option<u64>,
+            #[serde(default)]
+            action: Option<String>,
+            #[serde(default)]
+            target_id: Option<u64>,
         },
     }
 }
@@ -1,13 +1,56 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug)]
 pub struct Pong {
     pub content: String,
 }
 
+/// a channel was deleted, and can still be restored by an operator.
+#[derive(Debug)]
+pub struct ChannelTombstone {
+    pub id: u64,
+    /// date after which the channel can no longer be restored, in milliseconds since the epoch.
+    pub purge_at: i64,
+}
+#[derive(Debug)]
+pub struct ChannelRestore {
+    pub id: u64,
+}
 #[derive(Debug)]
 pub struct ChannelList {
+    pub guild_id: u64,
+    /// every channel, in tree order.
     pub channels: Vec<u64>,
+    pub tree: Vec<ChannelNode>,
+}
+
+/// a top level channel, with the channels of its category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelNode {
+    pub id: u64,
+    pub children: Vec<u64>,
 }
 
+#[derive(Debug)]
+pub struct CategoryCreate {
+    pub id: u64,
+    pub name: String,
+    pub guild_id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelGetParent {
+    pub id: u64,
+    pub parent: Option<u64>,
+}
+#[derive(Debug)]
+pub struct ChannelSetParent {
+    pub id: u64,
+    pub parent: Option<u64>,
+}
+#[derive(Debug)]
+pub struct ChannelReorder {
+    pub ids: Vec<u64>,
+}
 #[derive(Debug)]
 pub struct ChannelGetName {
     pub id: u64,
@@ -18,6 +61,7 @@ pub struct ChannelGetName {
 pub struct ChannelCreate {
     pub id: u64,
     pub name: String,
+    pub guild_id: u64,
 }
 
 #[derive(Debug)]
@@ -31,6 +75,77 @@ pub struct ChannelSetName {
     pub name: String,
 }
 
+#[derive(Debug)]
+pub struct ChannelGetInfo {
+    pub id: u64,
+    pub name: String,
+    pub topic: String,
+    pub description: String,
+    /// one of `text`, `announcement`, `read_only` or `category`.
+    pub kind: String,
+    /// in milliseconds since the epoch.
+    pub created: i64,
+    pub creator: Option<u64>,
+}
+#[derive(Debug)]
+pub struct ChannelSetTopic {
+    pub id: u64,
+    pub topic: String,
+}
+#[derive(Debug)]
+pub struct ChannelSetDescription {
+    pub id: u64,
+    pub description: String,
+}
+#[derive(Debug)]
+pub struct ChannelSetKind {
+    pub id: u64,
+    pub kind: String,
+}
+#[derive(Debug)]
+pub struct ChannelGetSlowMode {
+    pub id: u64,
+    pub seconds: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct ChannelSetSlowMode {
+    pub id: u64,
+    pub seconds: u64,
+}
+
+#[derive(Debug)]
+pub struct ChannelSetPrivate {
+    pub id: u64,
+    pub private: bool,
+}
+#[derive(Debug)]
+pub struct ChannelMemberAdd {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelMemberRemove {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct ChannelMemberList {
+    pub id: u64,
+    pub members: Vec<u64>,
+}
+#[derive(Debug)]
+pub struct ChannelListPins {
+    pub id: u64,
+    /// in pin order.
+    pub pins: Vec<u64>,
+    pub limit: u32,
+}
+#[derive(Debug)]
+pub struct ChannelSetPinLimit {
+    pub id: u64,
+    pub limit: u32,
+}
 #[derive(Debug)]
 pub struct MessageList {
     pub channel_id: u64,
@@ -41,141 +156,1101 @@ pub struct MessageCreate {
     pub channel_id: u64,
     pub id: u64,
     pub content: String,
+    pub reply_to: Option<u64>,
+    /// parent of the thread the message was posted in.
+    pub thread_id: Option<u64>,
 }
 #[derive(Debug)]
 pub struct MessageDelete {
     pub channel_id: u64,
     pub id: u64,
 }
+/// a message was deleted, and can still be restored by an operator.
+#[derive(Debug)]
+pub struct MessageTombstone {
+    pub channel_id: u64,
+    pub id: u64,
+    /// date after which the message can no longer be restored, in milliseconds since the epoch.
+    pub purge_at: i64,
+}
+#[derive(Debug)]
+pub struct MessageRestore {
+    pub channel_id: u64,
+    pub id: u64,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reaction {
+    pub emoji: String,
+    pub count: u32,
+    /// whether the receiving user is among those who reacted.
+    pub me: bool,
+}
+
+#[derive(Debug)]
+pub struct MessagePin {
+    pub channel_id: u64,
+    pub id: u64,
+    pub pinner_id: u64,
+}
+#[derive(Debug)]
+pub struct MessageUnpin {
+    pub channel_id: u64,
+    pub id: u64,
+}
 #[derive(Debug)]
 pub struct MessageGetContent {
     pub channel_id: u64,
     pub id: u64,
     pub content: Option<String>,
+    /// number of times the message was edited.
+    pub edits: u32,
+    pub reply_to: Option<u64>,
+    pub thread_id: Option<u64>,
+    pub reactions: Vec<Reaction>,
+    /// users addressed by the message.
+    pub mentions: Vec<u64>,
 }
 #[derive(Debug)]
 pub struct MessageSetContent {
     pub channel_id: u64,
     pub id: u64,
     pub content: String,
+    pub edits: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Revision {
+    pub content: String,
+    pub editor_id: Option<u64>,
+    /// in milliseconds since the epoch.
+    pub timestamp: i64,
+}
+
+#[derive(Debug)]
+pub struct MessageGetHistory {
+    pub channel_id: u64,
+    pub id: u64,
+    /// from the original content to the current one.
+    pub revisions: Vec<Revision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub channel_id: u64,
+    pub id: u64,
+    pub author_id: Option<u64>,
+    pub highlight: String,
+}
+
+#[derive(Debug)]
+pub struct MessageSearch {
+    pub query: String,
+    pub total: u32,
+    pub offset: u32,
+    pub results: Vec<SearchResult>,
+}
+#[derive(Debug)]
+pub struct MessageReindex {
+    pub count: u32,
+}
+
+#[derive(Debug)]
+pub struct ReactionAdd {
+    pub channel_id: u64,
+    pub id: u64,
+    pub emoji: String,
+    pub user_id: u64,
+    /// number of users who reacted with this emoji.
+    pub count: u32,
+}
+#[derive(Debug)]
+pub struct ReactionRemove {
+    pub channel_id: u64,
+    pub id: u64,
+    pub emoji: String,
+    pub user_id: u64,
+    /// number of users who reacted with this emoji.
+    pub count: u32,
+}
+#[derive(Debug)]
+pub struct ReactionListUsers {
+    pub channel_id: u64,
+    pub id: u64,
+    pub emoji: String,
+    pub users: Vec<u64>,
+}
+/// the thread of a message got a new message or lost one.
+#[derive(Debug)]
+pub struct ThreadUpdate {
+    pub channel_id: u64,
+    pub id: u64,
+    pub replies: u32,
+    /// in milliseconds since the epoch.
+    pub last_activity: Option<i64>,
+}
+#[derive(Debug)]
+pub struct ThreadMessageList {
+    pub channel_id: u64,
+    pub thread_id: u64,
+    pub replies: u32,
+    pub last_activity: Option<i64>,
+    /// in chronological order.
+    pub messages: Vec<u64>,
+}
+#[derive(Debug)]
+pub struct ThreadSubscribe {
+    pub channel_id: u64,
+    pub thread_id: u64,
+}
+#[derive(Debug)]
+pub struct ThreadUnsubscribe {
+    pub channel_id: u64,
+    pub thread_id: u64,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectChannel {
+    pub id: u64,
+    pub participants: Vec<u64>,
+}
+
+/// sent to the participants of a new direct channel, or to the opener of an existing one.
+#[derive(Debug)]
+pub struct DirectOpen {
+    pub id: u64,
+    pub participants: Vec<u64>,
+}
+#[derive(Debug)]
+pub struct DirectList {
+    pub channels: Vec<DirectChannel>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MentionRef {
+    pub channel_id: u64,
+    pub id: u64,
+}
+
+/// sent to the users addressed by a new or edited message.
+#[derive(Debug)]
+pub struct Mention {
+    pub channel_id: u64,
+    pub id: u64,
+    pub author_id: Option<u64>,
+    pub content: String,
+}
+#[derive(Debug)]
+pub struct MentionsList {
+    pub mentions: Vec<MentionRef>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadState {
+    pub channel_id: u64,
+    pub last_read: Option<u64>,
+    pub unread: u32,
+    /// unread messages mentioning the user.
+    pub mentions: u32,
+}
+
+/// sent to every session of a user acknowledging a channel.
+#[derive(Debug)]
+pub struct ChannelAck {
+    pub channel_id: u64,
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct ReadStateList {
+    pub states: Vec<ReadState>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Role {
+    pub id: u64,
+    pub name: String,
+    pub permissions: u64,
+}
+
+#[derive(Debug)]
+pub struct RoleCreate {
+    pub id: u64,
+    pub name: String,
+    pub permissions: u64,
+    pub guild_id: u64,
+}
+#[derive(Debug)]
+pub struct RoleUpdate {
+    pub id: u64,
+    pub name: String,
+    pub permissions: u64,
+}
+#[derive(Debug)]
+pub struct RoleDelete {
+    pub id: u64,
+}
+#[derive(Debug)]
+pub struct RoleList {
+    pub guild_id: u64,
+    /// the everyone role, whose id is the guild's, comes first.
+    pub roles: Vec<Role>,
+}
+#[derive(Debug)]
+pub struct RoleAssign {
+    pub role_id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct RoleUnassign {
+    pub role_id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct UserListRoles {
+    pub id: u64,
+    pub roles: Vec<u64>,
+}
+#[derive(Debug)]
+pub struct ChannelSetRoleOverwrite {
+    pub channel_id: u64,
+    pub role_id: u64,
+    pub allow: u64,
+    pub deny: u64,
+}
+#[derive(Debug)]
+pub struct ChannelSetUserOverwrite {
+    pub channel_id: u64,
+    pub user_id: u64,
+    pub allow: u64,
+    pub deny: u64,
+}
+/// the overwrite of a role or a user, depending on the list holding it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Overwrite {
+    pub id: u64,
+    pub allow: u64,
+    pub deny: u64,
+}
+#[derive(Debug)]
+pub struct ChannelListOverwrites {
+    pub channel_id: u64,
+    pub roles: Vec<Overwrite>,
+    pub users: Vec<Overwrite>,
+}
+#[derive(Debug)]
+pub struct PermissionsGet {
+    pub channel_id: Option<u64>,
+    pub permissions: u64,
+}
+#[derive(Debug)]
+pub struct GuildCreate {
+    pub id: u64,
+    pub name: String,
+    pub owner: Option<u64>,
+}
+#[derive(Debug)]
+pub struct GuildDelete {
+    pub id: u64,
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuildInfo {
+    pub id: u64,
+    pub name: String,
+    pub owner: Option<u64>,
+}
+#[derive(Debug)]
+pub struct GuildList {
+    /// the default guild, with id 0, comes first.
+    pub guilds: Vec<GuildInfo>,
+}
+#[derive(Debug)]
+pub struct GuildMemberAdd {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct GuildMemberRemove {
+    pub id: u64,
+    pub user_id: u64,
+}
+#[derive(Debug)]
+pub struct GuildMemberList {
+    pub id: u64,
+    pub members: Vec<u64>,
+}
+#[derive(Debug)]
+pub struct GuildSetOp {
+    pub id: u64,
+    pub user_id: u64,
+    pub op: bool,
+}
+
+#[derive(Debug)]
+pub struct UserList {
+    pub users: Vec<u64>,
+}
+
+#[derive(Debug)]
+pub struct UserCreate {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct UserDelete {
+    pub id: u64,
+}
+
+#[derive(Debug)]
+pub struct UserGetName {
+    pub id: u64,
+    pub name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct UserSetName {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct UserFindByName {
+    pub name: String,
+    pub id: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct UserSetPass {
+    pub id: u64,
+}
+
+/// what a user tells about themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    pub bio: Option<String>,
+    pub custom_status: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ProfileGet {
+    pub id: u64,
+    pub profile: Profile,
+}
+
+#[derive(Debug)]
+pub struct ProfileUpdate {
+    pub id: u64,
+    pub profile: Profile,
+}
+
+#[derive(Debug)]
+pub struct PresenceUpdate {
+    pub id: u64,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserPresence {
+    pub id: u64,
+    /// "online", "idle", "dnd" or "offline".
+    pub status: String,
+}
+
+#[derive(Debug)]
+pub struct PresenceGet {
+    pub presences: Vec<UserPresence>,
+}
+
+#[derive(Debug)]
+pub struct TypingStart {
+    pub channel_id: u64,
+    pub user_id: u64,
+}
+
+#[derive(Debug)]
+pub struct TypingStop {
+    pub channel_id: u64,
+    pub user_id: u64,
+}
+
+#[derive(Debug)]
+pub struct RateLimited {
+    pub category: String,
+    pub retry_after: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lockout {
+    pub id: u64,
+    pub user: Option<u64>,
+    pub host: Option<String>,
+    pub failures: u32,
+    pub until: i64,
+}
+
+#[derive(Debug)]
+pub struct LockoutList {
+    pub lockouts: Vec<Lockout>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invite {
+    pub code: String,
+    pub creator: u64,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub expires: Option<i64>,
+    /// `None` for registration invites.
+    #[serde(default)]
+    pub guild_id: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct InviteCreate {
+    pub invite: Invite,
+}
+
+#[derive(Debug)]
+pub struct InviteList {
+    pub invites: Vec<Invite>,
+}
+
+#[derive(Debug)]
+pub struct InviteRevoke {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ban {
+    pub user_id: u64,
+    pub moderator_id: u64,
+    pub reason: String,
+    pub created: i64,
+    /// in milliseconds since the epoch, `None` for permanent bans.
+    pub until: Option<i64>,
+}
+
+#[derive(Debug)]
+pub struct BanCreate {
+    pub ban: Ban,
+}
+
+#[derive(Debug)]
+pub struct BanRemove {
+    pub id: u64,
+}
+
+#[derive(Debug)]
+pub struct BanList {
+    pub bans: Vec<Ban>,
+}
+
+/// sent to a session right before the server closes it.
+#[derive(Debug)]
+pub struct Kicked {
+    pub reason: Option<String>,
+    /// set when the user got banned.
+    pub ban: Option<Ban>,
+}
+
+#[derive(Debug)]
+pub struct UserTimeout {
+    pub id: u64,
+    /// in milliseconds since the epoch, `None` once lifted.
+    pub until: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: u64,
+    /// in milliseconds since the epoch.
+    pub timestamp: i64,
+    /// `None` for anonymous users.
+    pub actor_id: Option<u64>,
+    pub action: String,
+    pub target_id: Option<u64>,
+    pub before: Option<String>,
+    pub after: Option<String>,
 }
 
-#[derive(Debug)]
-pub struct UserList {
-    pub users: Vec<u64>,
-}
+#[derive(Debug)]
+pub struct AuditLogList {
+    pub entries: Vec<AuditEntry>,
+}
+
+#[derive(Debug)]
+pub enum ServerEvent {
+    Pong(Pong),
+    RateLimited(RateLimited),
+
+    ChannelCreate(ChannelCreate),
+    ChannelDelete(ChannelDelete),
+    ChannelTombstone(ChannelTombstone),
+    ChannelRestore(ChannelRestore),
+    ChannelList(ChannelList),
+    CategoryCreate(CategoryCreate),
+    ChannelGetParent(ChannelGetParent),
+    ChannelSetParent(ChannelSetParent),
+    ChannelReorder(ChannelReorder),
+    ChannelGetName(ChannelGetName),
+    ChannelSetName(ChannelSetName),
+    ChannelGetInfo(ChannelGetInfo),
+    ChannelSetTopic(ChannelSetTopic),
+    ChannelSetDescription(ChannelSetDescription),
+    ChannelSetKind(ChannelSetKind),
+    ChannelGetSlowMode(ChannelGetSlowMode),
+    ChannelSetSlowMode(ChannelSetSlowMode),
+
+    ChannelSetPrivate(ChannelSetPrivate),
+    ChannelMemberAdd(ChannelMemberAdd),
+    ChannelMemberRemove(ChannelMemberRemove),
+    ChannelMemberList(ChannelMemberList),
+
+    ChannelListPins(ChannelListPins),
+    ChannelSetPinLimit(ChannelSetPinLimit),
+
+    MessageList(MessageList),
+    MessageCreate(MessageCreate),
+    MessageDelete(MessageDelete),
+    MessageTombstone(MessageTombstone),
+    MessageRestore(MessageRestore),
+    MessagePin(MessagePin),
+    MessageUnpin(MessageUnpin),
+    MessageGetContent(MessageGetContent),
+    MessageSetContent(MessageSetContent),
+    MessageGetHistory(MessageGetHistory),
+    MessageSearch(MessageSearch),
+    MessageReindex(MessageReindex),
+
+    ReactionAdd(ReactionAdd),
+    ReactionRemove(ReactionRemove),
+    ReactionListUsers(ReactionListUsers),
+
+    ThreadUpdate(ThreadUpdate),
+    ThreadMessageList(ThreadMessageList),
+    ThreadSubscribe(ThreadSubscribe),
+    ThreadUnsubscribe(ThreadUnsubscribe),
+
+    DirectOpen(DirectOpen),
+    DirectList(DirectList),
+
+    Mention(Mention),
+    MentionsList(MentionsList),
+
+    ChannelAck(ChannelAck),
+    ReadStateList(ReadStateList),
+
+    RoleCreate(RoleCreate),
+    RoleUpdate(RoleUpdate),
+    RoleDelete(RoleDelete),
+    RoleList(RoleList),
+    RoleAssign(RoleAssign),
+    RoleUnassign(RoleUnassign),
+    UserListRoles(UserListRoles),
+    ChannelSetRoleOverwrite(ChannelSetRoleOverwrite),
+    ChannelSetUserOverwrite(ChannelSetUserOverwrite),
+    ChannelListOverwrites(ChannelListOverwrites),
+    PermissionsGet(PermissionsGet),
+
+    GuildCreate(GuildCreate),
+    GuildDelete(GuildDelete),
+    GuildList(GuildList),
+    GuildMemberAdd(GuildMemberAdd),
+    GuildMemberRemove(GuildMemberRemove),
+    GuildMemberList(GuildMemberList),
+    GuildSetOp(GuildSetOp),
+
+    UserList(UserList),
+    UserCreate(UserCreate),
+    UserDelete(UserDelete),
+    UserGetName(UserGetName),
+    UserSetName(UserSetName),
+    UserSetPass(UserSetPass),
+    UserFindByName(UserFindByName),
+
+    ProfileGet(ProfileGet),
+    ProfileUpdate(ProfileUpdate),
+    PresenceUpdate(PresenceUpdate),
+    PresenceGet(PresenceGet),
+
+    TypingStart(TypingStart),
+    TypingStop(TypingStop),
+
+    LockoutList(LockoutList),
+
+    InviteCreate(InviteCreate),
+    InviteList(InviteList),
+    InviteRevoke(InviteRevoke),
+
+    BanCreate(BanCreate),
+    BanRemove(BanRemove),
+    BanList(BanList),
+    Kicked(Kicked),
+    UserTimeout(UserTimeout),
+
+    AuditLogList(AuditLogList),
+}
+
+impl ServerEvent {
+    pub fn new_pong(content: String) -> Self {
+        Self::Pong(Pong { content })
+    }
+
+    pub fn new_rate_limited(category: String, retry_after: u64) -> Self {
+        Self::RateLimited(RateLimited {
+            category,
+            retry_after,
+        })
+    }
+
+    pub fn new_channel_tombstone(id: u64, purge_at: i64) -> Self {
+        Self::ChannelTombstone(ChannelTombstone { id, purge_at })
+    }
+
+    pub fn new_channel_restore(id: u64) -> Self {
+        Self::ChannelRestore(ChannelRestore { id })
+    }
+
+    pub fn new_channel_list(guild_id: u64, channels: Vec<u64>, tree: Vec<ChannelNode>) -> Self {
+        Self::ChannelList(ChannelList {
+            guild_id,
+            channels,
+            tree,
+        })
+    }
+
+    pub fn new_category_create(id: u64, name: String, guild_id: u64) -> Self {
+        Self::CategoryCreate(CategoryCreate { id, name, guild_id })
+    }
+
+    pub fn new_channel_get_parent(id: u64, parent: Option<u64>) -> Self {
+        Self::ChannelGetParent(ChannelGetParent { id, parent })
+    }
+
+    pub fn new_channel_set_parent(id: u64, parent: Option<u64>) -> Self {
+        Self::ChannelSetParent(ChannelSetParent { id, parent })
+    }
+
+    pub fn new_channel_reorder(ids: Vec<u64>) -> Self {
+        Self::ChannelReorder(ChannelReorder { ids })
+    }
+
+    pub fn new_channel_get_name(id: u64, name: Option<String>) -> Self {
+        Self::ChannelGetName(ChannelGetName { name, id })
+    }
+
+    pub fn new_channel_create(id: u64, name: String, guild_id: u64) -> Self {
+        Self::ChannelCreate(ChannelCreate { id, name, guild_id })
+    }
+
+    pub fn new_channel_delete(id: u64) -> Self {
+        Self::ChannelDelete(ChannelDelete { id })
+    }
+
+    pub fn new_channel_set_name(id: u64, name: String) -> Self {
+        Self::ChannelSetName(ChannelSetName { id, name })
+    }
+
+    pub fn new_channel_get_info(
+        id: u64,
+        name: String,
+        topic: String,
+        description: String,
+        kind: String,
+        created: i64,
+        creator: Option<u64>,
+    ) -> Self {
+        Self::ChannelGetInfo(ChannelGetInfo {
+            id,
+            name,
+            topic,
+            description,
+            kind,
+            created,
+            creator,
+        })
+    }
+
+    pub fn new_channel_set_topic(id: u64, topic: String) -> Self {
+        Self::ChannelSetTopic(ChannelSetTopic { id, topic })
+    }
+
+    pub fn new_channel_set_description(id: u64, description: String) -> Self {
+        Self::ChannelSetDescription(ChannelSetDescription { id, description })
+    }
+
+    pub fn new_channel_set_kind(id: u64, kind: String) -> Self {
+        Self::ChannelSetKind(ChannelSetKind { id, kind })
+    }
+
+    pub fn new_channel_get_slow_mode(id: u64, seconds: Option<u64>) -> Self {
+        Self::ChannelGetSlowMode(ChannelGetSlowMode { id, seconds })
+    }
+
+    pub fn new_channel_set_slow_mode(id: u64, seconds: u64) -> Self {
+        Self::ChannelSetSlowMode(ChannelSetSlowMode { id, seconds })
+    }
+
+    pub fn new_channel_set_private(id: u64, private: bool) -> Self {
+        Self::ChannelSetPrivate(ChannelSetPrivate { id, private })
+    }
+
+    pub fn new_channel_member_add(id: u64, user_id: u64) -> Self {
+        Self::ChannelMemberAdd(ChannelMemberAdd { id, user_id })
+    }
+
+    pub fn new_channel_member_remove(id: u64, user_id: u64) -> Self {
+        Self::ChannelMemberRemove(ChannelMemberRemove { id, user_id })
+    }
+
+    pub fn new_channel_member_list(id: u64, members: Vec<u64>) -> Self {
+        Self::ChannelMemberList(ChannelMemberList { id, members })
+    }
+
+    pub fn new_channel_list_pins(id: u64, pins: Vec<u64>, limit: u32) -> Self {
+        Self::ChannelListPins(ChannelListPins { id, pins, limit })
+    }
+
+    pub fn new_channel_set_pin_limit(id: u64, limit: u32) -> Self {
+        Self::ChannelSetPinLimit(ChannelSetPinLimit { id, limit })
+    }
+
+    pub fn new_message_list(channel_id: u64, messages: Vec<u64>) -> Self {
+        Self::MessageList(MessageList {
+            channel_id,
+            messages,
+        })
+    }
+
+    pub fn new_message_create(
+        channel_id: u64,
+        id: u64,
+        content: String,
+        reply_to: Option<u64>,
+        thread_id: Option<u64>,
+    ) -> Self {
+        Self::MessageCreate(MessageCreate {
+            channel_id,
+            content,
+            id,
+            reply_to,
+            thread_id,
+        })
+    }
+    pub fn new_message_delete(channel_id: u64, id: u64) -> Self {
+        Self::MessageDelete(MessageDelete { channel_id, id })
+    }
+
+    pub fn new_message_tombstone(channel_id: u64, id: u64, purge_at: i64) -> Self {
+        Self::MessageTombstone(MessageTombstone {
+            channel_id,
+            id,
+            purge_at,
+        })
+    }
+
+    pub fn new_message_restore(channel_id: u64, id: u64) -> Self {
+        Self::MessageRestore(MessageRestore { channel_id, id })
+    }
+
+    pub fn new_message_pin(channel_id: u64, id: u64, pinner_id: u64) -> Self {
+        Self::MessagePin(MessagePin {
+            channel_id,
+            id,
+            pinner_id,
+        })
+    }
+
+    pub fn new_message_unpin(channel_id: u64, id: u64) -> Self {
+        Self::MessageUnpin(MessageUnpin { channel_id, id })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_message_get_content(
+        channel_id: u64,
+        id: u64,
+        content: Option<String>,
+        edits: u32,
+        reply_to: Option<u64>,
+        thread_id: Option<u64>,
+        reactions: Vec<Reaction>,
+        mentions: Vec<u64>,
+    ) -> Self {
+        Self::MessageGetContent(MessageGetContent {
+            channel_id,
+            content,
+            id,
+            edits,
+            reply_to,
+            thread_id,
+            reactions,
+            mentions,
+        })
+    }
+
+    pub fn new_message_set_content(channel_id: u64, id: u64, content: String, edits: u32) -> Self {
+        Self::MessageSetContent(MessageSetContent {
+            channel_id,
+            content,
+            id,
+            edits,
+        })
+    }
+
+    pub fn new_message_get_history(channel_id: u64, id: u64, revisions: Vec<Revision>) -> Self {
+        Self::MessageGetHistory(MessageGetHistory {
+            channel_id,
+            id,
+            revisions,
+        })
+    }
+
+    pub fn new_message_search(
+        query: String,
+        total: u32,
+        offset: u32,
+        results: Vec<SearchResult>,
+    ) -> Self {
+        Self::MessageSearch(MessageSearch {
+            query,
+            total,
+            offset,
+            results,
+        })
+    }
+
+    pub fn new_message_reindex(count: u32) -> Self {
+        Self::MessageReindex(MessageReindex { count })
+    }
+
+    pub fn new_reaction_add(
+        channel_id: u64,
+        id: u64,
+        emoji: String,
+        user_id: u64,
+        count: u32,
+    ) -> Self {
+        Self::ReactionAdd(ReactionAdd {
+            channel_id,
+            id,
+            emoji,
+            user_id,
+            count,
+        })
+    }
+
+    pub fn new_reaction_remove(
+        channel_id: u64,
+        id: u64,
+        emoji: String,
+        user_id: u64,
+        count: u32,
+    ) -> Self {
+        Self::ReactionRemove(ReactionRemove {
+            channel_id,
+            id,
+            emoji,
+            user_id,
+            count,
+        })
+    }
+
+    pub fn new_reaction_list_users(
+        channel_id: u64,
+        id: u64,
+        emoji: String,
+        users: Vec<u64>,
+    ) -> Self {
+        Self::ReactionListUsers(ReactionListUsers {
+            channel_id,
+            id,
+            emoji,
+            users,
+        })
+    }
 
-#[derive(Debug)]
-pub struct UserCreate {
-    pub id: u64,
-    pub name: String,
-}
+    pub fn new_thread_update(
+        channel_id: u64,
+        id: u64,
+        replies: u32,
+        last_activity: Option<i64>,
+    ) -> Self {
+        Self::ThreadUpdate(ThreadUpdate {
+            channel_id,
+            id,
+            replies,
+            last_activity,
+        })
+    }
 
-#[derive(Debug)]
-pub struct UserDelete {
-    pub id: u64,
-}
+    pub fn new_thread_message_list(
+        channel_id: u64,
+        thread_id: u64,
+        replies: u32,
+        last_activity: Option<i64>,
+        messages: Vec<u64>,
+    ) -> Self {
+        Self::ThreadMessageList(ThreadMessageList {
+            channel_id,
+            thread_id,
+            replies,
+            last_activity,
+            messages,
+        })
+    }
 
-#[derive(Debug)]
-pub struct UserGetName {
-    pub id: u64,
-    pub name: Option<String>,
-}
+    pub fn new_thread_subscribe(channel_id: u64, thread_id: u64) -> Self {
+        Self::ThreadSubscribe(ThreadSubscribe {
+            channel_id,
+            thread_id,
+        })
+    }
 
-#[derive(Debug)]
-pub struct UserSetName {
-    pub id: u64,
-    pub name: String,
-}
+    pub fn new_thread_unsubscribe(channel_id: u64, thread_id: u64) -> Self {
+        Self::ThreadUnsubscribe(ThreadUnsubscribe {
+            channel_id,
+            thread_id,
+        })
+    }
 
-#[derive(Debug)]
-pub struct UserSetPass {
-    pub id: u64,
-}
+    pub fn new_direct_open(id: u64, participants: Vec<u64>) -> Self {
+        Self::DirectOpen(DirectOpen { id, participants })
+    }
 
-#[derive(Debug)]
-pub enum ServerEvent {
-    Pong(Pong),
+    pub fn new_direct_list(channels: Vec<DirectChannel>) -> Self {
+        Self::DirectList(DirectList { channels })
+    }
 
-    ChannelCreate(ChannelCreate),
-    ChannelDelete(ChannelDelete),
-    ChannelList(ChannelList),
-    ChannelGetName(ChannelGetName),
-    ChannelSetName(ChannelSetName),
+    pub fn new_mention(channel_id: u64, id: u64, author_id: Option<u64>, content: String) -> Self {
+        Self::Mention(Mention {
+            channel_id,
+            id,
+            author_id,
+            content,
+        })
+    }
 
-    MessageList(MessageList),
-    MessageCreate(MessageCreate),
-    MessageDelete(MessageDelete),
-    MessageGetContent(MessageGetContent),
-    MessageSetContent(MessageSetContent),
+    pub fn new_mentions_list(mentions: Vec<MentionRef>) -> Self {
+        Self::MentionsList(MentionsList { mentions })
+    }
 
-    UserList(UserList),
-    UserCreate(UserCreate),
-    UserDelete(UserDelete),
-    UserGetName(UserGetName),
-    UserSetName(UserSetName),
-    UserSetPass(UserSetPass),
-}
+    pub fn new_channel_ack(channel_id: u64, id: u64) -> Self {
+        Self::ChannelAck(ChannelAck { channel_id, id })
+    }
 
-impl ServerEvent {
-    pub fn new_pong(content: String) -> Self {
-        Self::Pong(Pong { content })
+    pub fn new_read_state_list(states: Vec<ReadState>) -> Self {
+        Self::ReadStateList(ReadStateList { states })
     }
 
-    pub fn new_channel_list(channels: Vec<u64>) -> Self {
-        Self::ChannelList(ChannelList { channels })
+    pub fn new_role_create(id: u64, name: String, permissions: u64, guild_id: u64) -> Self {
+        Self::RoleCreate(RoleCreate {
+            id,
+            name,
+            permissions,
+            guild_id,
+        })
     }
 
-    pub fn new_channel_get_name(id: u64, name: Option<String>) -> Self {
-        Self::ChannelGetName(ChannelGetName { name, id })
+    pub fn new_role_update(id: u64, name: String, permissions: u64) -> Self {
+        Self::RoleUpdate(RoleUpdate {
+            id,
+            name,
+            permissions,
+        })
     }
 
-    pub fn new_channel_create(id: u64, name: String) -> Self {
-        Self::ChannelCreate(ChannelCreate { id, name })
+    pub fn new_role_delete(id: u64) -> Self {
+        Self::RoleDelete(RoleDelete { id })
     }
 
-    pub fn new_channel_delete(id: u64) -> Self {
-        Self::ChannelDelete(ChannelDelete { id })
+    pub fn new_role_list(guild_id: u64, roles: Vec<Role>) -> Self {
+        Self::RoleList(RoleList { guild_id, roles })
     }
 
-    pub fn new_channel_set_name(id: u64, name: String) -> Self {
-        Self::ChannelSetName(ChannelSetName { id, name })
+    pub fn new_role_assign(role_id: u64, user_id: u64) -> Self {
+        Self::RoleAssign(RoleAssign { role_id, user_id })
     }
 
-    pub fn new_message_list(channel_id: u64, messages: Vec<u64>) -> Self {
-        Self::MessageList(MessageList {
+    pub fn new_role_unassign(role_id: u64, user_id: u64) -> Self {
+        Self::RoleUnassign(RoleUnassign { role_id, user_id })
+    }
+
+    pub fn new_user_list_roles(id: u64, roles: Vec<u64>) -> Self {
+        Self::UserListRoles(UserListRoles { id, roles })
+    }
+
+    pub fn new_channel_set_role_overwrite(
+        channel_id: u64,
+        role_id: u64,
+        allow: u64,
+        deny: u64,
+    ) -> Self {
+        Self::ChannelSetRoleOverwrite(ChannelSetRoleOverwrite {
             channel_id,
-            messages,
+            role_id,
+            allow,
+            deny,
         })
     }
 
-    pub fn new_message_create(channel_id: u64, id: u64, content: String) -> Self {
-        Self::MessageCreate(MessageCreate {
+    pub fn new_channel_set_user_overwrite(
+        channel_id: u64,
+        user_id: u64,
+        allow: u64,
+        deny: u64,
+    ) -> Self {
+        Self::ChannelSetUserOverwrite(ChannelSetUserOverwrite {
             channel_id,
-            content,
-            id,
+            user_id,
+            allow,
+            deny,
         })
     }
-    pub fn new_message_delete(channel_id: u64, id: u64) -> Self {
-        Self::MessageDelete(MessageDelete { channel_id, id })
-    }
 
-    pub fn new_message_get_content(channel_id: u64, id: u64, content: Option<String>) -> Self {
-        Self::MessageGetContent(MessageGetContent {
+    pub fn new_channel_list_overwrites(
+        channel_id: u64,
+        roles: Vec<Overwrite>,
+        users: Vec<Overwrite>,
+    ) -> Self {
+        Self::ChannelListOverwrites(ChannelListOverwrites {
             channel_id,
-            content,
-            id,
+            roles,
+            users,
         })
     }
 
-    pub fn new_message_set_content(channel_id: u64, id: u64, content: String) -> Self {
-        Self::MessageSetContent(MessageSetContent {
+    pub fn new_permissions_get(channel_id: Option<u64>, permissions: u64) -> Self {
+        Self::PermissionsGet(PermissionsGet {
             channel_id,
-            content,
-            id,
+            permissions,
         })
     }
 
+    pub fn new_guild_create(id: u64, name: String, owner: Option<u64>) -> Self {
+        Self::GuildCreate(GuildCreate { id, name, owner })
+    }
+
+    pub fn new_guild_delete(id: u64) -> Self {
+        Self::GuildDelete(GuildDelete { id })
+    }
+
+    pub fn new_guild_list(guilds: Vec<GuildInfo>) -> Self {
+        Self::GuildList(GuildList { guilds })
+    }
+
+    pub fn new_guild_member_add(id: u64, user_id: u64) -> Self {
+        Self::GuildMemberAdd(GuildMemberAdd { id, user_id })
+    }
+
+    pub fn new_guild_member_remove(id: u64, user_id: u64) -> Self {
+        Self::GuildMemberRemove(GuildMemberRemove { id, user_id })
+    }
+
+    pub fn new_guild_member_list(id: u64, members: Vec<u64>) -> Self {
+        Self::GuildMemberList(GuildMemberList { id, members })
+    }
+
+    pub fn new_guild_set_op(id: u64, user_id: u64, op: bool) -> Self {
+        Self::GuildSetOp(GuildSetOp { id, user_id, op })
+    }
+
     pub fn new_user_list(users: Vec<u64>) -> Self {
         Self::UserList(UserList { users })
     }
@@ -200,16 +1275,167 @@ impl ServerEvent {
         Self::UserSetPass(UserSetPass { id })
     }
 
+    pub fn new_user_find_by_name(name: String, id: Option<u64>) -> Self {
+        Self::UserFindByName(UserFindByName { name, id })
+    }
+
+    pub fn new_profile_get(id: u64, profile: Profile) -> Self {
+        Self::ProfileGet(ProfileGet { id, profile })
+    }
+
+    pub fn new_profile_update(id: u64, profile: Profile) -> Self {
+        Self::ProfileUpdate(ProfileUpdate { id, profile })
+    }
+
+    pub fn new_presence_update(id: u64, status: String) -> Self {
+        Self::PresenceUpdate(PresenceUpdate { id, status })
+    }
+
+    pub fn new_presence_get(presences: Vec<UserPresence>) -> Self {
+        Self::PresenceGet(PresenceGet { presences })
+    }
+
+    pub fn new_typing_start(channel_id: u64, user_id: u64) -> Self {
+        Self::TypingStart(TypingStart {
+            channel_id,
+            user_id,
+        })
+    }
+
+    pub fn new_typing_stop(channel_id: u64, user_id: u64) -> Self {
+        Self::TypingStop(TypingStop {
+            channel_id,
+            user_id,
+        })
+    }
+
+    pub fn new_lockout_list(lockouts: Vec<Lockout>) -> Self {
+        Self::LockoutList(LockoutList { lockouts })
+    }
+
+    pub fn new_invite_create(invite: Invite) -> Self {
+        Self::InviteCreate(InviteCreate { invite })
+    }
+
+    pub fn new_invite_list(invites: Vec<Invite>) -> Self {
+        Self::InviteList(InviteList { invites })
+    }
+
+    pub fn new_invite_revoke(code: String) -> Self {
+        Self::InviteRevoke(InviteRevoke { code })
+    }
+
+    pub fn new_ban_create(ban: Ban) -> Self {
+        Self::BanCreate(BanCreate { ban })
+    }
+
+    pub fn new_ban_remove(id: u64) -> Self {
+        Self::BanRemove(BanRemove { id })
+    }
+
+    pub fn new_ban_list(bans: Vec<Ban>) -> Self {
+        Self::BanList(BanList { bans })
+    }
+
+    pub fn new_kicked(reason: Option<String>, ban: Option<Ban>) -> Self {
+        Self::Kicked(Kicked { reason, ban })
+    }
+
+    pub fn new_user_timeout(id: u64, until: Option<i64>) -> Self {
+        Self::UserTimeout(UserTimeout { id, until })
+    }
+
+    pub fn new_audit_log_list(entries: Vec<AuditEntry>) -> Self {
+        Self::AuditLogList(AuditLogList { entries })
+    }
+
     pub fn try_parse(line: &str) -> Option<Self> {
         use repr::Command::*;
         let command: repr::Command = serde_json::from_str(line).ok()?;
         let mapped = match command {
             pong { content } => Self::Pong(Pong { content }),
-            channel_list { channels } => Self::ChannelList(ChannelList { channels }),
+            rate_limited {
+                category,
+                retry_after,
+            } => Self::RateLimited(RateLimited {
+                category,
+                retry_after,
+            }),
+            channel_tombstone { id, purge_at } => {
+                Self::ChannelTombstone(ChannelTombstone { id, purge_at })
+            }
+            channel_restore { id } => Self::ChannelRestore(ChannelRestore { id }),
+            channel_list {
+                guild_id,
+                channels,
+                tree,
+            } => Self::ChannelList(ChannelList {
+                guild_id,
+                channels,
+                tree,
+            }),
+            category_create { id, name, guild_id } => {
+                Self::CategoryCreate(CategoryCreate { id, name, guild_id })
+            }
+            channel_get_parent { id, parent } => {
+                Self::ChannelGetParent(ChannelGetParent { id, parent })
+            }
+            channel_set_parent { id, parent } => {
+                Self::ChannelSetParent(ChannelSetParent { id, parent })
+            }
+            channel_reorder { ids } => Self::ChannelReorder(ChannelReorder { ids }),
             channel_get_name { id, name } => Self::ChannelGetName(ChannelGetName { id, name }),
-            channel_create { id, name } => Self::ChannelCreate(ChannelCreate { id, name }),
             channel_set_name { id, name } => Self::ChannelSetName(ChannelSetName { id, name }),
+            channel_create { id, name, guild_id } => {
+                Self::ChannelCreate(ChannelCreate { id, name, guild_id })
+            }
             channel_delete { id } => Self::ChannelDelete(ChannelDelete { id }),
+            channel_get_info {
+                id,
+                name,
+                topic,
+                description,
+                kind,
+                created,
+                creator,
+            } => Self::ChannelGetInfo(ChannelGetInfo {
+                id,
+                name,
+                topic,
+                description,
+                kind,
+                created,
+                creator,
+            }),
+            channel_set_topic { id, topic } => Self::ChannelSetTopic(ChannelSetTopic { id, topic }),
+            channel_set_description { id, description } => {
+                Self::ChannelSetDescription(ChannelSetDescription { id, description })
+            }
+            channel_set_kind { id, kind } => Self::ChannelSetKind(ChannelSetKind { id, kind }),
+            channel_get_slow_mode { id, seconds } => {
+                Self::ChannelGetSlowMode(ChannelGetSlowMode { id, seconds })
+            }
+            channel_set_slow_mode { id, seconds } => {
+                Self::ChannelSetSlowMode(ChannelSetSlowMode { id, seconds })
+            }
+            channel_set_private { id, private } => {
+                Self::ChannelSetPrivate(ChannelSetPrivate { id, private })
+            }
+            channel_member_add { id, user_id } => {
+                Self::ChannelMemberAdd(ChannelMemberAdd { id, user_id })
+            }
+            channel_member_remove { id, user_id } => {
+                Self::ChannelMemberRemove(ChannelMemberRemove { id, user_id })
+            }
+            channel_member_list { id, members } => {
+                Self::ChannelMemberList(ChannelMemberList { id, members })
+            }
+            channel_list_pins { id, pins, limit } => {
+                Self::ChannelListPins(ChannelListPins { id, pins, limit })
+            }
+            channel_set_pin_limit { id, limit } => {
+                Self::ChannelSetPinLimit(ChannelSetPinLimit { id, limit })
+            }
             message_list {
                 channel_id,
                 messages,
@@ -221,38 +1447,295 @@ impl ServerEvent {
                 channel_id,
                 id,
                 content,
+                reply_to,
+                thread_id,
             } => Self::MessageCreate(MessageCreate {
                 channel_id,
                 content,
                 id,
+                reply_to,
+                thread_id,
             }),
             message_delete { channel_id, id } => {
                 Self::MessageDelete(MessageDelete { channel_id, id })
             }
+            message_tombstone {
+                channel_id,
+                id,
+                purge_at,
+            } => Self::MessageTombstone(MessageTombstone {
+                channel_id,
+                id,
+                purge_at,
+            }),
+            message_restore { channel_id, id } => {
+                Self::MessageRestore(MessageRestore { channel_id, id })
+            }
+            message_pin {
+                channel_id,
+                id,
+                pinner_id,
+            } => Self::MessagePin(MessagePin {
+                channel_id,
+                id,
+                pinner_id,
+            }),
+            message_unpin { channel_id, id } => Self::MessageUnpin(MessageUnpin { channel_id, id }),
             message_get_content {
                 channel_id,
                 id,
                 content,
+                edits,
+                reply_to,
+                thread_id,
+                reactions,
+                mentions,
             } => Self::MessageGetContent(MessageGetContent {
                 channel_id,
                 content,
                 id,
+                edits,
+                reply_to,
+                thread_id,
+                reactions,
+                mentions,
             }),
             message_set_content {
                 channel_id,
                 id,
                 content,
+                edits,
             } => Self::MessageSetContent(MessageSetContent {
                 channel_id,
                 content,
                 id,
+                edits,
             }),
+            message_get_history {
+                channel_id,
+                id,
+                revisions,
+            } => Self::MessageGetHistory(MessageGetHistory {
+                channel_id,
+                id,
+                revisions,
+            }),
+            message_search {
+                query,
+                total,
+                offset,
+                results,
+            } => Self::MessageSearch(MessageSearch {
+                query,
+                total,
+                offset,
+                results,
+            }),
+            message_reindex { count } => Self::MessageReindex(MessageReindex { count }),
+            reaction_add {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            } => Self::ReactionAdd(ReactionAdd {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            }),
+            reaction_remove {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            } => Self::ReactionRemove(ReactionRemove {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            }),
+            reaction_list_users {
+                channel_id,
+                id,
+                emoji,
+                users,
+            } => Self::ReactionListUsers(ReactionListUsers {
+                channel_id,
+                id,
+                emoji,
+                users,
+            }),
+            thread_update {
+                channel_id,
+                id,
+                replies,
+                last_activity,
+            } => Self::ThreadUpdate(ThreadUpdate {
+                channel_id,
+                id,
+                replies,
+                last_activity,
+            }),
+            thread_message_list {
+                channel_id,
+                thread_id,
+                replies,
+                last_activity,
+                messages,
+            } => Self::ThreadMessageList(ThreadMessageList {
+                channel_id,
+                thread_id,
+                replies,
+                last_activity,
+                messages,
+            }),
+            thread_subscribe {
+                channel_id,
+                thread_id,
+            } => Self::ThreadSubscribe(ThreadSubscribe {
+                channel_id,
+                thread_id,
+            }),
+            thread_unsubscribe {
+                channel_id,
+                thread_id,
+            } => Self::ThreadUnsubscribe(ThreadUnsubscribe {
+                channel_id,
+                thread_id,
+            }),
+            direct_open { id, participants } => Self::DirectOpen(DirectOpen { id, participants }),
+            direct_list { channels } => Self::DirectList(DirectList { channels }),
+            mention {
+                channel_id,
+                id,
+                author_id,
+                content,
+            } => Self::Mention(Mention {
+                channel_id,
+                id,
+                author_id,
+                content,
+            }),
+            mentions_list { mentions } => Self::MentionsList(MentionsList { mentions }),
+            channel_ack { channel_id, id } => Self::ChannelAck(ChannelAck { channel_id, id }),
+            read_state_list { states } => Self::ReadStateList(ReadStateList { states }),
+            role_create {
+                id,
+                name,
+                permissions,
+                guild_id,
+            } => Self::RoleCreate(RoleCreate {
+                id,
+                name,
+                permissions,
+                guild_id,
+            }),
+            role_update {
+                id,
+                name,
+                permissions,
+            } => Self::RoleUpdate(RoleUpdate {
+                id,
+                name,
+                permissions,
+            }),
+            role_delete { id } => Self::RoleDelete(RoleDelete { id }),
+            role_list { guild_id, roles } => Self::RoleList(RoleList { guild_id, roles }),
+            role_assign { role_id, user_id } => Self::RoleAssign(RoleAssign { role_id, user_id }),
+            role_unassign { role_id, user_id } => {
+                Self::RoleUnassign(RoleUnassign { role_id, user_id })
+            }
+            user_list_roles { id, roles } => Self::UserListRoles(UserListRoles { id, roles }),
+            channel_set_role_overwrite {
+                channel_id,
+                role_id,
+                allow,
+                deny,
+            } => Self::ChannelSetRoleOverwrite(ChannelSetRoleOverwrite {
+                channel_id,
+                role_id,
+                allow,
+                deny,
+            }),
+            channel_set_user_overwrite {
+                channel_id,
+                user_id,
+                allow,
+                deny,
+            } => Self::ChannelSetUserOverwrite(ChannelSetUserOverwrite {
+                channel_id,
+                user_id,
+                allow,
+                deny,
+            }),
+            channel_list_overwrites {
+                channel_id,
+                roles,
+                users,
+            } => Self::ChannelListOverwrites(ChannelListOverwrites {
+                channel_id,
+                roles,
+                users,
+            }),
+            permissions_get {
+                channel_id,
+                permissions,
+            } => Self::PermissionsGet(PermissionsGet {
+                channel_id,
+                permissions,
+            }),
+            guild_create { id, name, owner } => Self::GuildCreate(GuildCreate { id, name, owner }),
+            guild_delete { id } => Self::GuildDelete(GuildDelete { id }),
+            guild_list { guilds } => Self::GuildList(GuildList { guilds }),
+            guild_member_add { id, user_id } => {
+                Self::GuildMemberAdd(GuildMemberAdd { id, user_id })
+            }
+            guild_member_remove { id, user_id } => {
+                Self::GuildMemberRemove(GuildMemberRemove { id, user_id })
+            }
+            guild_member_list { id, members } => {
+                Self::GuildMemberList(GuildMemberList { id, members })
+            }
+            guild_set_op { id, user_id, op } => Self::GuildSetOp(GuildSetOp { id, user_id, op }),
             user_list { users } => Self::UserList(UserList { users }),
             user_create { id, name } => Self::UserCreate(UserCreate { id, name }),
             user_delete { id } => Self::UserDelete(UserDelete { id }),
             user_get_name { id, name } => Self::UserGetName(UserGetName { id, name }),
             user_set_name { id, name } => Self::UserSetName(UserSetName { id, name }),
             user_set_pass { id } => Self::UserSetPass(UserSetPass { id }),
+            user_find_by_name { name, id } => Self::UserFindByName(UserFindByName { name, id }),
+            profile_get { id, profile } => Self::ProfileGet(ProfileGet { id, profile }),
+            profile_update { id, profile } => Self::ProfileUpdate(ProfileUpdate { id, profile }),
+            presence_update { id, status } => Self::PresenceUpdate(PresenceUpdate { id, status }),
+            presence_get { presences } => Self::PresenceGet(PresenceGet { presences }),
+            typing_start {
+                channel_id,
+                user_id,
+            } => Self::TypingStart(TypingStart {
+                channel_id,
+                user_id,
+            }),
+            typing_stop {
+                channel_id,
+                user_id,
+            } => Self::TypingStop(TypingStop {
+                channel_id,
+                user_id,
+            }),
+            lockout_list { lockouts } => Self::LockoutList(LockoutList { lockouts }),
+            invite_create { invite } => Self::InviteCreate(InviteCreate { invite }),
+            invite_list { invites } => Self::InviteList(InviteList { invites }),
+            invite_revoke { code } => Self::InviteRevoke(InviteRevoke { code }),
+            ban_create { ban } => Self::BanCreate(BanCreate { ban }),
+            ban_remove { id } => Self::BanRemove(BanRemove { id }),
+            ban_list { bans } => Self::BanList(BanList { bans }),
+            kicked { reason, ban } => Self::Kicked(Kicked { reason, ban }),
+            user_timeout { id, until } => Self::UserTimeout(UserTimeout { id, until }),
+            audit_log_list { entries } => Self::AuditLogList(AuditLogList { entries }),
         };
         Some(mapped)
     }
@@ -261,12 +1744,89 @@ impl ServerEvent {
         use repr::Command::*;
         let mapped = match self {
             Self::Pong(Pong { content }) => pong { content },
-            Self::ChannelList(ChannelList { channels }) => channel_list { channels },
+            Self::RateLimited(RateLimited {
+                category,
+                retry_after,
+            }) => rate_limited {
+                category,
+                retry_after,
+            },
+            Self::ChannelTombstone(ChannelTombstone { id, purge_at }) => {
+                channel_tombstone { id, purge_at }
+            }
+            Self::ChannelRestore(ChannelRestore { id }) => channel_restore { id },
+            Self::ChannelList(ChannelList {
+                guild_id,
+                channels,
+                tree,
+            }) => channel_list {
+                guild_id,
+                channels,
+                tree,
+            },
+            Self::CategoryCreate(CategoryCreate { id, name, guild_id }) => {
+                category_create { id, name, guild_id }
+            }
+            Self::ChannelGetParent(ChannelGetParent { id, parent }) => {
+                channel_get_parent { id, parent }
+            }
+            Self::ChannelSetParent(ChannelSetParent { id, parent }) => {
+                channel_set_parent { id, parent }
+            }
+            Self::ChannelReorder(ChannelReorder { ids }) => channel_reorder { ids },
             Self::ChannelGetName(ChannelGetName { id, name }) => channel_get_name { id, name },
-            Self::ChannelCreate(ChannelCreate { id, name }) => channel_create { id, name },
             Self::ChannelSetName(ChannelSetName { id, name }) => channel_set_name { id, name },
+            Self::ChannelCreate(ChannelCreate { id, name, guild_id }) => {
+                channel_create { id, name, guild_id }
+            }
             Self::ChannelDelete(ChannelDelete { id }) => channel_delete { id },
+            Self::ChannelGetInfo(ChannelGetInfo {
+                id,
+                name,
+                topic,
+                description,
+                kind,
+                created,
+                creator,
+            }) => channel_get_info {
+                id,
+                name,
+                topic,
+                description,
+                kind,
+                created,
+                creator,
+            },
+            Self::ChannelSetTopic(ChannelSetTopic { id, topic }) => channel_set_topic { id, topic },
+            Self::ChannelSetDescription(ChannelSetDescription { id, description }) => {
+                channel_set_description { id, description }
+            }
+            Self::ChannelSetKind(ChannelSetKind { id, kind }) => channel_set_kind { id, kind },
+            Self::ChannelGetSlowMode(ChannelGetSlowMode { id, seconds }) => {
+                channel_get_slow_mode { id, seconds }
+            }
+            Self::ChannelSetSlowMode(ChannelSetSlowMode { id, seconds }) => {
+                channel_set_slow_mode { id, seconds }
+            }
 
+            Self::ChannelSetPrivate(ChannelSetPrivate { id, private }) => {
+                channel_set_private { id, private }
+            }
+            Self::ChannelMemberAdd(ChannelMemberAdd { id, user_id }) => {
+                channel_member_add { id, user_id }
+            }
+            Self::ChannelMemberRemove(ChannelMemberRemove { id, user_id }) => {
+                channel_member_remove { id, user_id }
+            }
+            Self::ChannelMemberList(ChannelMemberList { id, members }) => {
+                channel_member_list { id, members }
+            }
+            Self::ChannelListPins(ChannelListPins { id, pins, limit }) => {
+                channel_list_pins { id, pins, limit }
+            }
+            Self::ChannelSetPinLimit(ChannelSetPinLimit { id, limit }) => {
+                channel_set_pin_limit { id, limit }
+            }
             Self::MessageList(MessageList {
                 channel_id,
                 messages,
@@ -278,38 +1838,295 @@ impl ServerEvent {
                 channel_id,
                 content,
                 id,
+                reply_to,
+                thread_id,
             }) => message_create {
                 channel_id,
                 id,
                 content,
+                reply_to,
+                thread_id,
             },
             Self::MessageDelete(MessageDelete { channel_id, id }) => {
                 message_delete { channel_id, id }
             }
+            Self::MessageTombstone(MessageTombstone {
+                channel_id,
+                id,
+                purge_at,
+            }) => message_tombstone {
+                channel_id,
+                id,
+                purge_at,
+            },
+            Self::MessageRestore(MessageRestore { channel_id, id }) => {
+                message_restore { channel_id, id }
+            }
+            Self::MessagePin(MessagePin {
+                channel_id,
+                id,
+                pinner_id,
+            }) => message_pin {
+                channel_id,
+                id,
+                pinner_id,
+            },
+            Self::MessageUnpin(MessageUnpin { channel_id, id }) => message_unpin { channel_id, id },
             Self::MessageGetContent(MessageGetContent {
                 channel_id,
                 content,
                 id,
+                edits,
+                reply_to,
+                thread_id,
+                reactions,
+                mentions,
             }) => message_get_content {
                 channel_id,
                 id,
                 content,
+                edits,
+                reply_to,
+                thread_id,
+                reactions,
+                mentions,
             },
             Self::MessageSetContent(MessageSetContent {
                 channel_id,
                 content,
                 id,
+                edits,
             }) => message_set_content {
                 channel_id,
                 id,
                 content,
+                edits,
+            },
+            Self::MessageGetHistory(MessageGetHistory {
+                channel_id,
+                id,
+                revisions,
+            }) => message_get_history {
+                channel_id,
+                id,
+                revisions,
+            },
+            Self::MessageSearch(MessageSearch {
+                query,
+                total,
+                offset,
+                results,
+            }) => message_search {
+                query,
+                total,
+                offset,
+                results,
+            },
+            Self::MessageReindex(MessageReindex { count }) => message_reindex { count },
+            Self::ReactionAdd(ReactionAdd {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            }) => reaction_add {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            },
+            Self::ReactionRemove(ReactionRemove {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            }) => reaction_remove {
+                channel_id,
+                id,
+                emoji,
+                user_id,
+                count,
+            },
+            Self::ReactionListUsers(ReactionListUsers {
+                channel_id,
+                id,
+                emoji,
+                users,
+            }) => reaction_list_users {
+                channel_id,
+                id,
+                emoji,
+                users,
+            },
+            Self::ThreadUpdate(ThreadUpdate {
+                channel_id,
+                id,
+                replies,
+                last_activity,
+            }) => thread_update {
+                channel_id,
+                id,
+                replies,
+                last_activity,
+            },
+            Self::ThreadMessageList(ThreadMessageList {
+                channel_id,
+                thread_id,
+                replies,
+                last_activity,
+                messages,
+            }) => thread_message_list {
+                channel_id,
+                thread_id,
+                replies,
+                last_activity,
+                messages,
+            },
+            Self::ThreadSubscribe(ThreadSubscribe {
+                channel_id,
+                thread_id,
+            }) => thread_subscribe {
+                channel_id,
+                thread_id,
+            },
+            Self::ThreadUnsubscribe(ThreadUnsubscribe {
+                channel_id,
+                thread_id,
+            }) => thread_unsubscribe {
+                channel_id,
+                thread_id,
+            },
+            Self::DirectOpen(DirectOpen { id, participants }) => direct_open { id, participants },
+            Self::DirectList(DirectList { channels }) => direct_list { channels },
+            Self::Mention(Mention {
+                channel_id,
+                id,
+                author_id,
+                content,
+            }) => mention {
+                channel_id,
+                id,
+                author_id,
+                content,
+            },
+            Self::MentionsList(MentionsList { mentions }) => mentions_list { mentions },
+            Self::ChannelAck(ChannelAck { channel_id, id }) => channel_ack { channel_id, id },
+            Self::ReadStateList(ReadStateList { states }) => read_state_list { states },
+            Self::RoleCreate(RoleCreate {
+                id,
+                name,
+                permissions,
+                guild_id,
+            }) => role_create {
+                id,
+                name,
+                permissions,
+                guild_id,
+            },
+            Self::RoleUpdate(RoleUpdate {
+                id,
+                name,
+                permissions,
+            }) => role_update {
+                id,
+                name,
+                permissions,
+            },
+            Self::RoleDelete(RoleDelete { id }) => role_delete { id },
+            Self::RoleList(RoleList { guild_id, roles }) => role_list { guild_id, roles },
+            Self::RoleAssign(RoleAssign { role_id, user_id }) => role_assign { role_id, user_id },
+            Self::RoleUnassign(RoleUnassign { role_id, user_id }) => {
+                role_unassign { role_id, user_id }
+            }
+            Self::UserListRoles(UserListRoles { id, roles }) => user_list_roles { id, roles },
+            Self::ChannelSetRoleOverwrite(ChannelSetRoleOverwrite {
+                channel_id,
+                role_id,
+                allow,
+                deny,
+            }) => channel_set_role_overwrite {
+                channel_id,
+                role_id,
+                allow,
+                deny,
+            },
+            Self::ChannelSetUserOverwrite(ChannelSetUserOverwrite {
+                channel_id,
+                user_id,
+                allow,
+                deny,
+            }) => channel_set_user_overwrite {
+                channel_id,
+                user_id,
+                allow,
+                deny,
+            },
+            Self::ChannelListOverwrites(ChannelListOverwrites {
+                channel_id,
+                roles,
+                users,
+            }) => channel_list_overwrites {
+                channel_id,
+                roles,
+                users,
+            },
+            Self::PermissionsGet(PermissionsGet {
+                channel_id,
+                permissions,
+            }) => permissions_get {
+                channel_id,
+                permissions,
             },
+            Self::GuildCreate(GuildCreate { id, name, owner }) => guild_create { id, name, owner },
+            Self::GuildDelete(GuildDelete { id }) => guild_delete { id },
+            Self::GuildList(GuildList { guilds }) => guild_list { guilds },
+            Self::GuildMemberAdd(GuildMemberAdd { id, user_id }) => {
+                guild_member_add { id, user_id }
+            }
+            Self::GuildMemberRemove(GuildMemberRemove { id, user_id }) => {
+                guild_member_remove { id, user_id }
+            }
+            Self::GuildMemberList(GuildMemberList { id, members }) => {
+                guild_member_list { id, members }
+            }
+            Self::GuildSetOp(GuildSetOp { id, user_id, op }) => guild_set_op { id, user_id, op },
             Self::UserList(UserList { users }) => user_list { users },
             Self::UserCreate(UserCreate { id, name }) => user_create { id, name },
             Self::UserDelete(UserDelete { id }) => user_delete { id },
             Self::UserGetName(UserGetName { id, name }) => user_get_name { id, name },
             Self::UserSetName(UserSetName { id, name }) => user_set_name { id, name },
             Self::UserSetPass(UserSetPass { id }) => user_set_pass { id },
+            Self::UserFindByName(UserFindByName { name, id }) => user_find_by_name { name, id },
+            Self::ProfileGet(ProfileGet { id, profile }) => profile_get { id, profile },
+            Self::ProfileUpdate(ProfileUpdate { id, profile }) => profile_update { id, profile },
+            Self::PresenceUpdate(PresenceUpdate { id, status }) => presence_update { id, status },
+            Self::PresenceGet(PresenceGet { presences }) => presence_get { presences },
+            Self::TypingStart(TypingStart {
+                channel_id,
+                user_id,
+            }) => typing_start {
+                channel_id,
+                user_id,
+            },
+            Self::TypingStop(TypingStop {
+                channel_id,
+                user_id,
+            }) => typing_stop {
+                channel_id,
+                user_id,
+            },
+            Self::LockoutList(LockoutList { lockouts }) => lockout_list { lockouts },
+            Self::InviteCreate(InviteCreate { invite }) => invite_create { invite },
+            Self::InviteList(InviteList { invites }) => invite_list { invites },
+            Self::InviteRevoke(InviteRevoke { code }) => invite_revoke { code },
+            Self::BanCreate(BanCreate { ban }) => ban_create { ban },
+            Self::BanRemove(BanRemove { id }) => ban_remove { id },
+            Self::BanList(BanList { bans }) => ban_list { bans },
+            Self::Kicked(Kicked { reason, ban }) => kicked { reason, ban },
+            Self::UserTimeout(UserTimeout { id, until }) => user_timeout { id, until },
+            Self::AuditLogList(AuditLogList { entries }) => audit_log_list { entries },
         };
 
         serde_json::to_string(&mapped).unwrap()
@@ -327,8 +2144,40 @@ mod repr {
         pong {
             content: String,
         },
+        rate_limited {
+            category: String,
+            retry_after: u64,
+        },
+        channel_tombstone {
+            id: u64,
+            purge_at: i64,
+        },
+        channel_restore {
+            id: u64,
+        },
         channel_list {
+            #[serde(default)]
+            guild_id: u64,
             channels: Vec<u64>,
+            #[serde(default)]
+            tree: Vec<super::ChannelNode>,
+        },
+        category_create {
+            id: u64,
+            name: String,
+            #[serde(default)]
+            guild_id: u64,
+        },
+        channel_get_parent {
+            id: u64,
+            parent: Option<u64>,
+        },
+        channel_set_parent {
+            id: u64,
+            parent: Option<u64>,
+        },
+        channel_reorder {
+            ids: Vec<u64>,
         },
         channel_get_name {
             id: u64,
@@ -337,6 +2186,8 @@ mod repr {
         channel_create {
             id: u64,
             name: String,
+            #[serde(default)]
+            guild_id: u64,
         },
         channel_delete {
             id: u64,
@@ -345,6 +2196,60 @@ mod repr {
             id: u64,
             name: String,
         },
+        channel_get_info {
+            id: u64,
+            name: String,
+            topic: String,
+            description: String,
+            kind: String,
+            created: i64,
+            creator: Option<u64>,
+        },
+        channel_set_topic {
+            id: u64,
+            topic: String,
+        },
+        channel_set_description {
+            id: u64,
+            description: String,
+        },
+        channel_set_kind {
+            id: u64,
+            kind: String,
+        },
+        channel_get_slow_mode {
+            id: u64,
+            seconds: Option<u64>,
+        },
+        channel_set_slow_mode {
+            id: u64,
+            seconds: u64,
+        },
+        channel_set_private {
+            id: u64,
+            private: bool,
+        },
+        channel_member_add {
+            id: u64,
+            user_id: u64,
+        },
+        channel_member_remove {
+            id: u64,
+            user_id: u64,
+        },
+        channel_member_list {
+            id: u64,
+            members: Vec<u64>,
+        },
+        channel_list_pins {
+            id: u64,
+            pins: Vec<u64>,
+            limit: u32,
+        },
+        channel_set_pin_limit {
+            id: u64,
+            limit: u32,
+        },
         message_list {
             channel_id: u64,
             messages: Vec<u64>,
@@ -353,20 +2258,213 @@ mod repr {
             channel_id: u64,
             id: u64,
             content: String,
+            #[serde(default)]
+            reply_to: Option<u64>,
+            #[serde(default)]
+            thread_id: Option<u64>,
         },
         message_delete {
             channel_id: u64,
             id: u64,
         },
+        message_tombstone {
+            channel_id: u64,
+            id: u64,
+            purge_at: i64,
+        },
+        message_restore {
+            channel_id: u64,
+            id: u64,
+        },
+        message_pin {
+            channel_id: u64,
+            id: u64,
+            pinner_id: u64,
+        },
+        message_unpin {
+            channel_id: u64,
+            id: u64,
+        },
         message_get_content {
             channel_id: u64,
             id: u64,
             content: Option<String>,
+            #[serde(default)]
+            edits: u32,
+            #[serde(default)]
+            reply_to: Option<u64>,
+            #[serde(default)]
+            thread_id: Option<u64>,
+            #[serde(default)]
+            reactions: Vec<super::Reaction>,
+            #[serde(default)]
+            mentions: Vec<u64>,
         },
         message_set_content {
             channel_id: u64,
             id: u64,
             content: String,
+            #[serde(default)]
+            edits: u32,
+        },
+        message_get_history {
+            channel_id: u64,
+            id: u64,
+            revisions: Vec<super::Revision>,
+        },
+        message_search {
+            query: String,
+            total: u32,
+            offset: u32,
+            results: Vec<super::SearchResult>,
+        },
+        message_reindex {
+            count: u32,
+        },
+        reaction_add {
+            channel_id: u64,
+            id: u64,
+            emoji: String,
+            user_id: u64,
+            count: u32,
+        },
+        reaction_remove {
+            channel_id: u64,
+            id: u64,
+            emoji: String,
+            user_id: u64,
+            count: u32,
+        },
+        reaction_list_users {
+            channel_id: u64,
+            id: u64,
+            emoji: String,
+            users: Vec<u64>,
+        },
+        thread_update {
+            channel_id: u64,
+            id: u64,
+            replies: u32,
+            last_activity: Option<i64>,
+        },
+        thread_message_list {
+            channel_id: u64,
+            thread_id: u64,
+            replies: u32,
+            last_activity: Option<i64>,
+            messages: Vec<u64>,
+        },
+        thread_subscribe {
+            channel_id: u64,
+            thread_id: u64,
+        },
+        thread_unsubscribe {
+            channel_id: u64,
+            thread_id: u64,
+        },
+        direct_open {
+            id: u64,
+            participants: Vec<u64>,
+        },
+        direct_list {
+            channels: Vec<super::DirectChannel>,
+        },
+        mention {
+            channel_id: u64,
+            id: u64,
+            author_id: Option<u64>,
+            content: String,
+        },
+        mentions_list {
+            mentions: Vec<super::MentionRef>,
+        },
+        channel_ack {
+            channel_id: u64,
+            id: u64,
+        },
+        read_state_list {
+            states: Vec<super::ReadState>,
+        },
+        role_create {
+            id: u64,
+            name: String,
+            permissions: u64,
+            #[serde(default)]
+            guild_id: u64,
+        },
+        role_update {
+            id: u64,
+            name: String,
+            permissions: u64,
+        },
+        role_delete {
+            id: u64,
+        },
+        role_list {
+            #[serde(default)]
+            guild_id: u64,
+            roles: Vec<super::Role>,
+        },
+        role_assign {
+            role_id: u64,
+            user_id: u64,
+        },
+        role_unassign {
+            role_id: u64,
+            user_id: u64,
+        },
+        user_list_roles {
+            id: u64,
+            roles: Vec<u64>,
+        },
+        channel_set_role_overwrite {
+            channel_id: u64,
+            role_id: u64,
+            allow: u64,
+            deny: u64,
+        },
+        channel_set_user_overwrite {
+            channel_id: u64,
+            user_id: u64,
+            allow: u64,
+            deny: u64,
+        },
+        channel_list_overwrites {
+            channel_id: u64,
+            roles: Vec<super::Overwrite>,
+            users: Vec<super::Overwrite>,
+        },
+        permissions_get {
+            channel_id: Option<u64>,
+            permissions: u64,
+        },
+        guild_create {
+            id: u64,
+            name: String,
+            owner: Option<u64>,
+        },
+        guild_delete {
+            id: u64,
+        },
+        guild_list {
+            guilds: Vec<super::GuildInfo>,
+        },
+        guild_member_add {
+            id: u64,
+            user_id: u64,
+        },
+        guild_member_remove {
+            id: u64,
+            user_id: u64,
+        },
+        guild_member_list {
+            id: u64,
+            members: Vec<u64>,
+        },
+        guild_set_op {
+            id: u64,
+            user_id: u64,
+            op: bool,
         },
         user_list {
             users: Vec<u64>,
@@ -389,5 +2487,67 @@ mod repr {
         user_set_pass {
             id: u64,
         },
+        user_find_by_name {
+            name: String,
+            id: Option<u64>,
+        },
+        profile_get {
+            id: u64,
+            profile: super::Profile,
+        },
+        profile_update {
+            id: u64,
+            profile: super::Profile,
+        },
+        presence_update {
+            id: u64,
+            status: String,
+        },
+        presence_get {
+            presences: Vec<super::UserPresence>,
+        },
+        typing_start {
+            channel_id: u64,
+            user_id: u64,
+        },
+        typing_stop {
+            channel_id: u64,
+            user_id: u64,
+        },
+        lockout_list {
+            lockouts: Vec<super::Lockout>,
+        },
+        invite_create {
+            invite: super::Invite,
+        },
+        invite_list {
+            invites: Vec<super::Invite>,
+        },
+        invite_revoke {
+            code: String,
+        },
+        ban_create {
+            ban: super::Ban,
+        },
+        ban_remove {
+            id: u64,
+        },
+        ban_list {
+            bans: Vec<super::Ban>,
+        },
+        kicked {
+            #[serde(default)]
+            reason: Option<String>,
+            #[serde(default)]
+            ban: Option<super::Ban>,
+        },
+        user_timeout {
+            id: u64,
+            #[serde(default)]
+            until: Option<i64>,
+        },
+        audit_log_list {
+            entries: Vec<super::AuditEntry>,
+        },
     }
 }
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::limits::Limit;
+
+/// server settings, read from a json file where every missing field falls back to its default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub limits: LimitsConfig,
+    pub auth: AuthConfig,
+    pub registration: RegistrationPolicy,
+    /// whether deleting a user also deletes the messages it authored.
+    pub purge_user_messages: bool,
+    pub trash: TrashConfig,
+    pub channels: ChannelsConfig,
+    pub presence: PresenceConfig,
+}
+
+impl Config {
+    pub fn load(path: &str) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .unwrap_or_else(|error| panic!("invalid config at '{path}': {error}")),
+            Err(_) => {
+                println!("[config/info] no config found at '{path}', using defaults");
+                Self::default()
+            }
+        }
+    }
+}
+
+/// rate limits of each category of requests, applied both per connection and per user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LimitsConfig {
+    pub ping: Limit,
+    pub authenticate: Limit,
+    pub query: Limit,
+    pub message: Limit,
+    pub channel: Limit,
+    pub user: Limit,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            ping: Limit::new(5, 1_000),
+            authenticate: Limit::new(5, 2_000),
+            query: Limit::new(20, 250),
+            message: Limit::new(5, 1_000),
+            channel: Limit::new(3, 5_000),
+            user: Limit::new(3, 5_000),
+        }
+    }
+}
+
+/// protection of authentication against password guessing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// delay imposed after a first failure, doubled by each following one.
+    pub backoff_base_ms: u64,
+    pub backoff_max_ms: u64,
+    /// failures on an account before it gets locked.
+    pub user_lockout_threshold: u32,
+    /// failures from a host before it gets locked.
+    pub host_lockout_threshold: u32,
+    pub lockout_duration_secs: u64,
+    /// duration without failure after which the count of failures is reset.
+    pub failure_window_secs: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            backoff_base_ms: 500,
+            backoff_max_ms: 30_000,
+            user_lockout_threshold: 10,
+            host_lockout_threshold: 30,
+            lockout_duration_secs: 15 * 60,
+            failure_window_secs: 60 * 60,
+        }
+    }
+}
+
+/// retention of deleted channels and messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrashConfig {
+    /// duration during which a deleted item can be restored.
+    pub grace_secs: u64,
+    /// delay between two purges of the expired items.
+    pub purge_interval_secs: u64,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self {
+            grace_secs: 7 * 24 * 60 * 60,
+            purge_interval_secs: 60 * 60,
+        }
+    }
+}
+
+/// defaults of the settings of each channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelsConfig {
+    /// maximum number of pinned messages in channels without a limit of their own.
+    pub default_pin_limit: u32,
+    /// maximum number of users in a direct channel, its opener included.
+    pub max_direct_participants: usize,
+}
+
+impl Default for ChannelsConfig {
+    fn default() -> Self {
+        Self {
+            default_pin_limit: 50,
+            max_direct_participants: 10,
+        }
+    }
+}
+
+/// detection of inactive users.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresenceConfig {
+    /// duration without any request after which a user is shown as idle.
+    pub idle_after_secs: u64,
+    /// delay between two checks for users becoming idle.
+    pub check_interval_secs: u64,
+}
+
+impl Default for PresenceConfig {
+    fn default() -> Self {
+        Self {
+            idle_after_secs: 5 * 60,
+            check_interval_secs: 30,
+        }
+    }
+}
+
+/// who may create new accounts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationPolicy {
+    /// anyone, even anonymously.
+    #[default]
+    Open,
+    /// server operators, or anyone holding a valid invite code.
+    InviteOnly,
+    /// server operators only.
+    OperatorOnly,
+    /// nobody.
+    Closed,
+}
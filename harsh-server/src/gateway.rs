@@ -1,21 +1,50 @@
+use std::time::Duration;
+
 use harsh_common::{client, server, ClientRequest, ServerEvent};
 use telecomande::{Processor, Remote};
 
 use crate::{
-    sessions::SessionExt, storage::Perm, Addr, Id, SecurityCmd, SecurityProc, SessionCmd,
-    SessionProc, StorageCmd, StorageProc,
+    config::{Config, RegistrationPolicy},
+    limits::{Category, Key, Limiter},
+    sessions::{Presence, SessionExt},
+    storage::{
+        is_valid_emoji, is_valid_profile, mentions_everyone, AuditEntry, AuditFilter, Ban, Channel,
+        ChannelKind, Guild, Invite, LockTarget, Message, Overwrite, OverwriteTarget, Perm,
+        Permissions, Profile, Role, Scope, SearchQuery, MAX_AUDIT_PAGE, MAX_SEARCH_LIMIT,
+    },
+    typing::Typing,
+    Addr, AuthOutcome, Id, SecurityCmd, SecurityProc, SessionCmd, SessionProc, StorageCmd,
+    StorageProc,
 };
 
+/// number of thread messages sent when a client does not ask for a specific amount.
+const DEFAULT_THREAD_PAGE: usize = 50;
+/// number of mentions sent when a client does not ask for a specific amount.
+const DEFAULT_MENTIONS_PAGE: usize = 50;
+/// number of users whose presence may be asked for at once.
+const MAX_PRESENCE_QUERY: usize = 100;
+
 #[derive(Debug)]
 pub enum GatewayCmd {
     Request(Addr, String),
     ClosedConnection(Addr),
+    /// removes for good the deleted items whose grace period expired.
+    Purge,
+    /// announces the end of the typings that were not renewed in time.
+    ExpireTyping,
 }
 
 pub struct GatewayProc {
     sessions: Remote<SessionProc>,
     storage: Remote<StorageProc>,
     security: Remote<SecurityProc>,
+    limiter: Limiter,
+    registration: RegistrationPolicy,
+    purge_user_messages: bool,
+    trash_grace: Duration,
+    default_pin_limit: u32,
+    max_direct_participants: usize,
+    typing: Typing,
 }
 
 use client::*;
@@ -25,226 +54,2551 @@ impl GatewayProc {
         use client::*;
         use ClientRequest as CR;
 
+        let user = self.sessions.get_user(addr.clone()).await;
+        let category = Category::of(&request);
+        if let Err(delay) = self.limiter.check(&addr, user, category) {
+            self.send_rate_limited(addr.clone(), category, delay);
+            Err(format!("rate limited on '{}'", category.name()))?;
+        }
+        if !matches!(request, CR::Ping(_)) {
+            let command = SessionCmd::new_touch(addr.clone());
+            self.sessions.send(command).unwrap();
+        }
+
         // auth-free API
         let request = match request {
             CR::Ping(ping) => return self.on_ping(ping, addr),
             CR::Authenticate(authenticate) => {
                 return self.on_authenticate(authenticate, addr).await
             }
+            CR::UserCreate(req) => return self.on_user_create(req, user).await,
             _ => request,
         };
 
-        // let user = self.sessions.get_user(addr.clone()).await.ok_or("owno")?;
+        if let Some(channel_id) = channel_of(&request) {
+            if !self.can_view(user, channel_id.into()).await {
+                Err("not a member of this channel")?;
+            }
+        }
 
         // auth API
         match request {
-            CR::Ping(_) | CR::Authenticate(_) => unreachable!(),
+            CR::Ping(_) | CR::Authenticate(_) | CR::UserCreate(_) => unreachable!(),
 
-            CR::ChannelCreate(req) => self.on_channel_create(req).await,
-            CR::ChannelDelete(req) => todo!(), //self.on_channel_delete(req, user).await,
-            CR::ChannelList(req) => self.on_channel_list(req, addr).await,
+            CR::ChannelCreate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_create(req, user).await?
+            }
+            CR::ChannelDelete(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_delete(req, user).await?
+            }
+            CR::ChannelRestore(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_restore(req, user).await?
+            }
+            CR::ChannelList(req) => self.on_channel_list(req, addr, user).await?,
+            CR::CategoryCreate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_category_create(req, user).await?
+            }
+            CR::ChannelGetParent(req) => self.on_channel_get_parent(req, addr).await?,
+            CR::ChannelSetParent(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_parent(req, user).await?
+            }
+            CR::ChannelReorder(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_reorder(req, user).await?
+            }
             CR::ChannelGetName(req) => self.on_channel_get_name(req, addr).await,
-            CR::ChannelSetName(req) => self.on_channel_set_name(req),
+            CR::ChannelSetName(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_name(req, user).await?
+            }
+            CR::ChannelGetInfo(req) => self.on_channel_get_info(req, addr).await?,
+            CR::ChannelSetTopic(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_topic(req, user).await?
+            }
+            CR::ChannelSetDescription(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_description(req, user).await?
+            }
+            CR::ChannelSetKind(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_kind(req, user).await?
+            }
+            CR::ChannelGetSlowMode(req) => self.on_channel_get_slow_mode(req, addr).await,
+            CR::ChannelSetSlowMode(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_slow_mode(req, user).await?
+            }
+
+            CR::ChannelSetPrivate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_private(req, user).await?
+            }
+            CR::ChannelMemberAdd(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_member_add(req, user).await?
+            }
+            CR::ChannelMemberRemove(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_member_remove(req, user).await?
+            }
+            CR::ChannelMemberList(req) => self.on_channel_member_list(req, addr).await,
+
+            CR::ChannelListPins(req) => self.on_channel_list_pins(req, addr).await,
+            CR::ChannelSetPinLimit(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_pin_limit(req, user).await?
+            }
+
+            CR::MessageList(req) => self.on_message_list(req, addr).await?,
+            CR::MessageCreate(req) => self.on_message_create(req, addr, user).await?,
+            CR::TypingStart(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_typing_start(req, user).await?
+            }
+            CR::MessageDelete(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_message_delete(req, user).await?
+            }
+            CR::MessageRestore(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_message_restore(req, user).await?
+            }
+            CR::MessagePin(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_message_pin(req, user).await?
+            }
+            CR::MessageUnpin(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_message_unpin(req, user).await?
+            }
+            CR::MessageGetContent(req) => self.on_message_get_content(req, addr, user).await?,
+            CR::MessageSetContent(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_message_set_content(req, user).await?
+            }
+            CR::MessageGetHistory(req) => self.on_message_get_history(req, addr).await?,
+            CR::MessageSearch(req) => self.on_message_search(req, addr, user).await,
+            CR::MessageReindex(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_message_reindex(req, user, addr).await?
+            }
+
+            CR::ReactionAdd(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_reaction_add(req, user).await?
+            }
+            CR::ReactionRemove(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_reaction_remove(req, user).await?
+            }
+            CR::ReactionListUsers(req) => self.on_reaction_list_users(req, addr).await?,
+
+            CR::ThreadMessageList(req) => self.on_thread_message_list(req, addr).await?,
+            CR::ThreadSubscribe(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_thread_subscribe(req, user, addr).await?
+            }
+            CR::ThreadUnsubscribe(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_thread_unsubscribe(req, user, addr).await?
+            }
+
+            CR::DirectOpen(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_direct_open(req, user, addr).await?
+            }
+            CR::DirectList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_direct_list(req, user, addr).await
+            }
+
+            CR::MentionsList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_mentions_list(req, user, addr).await
+            }
+
+            CR::ChannelAck(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_ack(req, user).await?
+            }
+            CR::ReadStateList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_read_state_list(req, user, addr).await
+            }
+
+            CR::RoleCreate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_role_create(req, user).await?
+            }
+            CR::RoleUpdate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_role_update(req, user).await?
+            }
+            CR::RoleDelete(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_role_delete(req, user).await?
+            }
+            CR::RoleList(req) => self.on_role_list(req, addr, user).await?,
+            CR::RoleAssign(RoleAssign { role_id, user_id }) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_role_assign(role_id, user_id, true, user).await?
+            }
+            CR::RoleUnassign(RoleUnassign { role_id, user_id }) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_role_assign(role_id, user_id, false, user).await?
+            }
+            CR::UserListRoles(req) => self.on_user_list_roles(req, addr).await,
+            CR::ChannelSetRoleOverwrite(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_role_overwrite(req, user).await?
+            }
+            CR::ChannelSetUserOverwrite(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_channel_set_user_overwrite(req, user).await?
+            }
+            CR::ChannelListOverwrites(req) => self.on_channel_list_overwrites(req, addr).await,
+            CR::PermissionsGet(req) => self.on_permissions_get(req, addr, user).await,
 
-            CR::MessageList(req) => self.on_message_list(req, addr).await,
-            CR::MessageCreate(req) => self.on_message_create(req).await,
-            CR::MessageDelete(req) => self.on_message_delete(req),
-            CR::MessageGetContent(req) => self.on_message_get_content(req, addr).await,
-            CR::MessageSetContent(req) => self.on_message_set_content(req),
+            CR::GuildCreate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_guild_create(req, user, addr).await
+            }
+            CR::GuildDelete(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_guild_delete(req, user).await?
+            }
+            CR::GuildList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_guild_list(req, user, addr).await
+            }
+            CR::GuildMemberAdd(GuildMemberAdd { id, user_id }) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_guild_member_set(id, user_id, true, user).await?
+            }
+            CR::GuildMemberRemove(GuildMemberRemove { id, user_id }) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_guild_member_set(id, user_id, false, user).await?
+            }
+            CR::GuildMemberList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_guild_member_list(req, user, addr).await?
+            }
+            CR::GuildSetOp(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_guild_set_op(req, user).await?
+            }
 
             CR::UserList(req) => self.on_user_list(req, addr).await,
-            CR::UserCreate(req) => self.on_user_create(req).await,
-            CR::UserDelete(req) => self.on_user_delete(req),
+            CR::UserDelete(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_user_delete(req, user).await?
+            }
             CR::UserGetName(req) => self.on_user_get_name(req, addr).await,
-            CR::UserSetName(req) => self.on_user_set_name(req),
-            CR::UserSetPass(req) => self.on_user_set_pass(req, addr),
+            CR::UserSetName(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_user_set_name(req, user).await?
+            }
+            CR::UserSetPass(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_user_set_pass(req, user, addr).await?
+            }
+            CR::UserFindByName(req) => self.on_user_find_by_name(req, addr).await,
+
+            CR::ProfileGet(req) => self.on_profile_get(req, addr).await?,
+            CR::ProfileUpdate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_profile_update(req, user).await?
+            }
+
+            CR::PresenceSet(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_presence_set(req, user).await?
+            }
+            CR::PresenceGet(req) => self.on_presence_get(req, addr).await?,
+
+            CR::LockoutList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_lockout_list(req, user, addr).await?
+            }
+
+            CR::InviteCreate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_invite_create(req, user, addr).await?
+            }
+            CR::InviteList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_invite_list(req, user, addr).await?
+            }
+            CR::InviteRevoke(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_invite_revoke(req, user, addr).await?
+            }
+            CR::InviteUse(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_invite_use(req, user).await?
+            }
+
+            CR::BanCreate(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_ban_create(req, user, addr).await?
+            }
+            CR::BanRemove(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_ban_remove(req, user, addr).await?
+            }
+            CR::BanList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_ban_list(req, user, addr).await?
+            }
+            CR::UserKick(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_user_kick(req, user).await?
+            }
+            CR::UserTimeout(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_user_timeout(req, user, addr).await?
+            }
+            CR::AuditLogList(req) => {
+                let user = user.ok_or("not authenticated")?;
+                self.on_audit_log_list(req, user, addr).await?
+            }
         };
         Ok(())
     }
 
     async fn on_authenticate(
         &mut self,
-        Authenticate { id, pass }: Authenticate,
+        Authenticate { login, pass }: Authenticate,
         address: Addr,
     ) -> Result<(), String> {
-        let (cmd, rec) = SecurityCmd::new_authenticate(id.into(), pass);
-        self.security.send(cmd).unwrap();
-        if rec.await.unwrap() {
-            let command = SessionCmd::new_set_user(address, Some(id.into()));
-            self.sessions.send(command).unwrap();
-        } else {
-            Err("Invalid password")?;
+        // unknown names fail like wrong passwords, not to tell which names are registered
+        let id = match login {
+            Login::Id(id) => Some(Id::from_u64(id)),
+            Login::Name(name) => {
+                let (cmd, rec) = StorageCmd::new_user_find_by_name(name);
+                self.storage.send(cmd).unwrap();
+                rec.await.unwrap()
+            }
         };
+        self.verify_pass(id, pass, address.clone()).await?;
+        let id = id.ok_or("Invalid password")?;
+        let command = SessionCmd::new_set_user(address, Some(id));
+        self.sessions.send(command).unwrap();
         Ok(())
     }
 
+    async fn verify_pass(
+        &mut self,
+        user: Option<Id>,
+        pass: String,
+        address: Addr,
+    ) -> Result<(), String> {
+        let (cmd, rec) = SecurityCmd::new_authenticate(user, pass, address.clone());
+        self.security.send(cmd).unwrap();
+        match rec.await.unwrap() {
+            AuthOutcome::Accepted => Ok(()),
+            AuthOutcome::Rejected => Err("Invalid password".into()),
+            AuthOutcome::Throttled(delay) => {
+                self.send_rate_limited(address, Category::Authenticate, delay);
+                Err("authentication throttled".into())
+            }
+            AuthOutcome::Banned(ban) => {
+                let request = ServerEvent::new_kicked(None, Some(ban_payload(&ban)));
+                let command = SessionCmd::new_send(address, request);
+                self.sessions.send(command).unwrap();
+                Err("banned".into())
+            }
+        }
+    }
+
     pub fn new(
         sessions: Remote<SessionProc>,
         storage: Remote<StorageProc>,
         security: Remote<SecurityProc>,
+        config: &Config,
     ) -> Self {
+        let limiter = Limiter::new(config.limits.clone());
+        let registration = config.registration;
+        let purge_user_messages = config.purge_user_messages;
+        let trash_grace = Duration::from_secs(config.trash.grace_secs);
+        let default_pin_limit = config.channels.default_pin_limit;
+        let max_direct_participants = config.channels.max_direct_participants;
+        let typing = Typing::new();
         Self {
             sessions,
             storage,
             security,
+            limiter,
+            registration,
+            purge_user_messages,
+            trash_grace,
+            default_pin_limit,
+            max_direct_participants,
+            typing,
         }
     }
 
-    fn on_ping(&mut self, Ping { content }: Ping, address: Addr) -> Result<(), String> {
-        println!("[gateway/PING] '{content:?}'");
-        let request = ServerEvent::Pong(server::Pong { content });
+    fn send_rate_limited(&mut self, address: Addr, category: Category, delay: Duration) {
+        let retry_after = delay.as_millis() as u64;
+        let request = ServerEvent::new_rate_limited(category.name().to_string(), retry_after);
         let command = SessionCmd::new_send(address, request);
         self.sessions.send(command).unwrap();
-        Ok(())
     }
 
-    async fn on_channel_create(&mut self, ChannelCreate { name }: ChannelCreate) {
-        let (cmd, rec) = StorageCmd::new_channel_create(name.clone());
-        self.storage.send(cmd).unwrap();
-        let id = rec.await.unwrap().to_u64();
-        let request = ServerEvent::new_channel_create(id, name);
-        let command = SessionCmd::new_broadcast(request);
-        self.sessions.send(command).unwrap();
+    async fn has_server_perm(&mut self, user: Id, required: Permissions) -> bool {
+        let (cmd, rec) = SecurityCmd::new_verify(user, Perm::Server(required));
+        self.security.send(cmd).unwrap();
+        rec.await.unwrap()
+    }
+
+    /// whether the user may administrate the target account.
+    async fn can_manage_user(&mut self, user: Id, target: Id) -> bool {
+        user == target || self.has_server_perm(user, Permissions::MANAGE_USERS).await
     }
 
-    async fn on_channel_delete(&mut self, ChannelDelete { id }: ChannelDelete, user: Id) {
-        // TODO: verify is OP
-        let (cmd, req) = SecurityCmd::new_verify(user, Perm::OpChannel(id.into()));
+    async fn has_channel_perm(&mut self, user: Id, channel_id: Id, required: Permissions) -> bool {
+        let (cmd, rec) = SecurityCmd::new_verify(user, Perm::Channel(channel_id, required));
         self.security.send(cmd).unwrap();
-        req.await.unwrap();
-        let command = StorageCmd::new_channel_delete(id.into());
-        self.storage.send(command).unwrap();
-        let request = ServerEvent::new_channel_delete(id);
-        let command = SessionCmd::new_broadcast(request);
-        self.sessions.send(command).unwrap();
+        rec.await.unwrap()
+    }
+
+    async fn has_guild_perm(&mut self, user: Id, guild: Id, required: Permissions) -> bool {
+        let (cmd, rec) = SecurityCmd::new_verify(user, Perm::Guild(guild, required));
+        self.security.send(cmd).unwrap();
+        rec.await.unwrap()
+    }
+
+    /// the permissions of a user, anonymous users getting those of the everyone role.
+    async fn permissions(&mut self, user: Option<Id>, scope: Scope) -> Permissions {
+        let (cmd, rec) = SecurityCmd::new_resolve(user, scope);
+        self.security.send(cmd).unwrap();
+        rec.await.unwrap()
     }
 
-    async fn on_channel_list(&mut self, _: ChannelList, address: Addr) {
-        let (cmd, rec) = StorageCmd::new_channel_list();
+    /// the users allowed in a channel, `None` when it is open to everyone.
+    async fn channel_audience(&mut self, channel_id: Id) -> Option<Vec<Id>> {
+        let (cmd, rec) = StorageCmd::new_channel_audience(channel_id);
         self.storage.send(cmd).unwrap();
-        let channels = rec.await.unwrap().iter().map(|id| id.to_u64()).collect();
-        let request = ServerEvent::new_channel_list(channels);
-        let command = SessionCmd::new_send(address, request);
-        self.sessions.send(command).unwrap();
+        rec.await.unwrap()
     }
 
-    async fn on_channel_get_name(&mut self, ChannelGetName { id }: ChannelGetName, address: Addr) {
-        let (cmd, rec) = StorageCmd::new_channel_get_name(id.into());
+    /// anonymous users only see channels open to everyone.
+    async fn can_view(&mut self, user: Option<Id>, channel_id: Id) -> bool {
+        match user {
+            Some(user) => {
+                let (cmd, rec) = SecurityCmd::new_verify(user, Perm::ViewChannel(channel_id));
+                self.security.send(cmd).unwrap();
+                rec.await.unwrap()
+            }
+            None => self.channel_audience(channel_id).await.is_none(),
+        }
+    }
+
+    /// anonymous users only belong to the default guild, like every user.
+    async fn is_guild_member(&mut self, user: Option<Id>, guild: Id) -> bool {
+        if guild == Guild::DEFAULT {
+            return true;
+        }
+        let Some(user) = user else {
+            return false;
+        };
+        let (cmd, rec) = StorageCmd::new_guild_is_member(guild, user);
         self.storage.send(cmd).unwrap();
-        let name = rec.await.unwrap();
-        let request = ServerEvent::new_channel_get_name(id, name);
-        let command = SessionCmd::new_send(address, request);
+        rec.await.unwrap()
+    }
+
+    async fn guild_of(&mut self, channel_id: Id) -> Result<Id, String> {
+        let (cmd, rec) = StorageCmd::new_channel_get(channel_id);
+        self.storage.send(cmd).unwrap();
+        let channel = rec.await.unwrap().ok_or("channel not found")?;
+        Ok(channel.get_guild())
+    }
+
+    /// sends an event about a guild to its members, everyone being in the default one.
+    async fn send_to_guild(&mut self, guild: Id, request: ServerEvent) {
+        let audience = match guild == Guild::DEFAULT {
+            true => None,
+            false => {
+                let (cmd, rec) = StorageCmd::new_guild_member_list(guild);
+                self.storage.send(cmd).unwrap();
+                Some(rec.await.unwrap())
+            }
+        };
+        self.send_to_audience(audience, request);
+    }
+
+    fn send_to_audience(&mut self, audience: Option<Vec<Id>>, request: ServerEvent) {
+        let command = match audience {
+            Some(users) => SessionCmd::new_send_to(users, request),
+            None => SessionCmd::new_broadcast(request),
+        };
         self.sessions.send(command).unwrap();
     }
 
-    fn on_channel_set_name(&mut self, ChannelSetName { id, name }: ChannelSetName) {
-        let command = StorageCmd::new_channel_set_name(id.into(), name.clone());
+    /// records a privileged change in the audit log.
+    fn audit(&mut self, entry: AuditEntry) {
+        let command = StorageCmd::new_audit_append(entry);
         self.storage.send(command).unwrap();
-        let request = ServerEvent::new_channel_set_name(id, name);
-        let command = SessionCmd::new_broadcast(request);
+    }
+
+    /// sends an event about a channel to the users allowed in it.
+    async fn send_to_channel(&mut self, channel_id: Id, request: ServerEvent) {
+        let audience = self.channel_audience(channel_id).await;
+        self.send_to_audience(audience, request);
+    }
+
+    /// sends an event about a channel to the users allowed in it, except the one causing it.
+    async fn send_to_others(&mut self, channel_id: Id, user: Id, request: ServerEvent) {
+        let command = match self.channel_audience(channel_id).await {
+            Some(mut users) => {
+                users.retain(|&other| other != user);
+                SessionCmd::new_send_to(users, request)
+            }
+            None => SessionCmd::new_broadcast_except(user, request),
+        };
         self.sessions.send(command).unwrap();
     }
 
-    async fn on_message_list(&mut self, MessageList { channel_id }: MessageList, address: Addr) {
-        let (cmd, rec) = StorageCmd::new_message_list(channel_id.into());
-        self.storage.send(cmd).unwrap();
-        let messages = rec.await.unwrap().iter().map(Id::to_u64).collect();
-        let request = ServerEvent::new_message_list(channel_id, messages);
+    fn on_ping(&mut self, Ping { content }: Ping, address: Addr) -> Result<(), String> {
+        println!("[gateway/PING] '{content:?}'");
+        let request = ServerEvent::Pong(server::Pong { content });
         let command = SessionCmd::new_send(address, request);
         self.sessions.send(command).unwrap();
+        Ok(())
     }
 
-    async fn on_message_create(
+    async fn on_channel_create(
         &mut self,
-        MessageCreate {
-            channel_id,
-            content,
-        }: MessageCreate,
-    ) {
-        let (cmd, rec) = StorageCmd::new_message_create(channel_id.into(), content.clone());
+        ChannelCreate { name, guild_id }: ChannelCreate,
+        user: Id,
+    ) -> Result<(), String> {
+        let guild = guild_id.map_or(Guild::DEFAULT, Id::from_u64);
+        if !self.is_guild_member(Some(user), guild).await {
+            Err("not a member of this guild")?;
+        }
+        if !self
+            .has_guild_perm(user, guild, Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let (cmd, rec) =
+            StorageCmd::new_channel_create_by(name.clone(), ChannelKind::Text, guild, Some(user));
         self.storage.send(cmd).unwrap();
         let id = rec.await.unwrap();
-        let request = ServerEvent::new_message_create(channel_id, id.to_u64(), content);
-        let command = SessionCmd::new_broadcast(request);
-        self.sessions.send(command).unwrap();
+        self.audit(AuditEntry::new(Some(user), "channel_create", Some(id)).with_after(&name));
+        let id = id.to_u64();
+        let request = ServerEvent::new_channel_create(id, name, guild.to_u64());
+        self.send_to_guild(guild, request).await;
+        Ok(())
     }
 
-    fn on_message_delete(&mut self, MessageDelete { channel_id, id }: MessageDelete) {
-        let command = StorageCmd::new_message_delete(channel_id.into(), id.into());
+    async fn on_channel_delete(
+        &mut self,
+        ChannelDelete { id }: ChannelDelete,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let audience = self.channel_audience(id.into()).await;
+        let command = StorageCmd::new_channel_delete(id.into());
         self.storage.send(command).unwrap();
-        let request = ServerEvent::new_message_delete(channel_id, id);
-        let command = SessionCmd::new_broadcast(request);
-        self.sessions.send(command).unwrap();
+        self.audit(AuditEntry::new(
+            Some(user),
+            "channel_delete",
+            Some(id.into()),
+        ));
+        let request = ServerEvent::new_channel_tombstone(id, self.purge_date());
+        self.send_to_audience(audience, request);
+        Ok(())
     }
 
-    async fn on_message_get_content(
+    async fn on_channel_restore(
         &mut self,
-        MessageGetContent { channel_id, id }: MessageGetContent,
+        ChannelRestore { id }: ChannelRestore,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_server_perm(user, Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_channel_restore(id.into());
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("no such deleted channel")?;
+        }
+        self.audit(AuditEntry::new(
+            Some(user),
+            "channel_restore",
+            Some(id.into()),
+        ));
+        let request = ServerEvent::new_channel_restore(id);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_channel_list(
+        &mut self,
+        ChannelList { guild_id }: ChannelList,
         address: Addr,
-    ) {
-        let (cmd, rec) = StorageCmd::new_message_get_content(channel_id.into(), id.into());
+        user: Option<Id>,
+    ) -> Result<(), String> {
+        let guild = guild_id.map_or(Guild::DEFAULT, Id::from_u64);
+        if !self.is_guild_member(user, guild).await {
+            Err("not a member of this guild")?;
+        }
+        let (cmd, rec) = StorageCmd::new_channel_tree(user, guild);
         self.storage.send(cmd).unwrap();
-        let request = ServerEvent::new_message_get_content(channel_id, id, rec.await.unwrap());
+        let tree: Vec<_> = rec
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, children)| server::ChannelNode {
+                id: id.to_u64(),
+                children: children.iter().map(Id::to_u64).collect(),
+            })
+            .collect();
+        let channels = tree
+            .iter()
+            .flat_map(|node| std::iter::once(node.id).chain(node.children.iter().copied()))
+            .collect();
+        let request = ServerEvent::new_channel_list(guild.to_u64(), channels, tree);
         let command = SessionCmd::new_send(address, request);
         self.sessions.send(command).unwrap();
+        Ok(())
     }
 
-    fn on_message_set_content(
+    async fn on_category_create(
         &mut self,
-        MessageSetContent {
-            channel_id,
-            id,
-            content,
-        }: MessageSetContent,
-    ) {
-        let command =
-            StorageCmd::new_message_set_content(channel_id.into(), id.into(), content.clone());
-        self.storage.send(command).unwrap();
-        let request = ServerEvent::new_message_set_content(channel_id, id, content);
-        let command = SessionCmd::new_broadcast(request);
-        self.sessions.send(command).unwrap();
+        CategoryCreate { name, guild_id }: CategoryCreate,
+        user: Id,
+    ) -> Result<(), String> {
+        let guild = guild_id.map_or(Guild::DEFAULT, Id::from_u64);
+        if !self.is_guild_member(Some(user), guild).await {
+            Err("not a member of this guild")?;
+        }
+        if !self
+            .has_guild_perm(user, guild, Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_channel_create_by(
+            name.clone(),
+            ChannelKind::Category,
+            guild,
+            Some(user),
+        );
+        self.storage.send(cmd).unwrap();
+        let id = rec.await.unwrap();
+        self.audit(AuditEntry::new(Some(user), "category_create", Some(id)).with_after(&name));
+        let id = id.to_u64();
+        let request = ServerEvent::new_category_create(id, name, guild.to_u64());
+        self.send_to_guild(guild, request).await;
+        Ok(())
     }
 
-    fn on_user_set_pass(&mut self, UserSetPass { id, pass }: UserSetPass, address: Addr) {
-        let command = StorageCmd::new_user_set_pass(id.into(), pass);
-        self.storage.send(command).unwrap();
-        let request = ServerEvent::new_user_set_pass(id);
+    async fn on_channel_get_parent(
+        &mut self,
+        ChannelGetParent { id }: ChannelGetParent,
+        address: Addr,
+    ) -> Result<(), String> {
+        let (cmd, rec) = StorageCmd::new_channel_get(id.into());
+        self.storage.send(cmd).unwrap();
+        let channel = rec.await.unwrap().ok_or("channel not found")?;
+        let parent = channel.get_parent().map(|id| id.to_u64());
+        let request = ServerEvent::new_channel_get_parent(id, parent);
         let command = SessionCmd::new_send(address, request);
         self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_channel_set_parent(
+        &mut self,
+        ChannelSetParent { id, parent }: ChannelSetParent,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        if let Some(parent) = parent {
+            if !self.can_view(Some(user), parent.into()).await {
+                Err("not a member of the category")?;
+            }
+        }
+        let before = self
+            .get_channel(id.into())
+            .await
+            .and_then(|channel| channel.get_parent());
+        let (cmd, rec) = StorageCmd::new_channel_set_parent(id.into(), parent.map(Id::from_u64));
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("only channels can be moved, and only into categories")?;
+        }
+        let mut entry = AuditEntry::new(Some(user), "channel_set_parent", Some(id.into()));
+        if let Some(before) = before {
+            entry = entry.with_before(before);
+        }
+        if let Some(parent) = parent {
+            entry = entry.with_after(parent);
+        }
+        self.audit(entry);
+        let request = ServerEvent::new_channel_set_parent(id, parent);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
     }
 
-    fn on_user_set_name(&mut self, UserSetName { id, name }: UserSetName) {
-        let command = StorageCmd::new_user_set_name(id.into(), name.clone());
+    async fn on_channel_reorder(
+        &mut self,
+        ChannelReorder { ids }: ChannelReorder,
+        user: Id,
+    ) -> Result<(), String> {
+        let mut guild = Guild::DEFAULT;
+        for (index, &id) in ids.iter().enumerate() {
+            let channel_guild = self.guild_of(id.into()).await?;
+            if index > 0 && channel_guild != guild {
+                Err("the channels belong to different guilds")?;
+            }
+            guild = channel_guild;
+        }
+        if !self
+            .has_guild_perm(user, guild, Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let command =
+            StorageCmd::new_channel_reorder(ids.iter().copied().map(Id::from_u64).collect());
         self.storage.send(command).unwrap();
-        let request = ServerEvent::new_user_set_name(id, name);
-        let command = SessionCmd::new_broadcast(request);
-        self.sessions.send(command).unwrap();
+        let order = ids.iter().map(u64::to_string).collect::<Vec<_>>().join(",");
+        self.audit(AuditEntry::new(Some(user), "channel_reorder", Some(guild)).with_after(order));
+        let request = ServerEvent::new_channel_reorder(ids);
+        self.send_to_guild(guild, request).await;
+        Ok(())
     }
 
-    async fn on_user_get_name(&mut self, UserGetName { id }: UserGetName, address: Addr) {
-        let (cmd, rec) = StorageCmd::new_user_get_name(id.into());
+    async fn on_channel_get_name(&mut self, ChannelGetName { id }: ChannelGetName, address: Addr) {
+        let (cmd, rec) = StorageCmd::new_channel_get_name(id.into());
         self.storage.send(cmd).unwrap();
         let name = rec.await.unwrap();
-        let request = ServerEvent::new_user_get_name(id, name);
+        let request = ServerEvent::new_channel_get_name(id, name);
         let command = SessionCmd::new_send(address, request);
         self.sessions.send(command).unwrap();
     }
 
-    fn on_user_delete(&mut self, UserDelete { id }: UserDelete) {
-        let command = StorageCmd::new_user_delete(id.into());
+    async fn on_channel_set_name(
+        &mut self,
+        ChannelSetName { id, name }: ChannelSetName,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let before = self.get_channel(id.into()).await;
+        let command = StorageCmd::new_channel_set_name(id.into(), name.clone());
         self.storage.send(command).unwrap();
-        let request = ServerEvent::new_user_delete(id);
-        let command = SessionCmd::new_broadcast(request);
-        self.sessions.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "channel_set_name", Some(id.into()));
+        let entry = match before {
+            Some(before) => entry.with_before(before.get_name()),
+            None => entry,
+        };
+        self.audit(entry.with_after(&name));
+        let request = ServerEvent::new_channel_set_name(id, name);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
     }
 
-    async fn on_user_create(&mut self, UserCreate { name, pass }: UserCreate) {
-        let (cmd, rec) = StorageCmd::new_user_create(name.clone(), pass);
+    async fn on_channel_get_info(
+        &mut self,
+        ChannelGetInfo { id }: ChannelGetInfo,
+        address: Addr,
+    ) -> Result<(), String> {
+        let (cmd, rec) = StorageCmd::new_channel_get(id.into());
         self.storage.send(cmd).unwrap();
-        let id = rec.await.unwrap();
+        let channel = rec.await.unwrap().ok_or("channel not found")?;
+        let request = ServerEvent::new_channel_get_info(
+            id,
+            channel.get_name().to_string(),
+            channel.get_topic().to_string(),
+            channel.get_description().to_string(),
+            channel.get_kind().name().to_string(),
+            channel.get_created(),
+            channel.get_creator().map(|id| id.to_u64()),
+        );
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_channel_set_topic(
+        &mut self,
+        ChannelSetTopic { id, topic }: ChannelSetTopic,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let before = self.get_channel(id.into()).await;
+        let command = StorageCmd::new_channel_set_topic(id.into(), topic.clone());
+        self.storage.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "channel_set_topic", Some(id.into()));
+        let entry = match before {
+            Some(before) => entry.with_before(before.get_topic()),
+            None => entry,
+        };
+        self.audit(entry.with_after(&topic));
+        let request = ServerEvent::new_channel_set_topic(id, topic);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_channel_set_description(
+        &mut self,
+        ChannelSetDescription { id, description }: ChannelSetDescription,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let before = self.get_channel(id.into()).await;
+        let command = StorageCmd::new_channel_set_description(id.into(), description.clone());
+        self.storage.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "channel_set_description", Some(id.into()));
+        let entry = match before {
+            Some(before) => entry.with_before(before.get_description()),
+            None => entry,
+        };
+        self.audit(entry.with_after(&description));
+        let request = ServerEvent::new_channel_set_description(id, description);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_channel_set_kind(
+        &mut self,
+        ChannelSetKind { id, kind }: ChannelSetKind,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let parsed = ChannelKind::from_name(&kind).ok_or("unknown channel kind")?;
+        let before = self.get_channel(id.into()).await;
+        let (cmd, rec) = StorageCmd::new_channel_set_kind(id.into(), parsed);
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("channels and categories can not be turned into one another")?;
+        }
+        let entry = AuditEntry::new(Some(user), "channel_set_kind", Some(id.into()));
+        let entry = match before {
+            Some(before) => entry.with_before(before.get_kind().name()),
+            None => entry,
+        };
+        self.audit(entry.with_after(&kind));
+        let request = ServerEvent::new_channel_set_kind(id, kind);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_channel_get_slow_mode(
+        &mut self,
+        ChannelGetSlowMode { id }: ChannelGetSlowMode,
+        address: Addr,
+    ) {
+        let (cmd, rec) = StorageCmd::new_channel_get_slow_mode(id.into());
+        self.storage.send(cmd).unwrap();
+        let seconds = rec.await.unwrap();
+        let request = ServerEvent::new_channel_get_slow_mode(id, seconds);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn on_channel_set_slow_mode(
+        &mut self,
+        ChannelSetSlowMode { id, seconds }: ChannelSetSlowMode,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let before = self.get_channel(id.into()).await;
+        let command = StorageCmd::new_channel_set_slow_mode(id.into(), seconds);
+        self.storage.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "channel_set_slow_mode", Some(id.into()));
+        let entry = match before {
+            Some(before) => entry.with_before(before.get_slow_mode()),
+            None => entry,
+        };
+        self.audit(entry.with_after(seconds));
+        let request = ServerEvent::new_channel_set_slow_mode(id, seconds);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_channel_set_private(
+        &mut self,
+        ChannelSetPrivate { id, private }: ChannelSetPrivate,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let before = self.get_channel(id.into()).await;
+        let command = StorageCmd::new_channel_set_private(id.into(), private);
+        self.storage.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "channel_set_private", Some(id.into()));
+        let entry = match before {
+            Some(before) => entry.with_before(before.is_private()),
+            None => entry,
+        };
+        self.audit(entry.with_after(private));
+        let request = ServerEvent::new_channel_set_private(id, private);
+        let command = SessionCmd::new_broadcast(request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    /// the added user is notified along with the channel, even if it is not private yet.
+    async fn on_channel_member_add(
+        &mut self,
+        ChannelMemberAdd { id, user_id }: ChannelMemberAdd,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_user_get_name(user_id.into());
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap().ok_or("user not found")?;
+        let command = StorageCmd::new_channel_member_add(id.into(), user_id.into());
+        self.storage.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "channel_member_add", Some(id.into()));
+        self.audit(entry.with_after(user_id));
+        let audience = self.channel_audience(id.into()).await;
+        let request = ServerEvent::new_channel_member_add(id, user_id);
+        self.send_to_audience(audience, request);
+        Ok(())
+    }
+
+    async fn on_channel_member_remove(
+        &mut self,
+        ChannelMemberRemove { id, user_id }: ChannelMemberRemove,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let audience = self.channel_audience(id.into()).await.map(|mut users| {
+            users.push(user_id.into());
+            users
+        });
+        let command = StorageCmd::new_channel_member_remove(id.into(), user_id.into());
+        self.storage.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "channel_member_remove", Some(id.into()));
+        self.audit(entry.with_before(user_id));
+        let request = ServerEvent::new_channel_member_remove(id, user_id);
+        self.send_to_audience(audience, request);
+        Ok(())
+    }
+
+    async fn on_channel_member_list(
+        &mut self,
+        ChannelMemberList { id }: ChannelMemberList,
+        address: Addr,
+    ) {
+        let (cmd, rec) = StorageCmd::new_channel_member_list(id.into());
+        self.storage.send(cmd).unwrap();
+        let members = rec.await.unwrap().iter().map(Id::to_u64).collect();
+        let request = ServerEvent::new_channel_member_list(id, members);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn pin_limit(&mut self, channel_id: Id) -> u32 {
+        let (cmd, rec) = StorageCmd::new_channel_get_pin_limit(channel_id);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap().unwrap_or(self.default_pin_limit)
+    }
+
+    async fn on_channel_list_pins(
+        &mut self,
+        ChannelListPins { id }: ChannelListPins,
+        address: Addr,
+    ) {
+        let (cmd, rec) = StorageCmd::new_channel_get_pins(id.into());
+        self.storage.send(cmd).unwrap();
+        let pins = rec.await.unwrap().iter().map(Id::to_u64).collect();
+        let limit = self.pin_limit(id.into()).await;
+        let request = ServerEvent::new_channel_list_pins(id, pins, limit);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn on_channel_set_pin_limit(
+        &mut self,
+        ChannelSetPinLimit { id, limit }: ChannelSetPinLimit,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, id.into(), Permissions::MANAGE_CHANNELS)
+            .await
+        {
+            Err("missing the 'manage channels' permission")?;
+        }
+        let before = self.pin_limit(id.into()).await;
+        let command = StorageCmd::new_channel_set_pin_limit(id.into(), limit);
+        self.storage.send(command).unwrap();
+        let limit = limit.unwrap_or(self.default_pin_limit);
+        let entry = AuditEntry::new(Some(user), "channel_set_pin_limit", Some(id.into()));
+        self.audit(entry.with_before(before).with_after(limit));
+        let request = ServerEvent::new_channel_set_pin_limit(id, limit);
+        self.send_to_channel(id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_message_pin(
+        &mut self,
+        MessagePin { channel_id, id }: MessagePin,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, channel_id.into(), Permissions::MANAGE_MESSAGES)
+            .await
+        {
+            Err("missing the 'manage messages' permission")?;
+        }
+        self.get_message(channel_id.into(), id.into())
+            .await
+            .ok_or("message not found")?;
+        let (cmd, rec) = StorageCmd::new_channel_get_pins(channel_id.into());
+        self.storage.send(cmd).unwrap();
+        let pins = rec.await.unwrap();
+        if pins.len() as u32 >= self.pin_limit(channel_id.into()).await {
+            Err("too many pinned messages")?;
+        }
+        let (cmd, rec) = StorageCmd::new_message_pin(channel_id.into(), id.into());
+        self.storage.send(cmd).unwrap();
+        if rec.await.unwrap() {
+            let entry = AuditEntry::new(Some(user), "message_pin", Some(id.into()));
+            self.audit(entry.with_after(channel_id));
+            let request = ServerEvent::new_message_pin(channel_id, id, user.to_u64());
+            self.send_to_channel(channel_id.into(), request).await;
+        }
+        Ok(())
+    }
+
+    async fn on_message_unpin(
+        &mut self,
+        MessageUnpin { channel_id, id }: MessageUnpin,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_channel_perm(user, channel_id.into(), Permissions::MANAGE_MESSAGES)
+            .await
+        {
+            Err("missing the 'manage messages' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_message_unpin(channel_id.into(), id.into());
+        self.storage.send(cmd).unwrap();
+        if rec.await.unwrap() {
+            let entry = AuditEntry::new(Some(user), "message_unpin", Some(id.into()));
+            self.audit(entry.with_before(channel_id));
+            let request = ServerEvent::new_message_unpin(channel_id, id);
+            self.send_to_channel(channel_id.into(), request).await;
+        }
+        Ok(())
+    }
+
+    async fn on_message_list(
+        &mut self,
+        MessageList { channel_id }: MessageList,
+        address: Addr,
+    ) -> Result<(), String> {
+        self.get_channel(channel_id.into())
+            .await
+            .ok_or("channel not found")?;
+        let (cmd, rec) = StorageCmd::new_message_list(channel_id.into());
+        self.storage.send(cmd).unwrap();
+        let messages = rec.await.unwrap().iter().map(Id::to_u64).collect();
+        let request = ServerEvent::new_message_list(channel_id, messages);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_message_create(
+        &mut self,
+        MessageCreate {
+            channel_id,
+            content,
+            reply_to,
+            thread_id,
+        }: MessageCreate,
+        address: Addr,
+        user: Option<Id>,
+    ) -> Result<(), String> {
+        if let Some(reply_to) = reply_to {
+            self.get_message(channel_id.into(), reply_to.into())
+                .await
+                .ok_or("replied message not found")?;
+        }
+        if let Some(thread_id) = thread_id {
+            let parent = self
+                .get_message(channel_id.into(), thread_id.into())
+                .await
+                .ok_or("thread not found")?;
+            if parent.get_thread().is_some() {
+                Err("threads can not be nested")?;
+            }
+        }
+
+        let (cmd, rec) = StorageCmd::new_channel_get(channel_id.into());
+        self.storage.send(cmd).unwrap();
+        let channel = rec.await.unwrap().ok_or("channel not found")?;
+        let permissions = self
+            .permissions(user, Scope::Channel(channel_id.into()))
+            .await;
+        match channel.get_kind() {
+            ChannelKind::Category => Err("categories hold no message")?,
+            ChannelKind::ReadOnly => Err("the channel is read-only")?,
+            ChannelKind::Announcement if !permissions.contains(Permissions::MANAGE_CHANNELS) => {
+                Err("only operators may post in announcement channels")?
+            }
+            _ => (),
+        }
+        if !permissions.contains(Permissions::SEND_MESSAGES) {
+            Err("missing the 'send messages' permission")?;
+        }
+        if mentions_everyone(&content) && !permissions.contains(Permissions::MENTION_EVERYONE) {
+            Err("missing the 'mention everyone' permission")?;
+        }
+
+        let (cmd, rec) = StorageCmd::new_channel_get_slow_mode(channel_id.into());
+        self.storage.send(cmd).unwrap();
+        let slow_mode = rec.await.unwrap().unwrap_or(0);
+        if slow_mode > 0 && !permissions.contains(Permissions::MANAGE_MESSAGES) {
+            let key = match user {
+                Some(user) => Key::User(user),
+                None => Key::Addr(address.clone()),
+            };
+            let delay = Duration::from_secs(slow_mode);
+            if let Err(remaining) = self.limiter.check_slow_mode(channel_id.into(), key, delay) {
+                self.send_rate_limited(address, Category::Message, remaining);
+                Err("slow mode")?;
+            }
+        }
+
+        let (cmd, rec) = StorageCmd::new_message_create_linked(
+            channel_id.into(),
+            user,
+            content.clone(),
+            reply_to.map(Id::from_u64),
+            thread_id.map(Id::from_u64),
+        );
+        self.storage.send(cmd).unwrap();
+        let id = rec.await.unwrap();
+        if let Some(user) = user {
+            self.stop_typing(channel_id.into(), user).await;
+        }
+        self.send_mentions(channel_id.into(), id, &[]).await;
+        let request =
+            ServerEvent::new_message_create(channel_id, id.to_u64(), content, reply_to, thread_id);
+        match thread_id {
+            None => self.send_to_channel(channel_id.into(), request).await,
+            Some(thread_id) => {
+                let (cmd, rec) =
+                    StorageCmd::new_thread_subscribers(channel_id.into(), thread_id.into());
+                self.storage.send(cmd).unwrap();
+                let mut subscribers = rec.await.unwrap();
+                if let Some(audience) = self.channel_audience(channel_id.into()).await {
+                    subscribers.retain(|user| audience.contains(user));
+                }
+                let command = SessionCmd::new_send_to(subscribers, request);
+                self.sessions.send(command).unwrap();
+                self.send_thread_update(channel_id.into(), thread_id.into())
+                    .await;
+            }
+        }
+        Ok(())
+    }
+
+    /// only announces the start of a typing, renewals merely delaying its expiry.
+    async fn on_typing_start(
+        &mut self,
+        TypingStart { channel_id }: TypingStart,
+        user: Id,
+    ) -> Result<(), String> {
+        let permissions = self
+            .permissions(Some(user), Scope::Channel(channel_id.into()))
+            .await;
+        if !permissions.contains(Permissions::SEND_MESSAGES) {
+            Err("missing the 'send messages' permission")?;
+        }
+        if self.typing.start(channel_id.into(), user) {
+            let request = ServerEvent::new_typing_start(channel_id, user.to_u64());
+            self.send_to_others(channel_id.into(), user, request).await;
+        }
+        Ok(())
+    }
+
+    async fn stop_typing(&mut self, channel_id: Id, user: Id) {
+        if self.typing.stop(channel_id, user) {
+            let request = ServerEvent::new_typing_stop(channel_id.to_u64(), user.to_u64());
+            self.send_to_others(channel_id, user, request).await;
+        }
+    }
+
+    async fn on_expire_typing(&mut self) {
+        for (channel_id, user) in self.typing.expire() {
+            let request = ServerEvent::new_typing_stop(channel_id.to_u64(), user.to_u64());
+            self.send_to_others(channel_id, user, request).await;
+        }
+    }
+
+    /// notifies the users addressed by a message, except its author and those already notified.
+    async fn send_mentions(&mut self, channel_id: Id, id: Id, notified: &[Id]) {
+        let Some(message) = self.get_message(channel_id, id).await else {
+            return;
+        };
+        let users: Vec<_> = message
+            .get_mentions()
+            .iter()
+            .filter(|user| !notified.contains(user) && Some(**user) != message.get_author())
+            .copied()
+            .collect();
+        if users.is_empty() {
+            return;
+        }
+        let request = ServerEvent::new_mention(
+            channel_id.to_u64(),
+            id.to_u64(),
+            message.get_author().map(|id| id.to_u64()),
+            message.get_content().to_string(),
+        );
+        let command = SessionCmd::new_send_to(users, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn get_channel(&mut self, id: Id) -> Option<Channel> {
+        let (cmd, rec) = StorageCmd::new_channel_get(id);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap()
+    }
+
+    async fn get_message(&mut self, channel_id: Id, id: Id) -> Option<Message> {
+        let (cmd, rec) = StorageCmd::new_message_get(channel_id, id);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap()
+    }
+
+    /// notifies the channel of the new counters of a thread.
+    async fn send_thread_update(&mut self, channel_id: Id, id: Id) {
+        if let Some(parent) = self.get_message(channel_id, id).await {
+            let request = ServerEvent::new_thread_update(
+                channel_id.to_u64(),
+                id.to_u64(),
+                parent.get_replies(),
+                parent.get_last_activity(),
+            );
+            self.send_to_channel(channel_id, request).await;
+        }
+    }
+
+    async fn on_message_delete(
+        &mut self,
+        MessageDelete { channel_id, id }: MessageDelete,
+        user: Id,
+    ) -> Result<(), String> {
+        let message = self
+            .get_message(channel_id.into(), id.into())
+            .await
+            .ok_or("message not found")?;
+        if message.get_author() != Some(user)
+            && !self
+                .has_channel_perm(user, channel_id.into(), Permissions::MANAGE_MESSAGES)
+                .await
+        {
+            Err("missing the 'manage messages' permission")?;
+        }
+        let command = StorageCmd::new_message_delete(channel_id.into(), id.into());
+        self.storage.send(command).unwrap();
+        if message.get_author() != Some(user) {
+            let entry = AuditEntry::new(Some(user), "message_delete", Some(id.into()));
+            self.audit(entry.with_before(message.get_content()));
+        }
+        let request = ServerEvent::new_message_tombstone(channel_id, id, self.purge_date());
+        self.send_to_channel(channel_id.into(), request).await;
+        if let Some(thread) = message.get_thread() {
+            self.send_thread_update(channel_id.into(), thread).await;
+        }
+        Ok(())
+    }
+
+    async fn on_message_restore(
+        &mut self,
+        MessageRestore { channel_id, id }: MessageRestore,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self
+            .has_server_perm(user, Permissions::MANAGE_MESSAGES)
+            .await
+        {
+            Err("missing the 'manage messages' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_message_restore(channel_id.into(), id.into());
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("no such deleted message")?;
+        }
+        let entry = AuditEntry::new(Some(user), "message_restore", Some(id.into()));
+        self.audit(entry.with_after(channel_id));
+        let request = ServerEvent::new_message_restore(channel_id, id);
+        self.send_to_channel(channel_id.into(), request).await;
+        let message = self.get_message(channel_id.into(), id.into()).await;
+        if let Some(thread) = message.and_then(|m| m.get_thread()) {
+            self.send_thread_update(channel_id.into(), thread).await;
+        }
+        Ok(())
+    }
+
+    /// date at which an item deleted now gets purged, in milliseconds since the epoch.
+    fn purge_date(&self) -> i64 {
+        chrono::Utc::now().timestamp_millis() + self.trash_grace.as_millis() as i64
+    }
+
+    async fn on_purge(&mut self) {
+        let before = chrono::Utc::now().timestamp_millis() - self.trash_grace.as_millis() as i64;
+        let (cmd, rec) = StorageCmd::new_purge(before);
+        self.storage.send(cmd).unwrap();
+        let purged = rec.await.unwrap();
+        for (channel_id, id) in purged.messages {
+            let request = ServerEvent::new_message_delete(channel_id.to_u64(), id.to_u64());
+            self.send_to_channel(channel_id, request).await;
+        }
+        for id in purged.channels {
+            let request = ServerEvent::new_channel_delete(id.to_u64());
+            self.sessions
+                .send(SessionCmd::new_broadcast(request))
+                .unwrap();
+        }
+    }
+
+    async fn on_message_get_content(
+        &mut self,
+        MessageGetContent { channel_id, id }: MessageGetContent,
+        address: Addr,
+        user: Option<Id>,
+    ) -> Result<(), String> {
+        self.get_channel(channel_id.into())
+            .await
+            .ok_or("channel not found")?;
+        let message = self.get_message(channel_id.into(), id.into()).await;
+        let (cmd, rec) = StorageCmd::new_reaction_list(channel_id.into(), id.into());
+        self.storage.send(cmd).unwrap();
+        let reactions = rec
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(emoji, users)| server::Reaction {
+                emoji,
+                count: users.len() as u32,
+                me: user.map(|user| users.contains(&user)).unwrap_or(false),
+            })
+            .collect();
+        let edits = message.as_ref().map(|m| m.get_edits()).unwrap_or(0);
+        let reply_to = message.as_ref().and_then(|m| m.get_reply_to());
+        let thread = message.as_ref().and_then(|m| m.get_thread());
+        let mentions = message
+            .as_ref()
+            .map(|m| m.get_mentions().iter().map(Id::to_u64).collect())
+            .unwrap_or_default();
+        let content = message.map(|m| m.get_content().to_string());
+        let request = ServerEvent::new_message_get_content(
+            channel_id,
+            id,
+            content,
+            edits,
+            reply_to.map(|id| id.to_u64()),
+            thread.map(|id| id.to_u64()),
+            reactions,
+            mentions,
+        );
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_message_set_content(
+        &mut self,
+        MessageSetContent {
+            channel_id,
+            id,
+            content,
+        }: MessageSetContent,
+        user: Id,
+    ) -> Result<(), String> {
+        if self.timed_out_until(user).await.is_some() {
+            Err("timed out")?;
+        }
+        let message = self
+            .get_message(channel_id.into(), id.into())
+            .await
+            .ok_or("message not found")?;
+        if message.get_author() != Some(user)
+            && !self
+                .has_channel_perm(user, channel_id.into(), Permissions::MANAGE_MESSAGES)
+                .await
+        {
+            Err("missing the 'manage messages' permission")?;
+        }
+        if mentions_everyone(&content) {
+            let permissions = self
+                .permissions(Some(user), Scope::Channel(channel_id.into()))
+                .await;
+            if !permissions.contains(Permissions::MENTION_EVERYONE) {
+                Err("missing the 'mention everyone' permission")?;
+            }
+        }
+        let notified = message.get_mentions().to_vec();
+        let (cmd, rec) = StorageCmd::new_message_set_content(
+            channel_id.into(),
+            id.into(),
+            Some(user),
+            content.clone(),
+        );
+        self.storage.send(cmd).unwrap();
+        if let Some(edits) = rec.await.unwrap() {
+            let request = ServerEvent::new_message_set_content(channel_id, id, content, edits);
+            self.send_to_channel(channel_id.into(), request).await;
+            self.send_mentions(channel_id.into(), id.into(), &notified)
+                .await;
+        }
+        Ok(())
+    }
+
+    async fn on_message_get_history(
+        &mut self,
+        MessageGetHistory { channel_id, id }: MessageGetHistory,
+        address: Addr,
+    ) -> Result<(), String> {
+        self.get_channel(channel_id.into())
+            .await
+            .ok_or("channel not found")?;
+        let (cmd, rec) = StorageCmd::new_message_get_history(channel_id.into(), id.into());
+        self.storage.send(cmd).unwrap();
+        let revisions = rec
+            .await
+            .unwrap()
+            .iter()
+            .map(|revision| server::Revision {
+                content: revision.get_content().to_string(),
+                editor_id: revision.get_editor().map(|id| id.to_u64()),
+                timestamp: revision.get_timestamp(),
+            })
+            .collect();
+        let request = ServerEvent::new_message_get_history(channel_id, id, revisions);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_message_search(
+        &mut self,
+        MessageSearch {
+            query,
+            channel_id,
+            author_id,
+            after,
+            before,
+            offset,
+            limit,
+        }: MessageSearch,
+        address: Addr,
+        user: Option<Id>,
+    ) {
+        let offset = offset.unwrap_or(0);
+        let search = SearchQuery {
+            text: query.clone(),
+            channel: channel_id.map(Id::from_u64),
+            author: author_id.map(Id::from_u64),
+            after,
+            before,
+            offset: offset as usize,
+            limit: limit.map(|l| l as usize).unwrap_or(MAX_SEARCH_LIMIT / 4),
+            viewer: user,
+        };
+        let (cmd, rec) = StorageCmd::new_message_search(search);
+        self.storage.send(cmd).unwrap();
+        let results = rec.await.unwrap();
+        let hits = results
+            .hits
+            .into_iter()
+            .map(|hit| server::SearchResult {
+                channel_id: hit.channel_id.to_u64(),
+                id: hit.id.to_u64(),
+                author_id: hit.author.map(|a| a.to_u64()),
+                highlight: hit.highlight,
+            })
+            .collect();
+        let request = ServerEvent::new_message_search(query, results.total as u32, offset, hits);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn on_message_reindex(
+        &mut self,
+        _: MessageReindex,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        if !self.has_server_perm(user, Permissions::ADMINISTRATOR).await {
+            Err("missing the 'administrator' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_message_reindex();
+        self.storage.send(cmd).unwrap();
+        let count = rec.await.unwrap() as u32;
+        self.audit(AuditEntry::new(Some(user), "message_reindex", None).with_after(count));
+        let request = ServerEvent::new_message_reindex(count);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_user_set_pass(
+        &mut self,
+        UserSetPass { id, pass, current }: UserSetPass,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        let target = Id::from_u64(id);
+        if target == user {
+            let current = current.ok_or("The current password is required")?;
+            self.verify_pass(Some(user), current, address.clone())
+                .await?;
+        } else if !self.has_server_perm(user, Permissions::MANAGE_USERS).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let command = SecurityCmd::new_store_pass(target, pass);
+        self.security.send(command).unwrap();
+        // the password itself is never recorded
+        if target != user {
+            self.audit(AuditEntry::new(Some(user), "user_set_pass", Some(target)));
+        }
+        let request = ServerEvent::new_user_set_pass(id);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_user_set_name(
+        &mut self,
+        UserSetName { id, name }: UserSetName,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self.can_manage_user(user, id.into()).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_user_get_name(id.into());
+        self.storage.send(cmd).unwrap();
+        let before = rec.await.unwrap();
+        let (cmd, rec) = StorageCmd::new_user_set_name(id.into(), name.clone());
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("Unknown user or name already taken")?;
+        }
+        if user != id.into() {
+            let entry = AuditEntry::new(Some(user), "user_set_name", Some(id.into()));
+            let entry = match before {
+                Some(before) => entry.with_before(before),
+                None => entry,
+            };
+            self.audit(entry.with_after(&name));
+        }
+        let request = ServerEvent::new_user_set_name(id, name);
+        let command = SessionCmd::new_broadcast(request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_user_get_name(&mut self, UserGetName { id }: UserGetName, address: Addr) {
+        let (cmd, rec) = StorageCmd::new_user_get_name(id.into());
+        self.storage.send(cmd).unwrap();
+        let name = rec.await.unwrap();
+        let request = ServerEvent::new_user_get_name(id, name);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn on_profile_get(
+        &mut self,
+        ProfileGet { id }: ProfileGet,
+        address: Addr,
+    ) -> Result<(), String> {
+        let (cmd, rec) = StorageCmd::new_user_get_profile(id.into());
+        self.storage.send(cmd).unwrap();
+        let profile = rec.await.unwrap().ok_or("Unknown user")?;
+        let request = ServerEvent::new_profile_get(id, profile_payload(profile));
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    /// the update reaches the users sharing a channel with the profile's owner.
+    async fn on_profile_update(
+        &mut self,
+        ProfileUpdate {
+            id,
+            display_name,
+            avatar,
+            bio,
+            custom_status,
+        }: ProfileUpdate,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self.can_manage_user(user, id.into()).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let update = Profile {
+            display_name,
+            avatar,
+            bio,
+            custom_status,
+        };
+        if !is_valid_profile(&update) {
+            Err("profile field too long")?;
+        }
+        let (cmd, rec) = StorageCmd::new_user_get_profile(id.into());
+        self.storage.send(cmd).unwrap();
+        let before = rec.await.unwrap();
+        let (cmd, rec) = StorageCmd::new_user_update_profile(id.into(), update);
+        self.storage.send(cmd).unwrap();
+        let profile = rec.await.unwrap().ok_or("Unknown user")?;
+        if user != id.into() {
+            let entry = AuditEntry::new(Some(user), "profile_update", Some(id.into()));
+            let entry = match before {
+                Some(before) => entry.with_before(serde_json::to_string(&before).unwrap()),
+                None => entry,
+            };
+            self.audit(entry.with_after(serde_json::to_string(&profile).unwrap()));
+        }
+        let (cmd, rec) = StorageCmd::new_user_profile_audience(id.into());
+        self.storage.send(cmd).unwrap();
+        let audience = rec.await.unwrap();
+        let request = ServerEvent::new_profile_update(id, profile_payload(profile));
+        self.send_to_audience(audience, request);
+        Ok(())
+    }
+
+    async fn on_presence_set(
+        &mut self,
+        PresenceSet { status }: PresenceSet,
+        user: Id,
+    ) -> Result<(), String> {
+        let status = Presence::from_name(&status).ok_or("unknown status")?;
+        let command = SessionCmd::new_set_status(user, status);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_presence_get(
+        &mut self,
+        PresenceGet { ids }: PresenceGet,
+        address: Addr,
+    ) -> Result<(), String> {
+        if ids.len() > MAX_PRESENCE_QUERY {
+            Err("too many users")?;
+        }
+        let (cmd, rec) = SessionCmd::new_get_presences(ids.iter().map(|&id| id.into()).collect());
+        self.sessions.send(cmd).unwrap();
+        let presences = rec.await.unwrap();
+        let presences = ids
+            .into_iter()
+            .zip(presences)
+            .map(|(id, presence)| server::UserPresence {
+                id,
+                status: presence.name().into(),
+            })
+            .collect();
+        let request = ServerEvent::new_presence_get(presences);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_user_delete(
+        &mut self,
+        UserDelete { id }: UserDelete,
+        user: Id,
+    ) -> Result<(), String> {
+        if !self.can_manage_user(user, id.into()).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_user_get_name(id.into());
+        self.storage.send(cmd).unwrap();
+        let before = rec.await.unwrap();
+        let (cmd, rec) = StorageCmd::new_user_delete(id.into(), self.purge_user_messages);
+        self.storage.send(cmd).unwrap();
+        let removed = rec.await.unwrap();
+        if user != id.into() {
+            let entry = AuditEntry::new(Some(user), "user_delete", Some(id.into()));
+            self.audit(match before {
+                Some(before) => entry.with_before(before),
+                None => entry,
+            });
+        }
+        let command = SessionCmd::new_logout(id.into());
+        self.sessions.send(command).unwrap();
+        for (channel_id, message_id) in removed {
+            let request = ServerEvent::new_message_delete(channel_id.into(), message_id.into());
+            self.send_to_channel(channel_id, request).await;
+        }
+        let request = ServerEvent::new_user_delete(id);
+        let command = SessionCmd::new_broadcast(request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_user_create(
+        &mut self,
+        UserCreate { name, pass, invite }: UserCreate,
+        user: Option<Id>,
+    ) -> Result<(), String> {
+        let is_op = match user {
+            Some(user) => self.has_server_perm(user, Permissions::MANAGE_USERS).await,
+            None => false,
+        };
+        let invite = match self.registration {
+            RegistrationPolicy::Open => None,
+            RegistrationPolicy::InviteOnly | RegistrationPolicy::OperatorOnly if is_op => None,
+            RegistrationPolicy::InviteOnly => Some(invite.ok_or("An invite is required")?),
+            RegistrationPolicy::OperatorOnly => Err("Registration is restricted to operators")?,
+            RegistrationPolicy::Closed => Err("Registration is closed")?,
+        };
+
+        let (cmd, rec) = StorageCmd::new_user_find_by_name(name.clone());
+        self.storage.send(cmd).unwrap();
+        if rec.await.unwrap().is_some() {
+            Err("Name already taken")?;
+        }
+        if let Some(code) = invite {
+            let (cmd, rec) = StorageCmd::new_invite_use(code, None);
+            self.storage.send(cmd).unwrap();
+            if !rec.await.unwrap() {
+                Err("Invalid invite")?;
+            }
+        }
+
+        let (cmd, rec) = StorageCmd::new_user_create(name.clone(), String::new());
+        self.storage.send(cmd).unwrap();
+        let id = rec.await.unwrap().ok_or("Name already taken")?;
+        let command = SecurityCmd::new_store_pass(id, pass);
+        self.security.send(command).unwrap();
+        // operators bypass the registration policy
+        if is_op {
+            self.audit(AuditEntry::new(user, "user_create", Some(id)).with_after(&name));
+        }
         let request = ServerEvent::new_user_create(id.into(), name);
         let command = SessionCmd::new_broadcast(request);
         self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_user_find_by_name(
+        &mut self,
+        UserFindByName { name }: UserFindByName,
+        address: Addr,
+    ) {
+        let (cmd, rec) = StorageCmd::new_user_find_by_name(name.clone());
+        self.storage.send(cmd).unwrap();
+        let id = rec.await.unwrap().map(|id| id.to_u64());
+        let request = ServerEvent::new_user_find_by_name(name, id);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn reaction_users(&mut self, channel_id: u64, id: u64, emoji: String) -> Vec<Id> {
+        let (cmd, rec) = StorageCmd::new_reaction_list_users(channel_id.into(), id.into(), emoji);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap()
+    }
+
+    async fn on_reaction_add(
+        &mut self,
+        ReactionAdd {
+            channel_id,
+            id,
+            emoji,
+        }: ReactionAdd,
+        user: Id,
+    ) -> Result<(), String> {
+        if !is_valid_emoji(&emoji) {
+            Err("invalid emoji")?;
+        }
+        self.get_message(channel_id.into(), id.into())
+            .await
+            .ok_or("message not found")?;
+        let (cmd, rec) =
+            StorageCmd::new_reaction_add(channel_id.into(), id.into(), emoji.clone(), user);
+        self.storage.send(cmd).unwrap();
+        if rec.await.unwrap() {
+            let count = self
+                .reaction_users(channel_id, id, emoji.clone())
+                .await
+                .len();
+            let request =
+                ServerEvent::new_reaction_add(channel_id, id, emoji, user.to_u64(), count as u32);
+            self.send_to_channel(channel_id.into(), request).await;
+        }
+        Ok(())
+    }
+
+    async fn on_reaction_remove(
+        &mut self,
+        ReactionRemove {
+            channel_id,
+            id,
+            emoji,
+        }: ReactionRemove,
+        user: Id,
+    ) -> Result<(), String> {
+        if !is_valid_emoji(&emoji) {
+            Err("invalid emoji")?;
+        }
+        let (cmd, rec) =
+            StorageCmd::new_reaction_remove(channel_id.into(), id.into(), emoji.clone(), user);
+        self.storage.send(cmd).unwrap();
+        if rec.await.unwrap() {
+            let count = self
+                .reaction_users(channel_id, id, emoji.clone())
+                .await
+                .len();
+            let request = ServerEvent::new_reaction_remove(
+                channel_id,
+                id,
+                emoji,
+                user.to_u64(),
+                count as u32,
+            );
+            self.send_to_channel(channel_id.into(), request).await;
+        }
+        Ok(())
+    }
+
+    async fn on_reaction_list_users(
+        &mut self,
+        ReactionListUsers {
+            channel_id,
+            id,
+            emoji,
+        }: ReactionListUsers,
+        address: Addr,
+    ) -> Result<(), String> {
+        self.get_channel(channel_id.into())
+            .await
+            .ok_or("channel not found")?;
+        let users = match is_valid_emoji(&emoji) {
+            true => self.reaction_users(channel_id, id, emoji.clone()).await,
+            false => Vec::new(),
+        };
+        let users = users.iter().map(|id| id.to_u64()).collect();
+        let request = ServerEvent::new_reaction_list_users(channel_id, id, emoji, users);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn get_thread(&mut self, channel_id: Id, id: Id) -> Result<Message, String> {
+        let parent = self.get_message(channel_id, id).await;
+        match parent {
+            Some(parent) if parent.get_thread().is_none() => Ok(parent),
+            _ => Err("thread not found".to_string()),
+        }
+    }
+
+    async fn on_thread_message_list(
+        &mut self,
+        ThreadMessageList {
+            channel_id,
+            thread_id,
+            before,
+            limit,
+        }: ThreadMessageList,
+        address: Addr,
+    ) -> Result<(), String> {
+        let parent = self.get_thread(channel_id.into(), thread_id.into()).await?;
+        let limit = limit.map(|l| l as usize).unwrap_or(DEFAULT_THREAD_PAGE);
+        let (cmd, rec) = StorageCmd::new_thread_message_list(
+            channel_id.into(),
+            thread_id.into(),
+            before.map(Id::from_u64),
+            limit,
+        );
+        self.storage.send(cmd).unwrap();
+        let messages = rec.await.unwrap().iter().map(|id| id.to_u64()).collect();
+        let request = ServerEvent::new_thread_message_list(
+            channel_id,
+            thread_id,
+            parent.get_replies(),
+            parent.get_last_activity(),
+            messages,
+        );
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_thread_subscribe(
+        &mut self,
+        ThreadSubscribe {
+            channel_id,
+            thread_id,
+        }: ThreadSubscribe,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        self.get_thread(channel_id.into(), thread_id.into()).await?;
+        let command = StorageCmd::new_thread_subscribe(channel_id.into(), thread_id.into(), user);
+        self.storage.send(command).unwrap();
+        let request = ServerEvent::new_thread_subscribe(channel_id, thread_id);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_thread_unsubscribe(
+        &mut self,
+        ThreadUnsubscribe {
+            channel_id,
+            thread_id,
+        }: ThreadUnsubscribe,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        let command = StorageCmd::new_thread_unsubscribe(channel_id.into(), thread_id.into(), user);
+        self.storage.send(command).unwrap();
+        let request = ServerEvent::new_thread_unsubscribe(channel_id, thread_id);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_direct_open(
+        &mut self,
+        DirectOpen { users }: DirectOpen,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        let mut participants = users.into_iter().map(Id::from_u64).collect::<Vec<_>>();
+        participants.push(user);
+        participants.sort();
+        participants.dedup();
+        if participants.len() < 2 {
+            Err("a direct channel needs another participant")?;
+        }
+        if participants.len() > self.max_direct_participants {
+            Err("too many participants")?;
+        }
+        for participant in &participants {
+            let (cmd, rec) = StorageCmd::new_user_get_name(*participant);
+            self.storage.send(cmd).unwrap();
+            rec.await.unwrap().ok_or("user not found")?;
+        }
+        let (cmd, rec) = StorageCmd::new_direct_open(participants.clone());
+        self.storage.send(cmd).unwrap();
+        let (id, created) = rec.await.unwrap();
+        let ids = participants.iter().map(Id::to_u64).collect();
+        let request = ServerEvent::new_direct_open(id.to_u64(), ids);
+        let command = match created {
+            true => SessionCmd::new_send_to(participants, request),
+            false => SessionCmd::new_send(address, request),
+        };
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_direct_list(&mut self, _: DirectList, user: Id, address: Addr) {
+        let (cmd, rec) = StorageCmd::new_direct_list(user);
+        self.storage.send(cmd).unwrap();
+        let mut channels = Vec::new();
+        for id in rec.await.unwrap() {
+            if let Some(channel) = self.get_channel(id).await {
+                channels.push(server::DirectChannel {
+                    id: id.to_u64(),
+                    participants: channel.get_participants().iter().map(Id::to_u64).collect(),
+                });
+            }
+        }
+        let request = ServerEvent::new_direct_list(channels);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn on_mentions_list(
+        &mut self,
+        MentionsList { after, limit }: MentionsList,
+        user: Id,
+        address: Addr,
+    ) {
+        let limit = limit.map(|l| l as usize).unwrap_or(DEFAULT_MENTIONS_PAGE);
+        let (cmd, rec) = StorageCmd::new_mention_list(user, after.map(Id::from_u64), limit);
+        self.storage.send(cmd).unwrap();
+        let mentions = rec
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(channel_id, id)| server::MentionRef {
+                channel_id: channel_id.to_u64(),
+                id: id.to_u64(),
+            })
+            .collect();
+        let request = ServerEvent::new_mentions_list(mentions);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    /// the acknowledgement is echoed to every session of the user so that other devices catch up.
+    async fn on_channel_ack(
+        &mut self,
+        ChannelAck { channel_id, id }: ChannelAck,
+        user: Id,
+    ) -> Result<(), String> {
+        self.get_message(channel_id.into(), id.into())
+            .await
+            .ok_or("message not found")?;
+        let command = StorageCmd::new_channel_ack(user, channel_id.into(), id.into());
+        self.storage.send(command).unwrap();
+        let request = ServerEvent::new_channel_ack(channel_id, id);
+        let command = SessionCmd::new_send_to(vec![user], request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_read_state_list(&mut self, _: ReadStateList, user: Id, address: Addr) {
+        let (cmd, rec) = StorageCmd::new_read_state_list(user);
+        self.storage.send(cmd).unwrap();
+        let states = rec
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|state| server::ReadState {
+                channel_id: state.channel.to_u64(),
+                last_read: state.last_read.map(|id| id.to_u64()),
+                unread: state.unread as u32,
+                mentions: state.mentions as u32,
+            })
+            .collect();
+        let request = ServerEvent::new_read_state_list(states);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    /// roles can only be edited by users holding every permission they grant.
+    async fn check_grantable(
+        &mut self,
+        user: Id,
+        scope: Scope,
+        granted: Permissions,
+    ) -> Result<(), String> {
+        let permissions = self.permissions(Some(user), scope).await;
+        if !permissions.contains(Permissions::MANAGE_ROLES) {
+            Err("missing the 'manage roles' permission")?;
+        }
+        if !permissions.contains(granted) {
+            Err("can not grant permissions one does not have")?;
+        }
+        Ok(())
+    }
+
+    async fn get_role(&mut self, id: Id) -> Result<Role, String> {
+        let (cmd, rec) = StorageCmd::new_role_get(id);
+        self.storage.send(cmd).unwrap();
+        Ok(rec.await.unwrap().ok_or("role not found")?)
+    }
+
+    async fn on_role_create(
+        &mut self,
+        RoleCreate {
+            name,
+            permissions,
+            guild_id,
+        }: RoleCreate,
+        user: Id,
+    ) -> Result<(), String> {
+        let guild = guild_id.map_or(Guild::DEFAULT, Id::from_u64);
+        if !self.is_guild_member(Some(user), guild).await {
+            Err("not a member of this guild")?;
+        }
+        let permissions = Permissions::from_bits(permissions);
+        self.check_grantable(user, Scope::Guild(guild), permissions)
+            .await?;
+        let (cmd, rec) = StorageCmd::new_role_create(guild, name.clone(), permissions);
+        self.storage.send(cmd).unwrap();
+        let id = rec.await.unwrap();
+        let entry = AuditEntry::new(Some(user), "role_create", Some(id));
+        self.audit(entry.with_after(format!("{name} {}", permissions.bits())));
+        let request =
+            ServerEvent::new_role_create(id.to_u64(), name, permissions.bits(), guild.to_u64());
+        self.send_to_guild(guild, request).await;
+        Ok(())
+    }
+
+    async fn on_role_update(
+        &mut self,
+        RoleUpdate {
+            id,
+            name,
+            permissions,
+        }: RoleUpdate,
+        user: Id,
+    ) -> Result<(), String> {
+        let permissions = Permissions::from_bits(permissions);
+        let role = self.get_role(id.into()).await?;
+        let changed = role.get_permissions().union(permissions);
+        let guild = role.get_guild();
+        self.check_grantable(user, Scope::Guild(guild), changed)
+            .await?;
+        let (cmd, rec) = StorageCmd::new_role_update(id.into(), name.clone(), permissions);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap();
+        let entry = AuditEntry::new(Some(user), "role_update", Some(id.into()))
+            .with_before(format!(
+                "{} {}",
+                role.get_name(),
+                role.get_permissions().bits()
+            ))
+            .with_after(format!("{name} {}", permissions.bits()));
+        self.audit(entry);
+        let request = ServerEvent::new_role_update(id, name, permissions.bits());
+        self.send_to_guild(guild, request).await;
+        Ok(())
+    }
+
+    async fn on_role_delete(
+        &mut self,
+        RoleDelete { id }: RoleDelete,
+        user: Id,
+    ) -> Result<(), String> {
+        let role = self.get_role(id.into()).await?;
+        let guild = role.get_guild();
+        self.check_grantable(user, Scope::Guild(guild), role.get_permissions())
+            .await?;
+        let (cmd, rec) = StorageCmd::new_role_delete(id.into());
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("the everyone role can not be deleted")?;
+        }
+        let entry = AuditEntry::new(Some(user), "role_delete", Some(id.into())).with_before(
+            format!("{} {}", role.get_name(), role.get_permissions().bits()),
+        );
+        self.audit(entry);
+        let request = ServerEvent::new_role_delete(id);
+        self.send_to_guild(guild, request).await;
+        Ok(())
+    }
+
+    async fn on_role_list(
+        &mut self,
+        RoleList { guild_id }: RoleList,
+        address: Addr,
+        user: Option<Id>,
+    ) -> Result<(), String> {
+        let guild = guild_id.map_or(Guild::DEFAULT, Id::from_u64);
+        if !self.is_guild_member(user, guild).await {
+            Err("not a member of this guild")?;
+        }
+        let (cmd, rec) = StorageCmd::new_role_list(guild);
+        self.storage.send(cmd).unwrap();
+        let roles = rec
+            .await
+            .unwrap()
+            .iter()
+            .map(|role| server::Role {
+                id: role.get_id().to_u64(),
+                name: role.get_name().to_string(),
+                permissions: role.get_permissions().bits(),
+            })
+            .collect();
+        let request = ServerEvent::new_role_list(guild.to_u64(), roles);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_role_assign(
+        &mut self,
+        role_id: u64,
+        user_id: u64,
+        assigned: bool,
+        user: Id,
+    ) -> Result<(), String> {
+        let role = self.get_role(role_id.into()).await?;
+        let guild = role.get_guild();
+        self.check_grantable(user, Scope::Guild(guild), role.get_permissions())
+            .await?;
+        let (cmd, rec) = StorageCmd::new_user_get_name(user_id.into());
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap().ok_or("user not found")?;
+        if assigned && !self.is_guild_member(Some(user_id.into()), guild).await {
+            Err("the user is not a member of the role's guild")?;
+        }
+        let (cmd, rec) = StorageCmd::new_role_assign(role_id.into(), user_id.into(), assigned);
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            return Ok(());
+        }
+        let entry = match assigned {
+            true => {
+                AuditEntry::new(Some(user), "role_assign", Some(role_id.into())).with_after(user_id)
+            }
+            false => AuditEntry::new(Some(user), "role_unassign", Some(role_id.into()))
+                .with_before(user_id),
+        };
+        self.audit(entry);
+        let request = match assigned {
+            true => ServerEvent::new_role_assign(role_id, user_id),
+            false => ServerEvent::new_role_unassign(role_id, user_id),
+        };
+        self.send_to_guild(guild, request).await;
+        Ok(())
+    }
+
+    async fn on_user_list_roles(&mut self, UserListRoles { id }: UserListRoles, address: Addr) {
+        let (cmd, rec) = StorageCmd::new_user_list_roles(id.into());
+        self.storage.send(cmd).unwrap();
+        let roles = rec.await.unwrap().iter().map(Id::to_u64).collect();
+        let request = ServerEvent::new_user_list_roles(id, roles);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn set_overwrite(
+        &mut self,
+        channel_id: Id,
+        target: OverwriteTarget,
+        overwrite: Overwrite,
+        user: Id,
+    ) -> Result<(), String> {
+        let changed = overwrite.allow.union(overwrite.deny);
+        self.check_grantable(user, Scope::Channel(channel_id), changed)
+            .await?;
+        let (action, subject) = match target {
+            OverwriteTarget::Role(id) => ("channel_set_role_overwrite", id),
+            OverwriteTarget::User(id) => ("channel_set_user_overwrite", id),
+        };
+        let after = format!(
+            "{subject} {} {}",
+            overwrite.allow.bits(),
+            overwrite.deny.bits()
+        );
+        let command = StorageCmd::new_overwrite_set(channel_id, target, overwrite);
+        self.storage.send(command).unwrap();
+        self.audit(AuditEntry::new(Some(user), action, Some(channel_id)).with_after(after));
+        Ok(())
+    }
+
+    async fn on_channel_set_role_overwrite(
+        &mut self,
+        ChannelSetRoleOverwrite {
+            channel_id,
+            role_id,
+            allow,
+            deny,
+        }: ChannelSetRoleOverwrite,
+        user: Id,
+    ) -> Result<(), String> {
+        let role = self.get_role(role_id.into()).await?;
+        if role.get_guild() != self.guild_of(channel_id.into()).await? {
+            Err("the role belongs to another guild")?;
+        }
+        let overwrite = Overwrite {
+            allow: Permissions::from_bits(allow),
+            deny: Permissions::from_bits(deny),
+        };
+        let target = OverwriteTarget::Role(role_id.into());
+        self.set_overwrite(channel_id.into(), target, overwrite, user)
+            .await?;
+        let request = ServerEvent::new_channel_set_role_overwrite(
+            channel_id,
+            role_id,
+            overwrite.allow.bits(),
+            overwrite.deny.bits(),
+        );
+        self.send_to_channel(channel_id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_channel_set_user_overwrite(
+        &mut self,
+        ChannelSetUserOverwrite {
+            channel_id,
+            user_id,
+            allow,
+            deny,
+        }: ChannelSetUserOverwrite,
+        user: Id,
+    ) -> Result<(), String> {
+        let (cmd, rec) = StorageCmd::new_user_get_name(user_id.into());
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap().ok_or("user not found")?;
+        let overwrite = Overwrite {
+            allow: Permissions::from_bits(allow),
+            deny: Permissions::from_bits(deny),
+        };
+        let target = OverwriteTarget::User(user_id.into());
+        self.set_overwrite(channel_id.into(), target, overwrite, user)
+            .await?;
+        let request = ServerEvent::new_channel_set_user_overwrite(
+            channel_id,
+            user_id,
+            overwrite.allow.bits(),
+            overwrite.deny.bits(),
+        );
+        self.send_to_channel(channel_id.into(), request).await;
+        Ok(())
+    }
+
+    async fn on_channel_list_overwrites(
+        &mut self,
+        ChannelListOverwrites { channel_id }: ChannelListOverwrites,
+        address: Addr,
+    ) {
+        let (cmd, rec) = StorageCmd::new_overwrite_list(channel_id.into());
+        self.storage.send(cmd).unwrap();
+        let (mut roles, mut users) = (Vec::new(), Vec::new());
+        for (target, overwrite) in rec.await.unwrap() {
+            let (list, id) = match target {
+                OverwriteTarget::Role(id) => (&mut roles, id),
+                OverwriteTarget::User(id) => (&mut users, id),
+            };
+            list.push(server::Overwrite {
+                id: id.to_u64(),
+                allow: overwrite.allow.bits(),
+                deny: overwrite.deny.bits(),
+            });
+        }
+        let request = ServerEvent::new_channel_list_overwrites(channel_id, roles, users);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn on_permissions_get(
+        &mut self,
+        PermissionsGet { channel_id }: PermissionsGet,
+        address: Addr,
+        user: Option<Id>,
+    ) {
+        let scope = channel_id.map_or(Scope::Server, |id| Scope::Channel(id.into()));
+        let permissions = self.permissions(user, scope).await;
+        let request = ServerEvent::new_permissions_get(channel_id, permissions.bits());
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn on_guild_create(
+        &mut self,
+        GuildCreate { name }: GuildCreate,
+        user: Id,
+        address: Addr,
+    ) {
+        let (cmd, rec) = StorageCmd::new_guild_create(name.clone(), user);
+        self.storage.send(cmd).unwrap();
+        let id = rec.await.unwrap();
+        self.audit(AuditEntry::new(Some(user), "guild_create", Some(id)).with_after(&name));
+        let request = ServerEvent::new_guild_create(id.to_u64(), name, Some(user.to_u64()));
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    async fn get_guild(&mut self, id: Id) -> Result<Guild, String> {
+        let (cmd, rec) = StorageCmd::new_guild_get(id);
+        self.storage.send(cmd).unwrap();
+        Ok(rec.await.unwrap().ok_or("guild not found")?)
+    }
+
+    /// whether the user owns the guild or administrates the server.
+    async fn owns_guild(&mut self, user: Id, guild: &Guild) -> bool {
+        guild.get_owner() == Some(user)
+            || self.has_server_perm(user, Permissions::ADMINISTRATOR).await
+    }
+
+    async fn on_guild_delete(
+        &mut self,
+        GuildDelete { id }: GuildDelete,
+        user: Id,
+    ) -> Result<(), String> {
+        let guild = self.get_guild(id.into()).await?;
+        if !self.owns_guild(user, &guild).await {
+            Err("only the owner can delete a guild")?;
+        }
+        let (cmd, rec) = StorageCmd::new_guild_member_list(id.into());
+        self.storage.send(cmd).unwrap();
+        let members = rec.await.unwrap();
+        let (cmd, rec) = StorageCmd::new_guild_delete(id.into());
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("the default guild can not be deleted")?;
+        }
+        let entry = AuditEntry::new(Some(user), "guild_delete", Some(id.into()));
+        self.audit(entry.with_before(guild.get_name()));
+        let request = ServerEvent::new_guild_delete(id);
+        self.send_to_audience(Some(members), request);
+        Ok(())
+    }
+
+    async fn on_guild_list(&mut self, _: GuildList, user: Id, address: Addr) {
+        let (cmd, rec) = StorageCmd::new_guild_list(user);
+        self.storage.send(cmd).unwrap();
+        let mut guilds = Vec::new();
+        for id in rec.await.unwrap() {
+            if let Ok(guild) = self.get_guild(id).await {
+                guilds.push(server::GuildInfo {
+                    id: id.to_u64(),
+                    name: guild.get_name().to_string(),
+                    owner: guild.get_owner().map(|id| id.to_u64()),
+                });
+            }
+        }
+        let request = ServerEvent::new_guild_list(guilds);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+    }
+
+    /// members can leave on their own, the owner of the guild excepted.
+    async fn on_guild_member_set(
+        &mut self,
+        id: u64,
+        user_id: u64,
+        member: bool,
+        user: Id,
+    ) -> Result<(), String> {
+        let guild = self.get_guild(id.into()).await?;
+        let target = Id::from_u64(user_id);
+        let leaving = !member && user == target;
+        if !leaving
+            && !self
+                .has_guild_perm(user, id.into(), Permissions::MANAGE_USERS)
+                .await
+        {
+            Err("missing the 'manage users' permission")?;
+        }
+        if !member && guild.get_owner() == Some(target) {
+            Err("the owner can not leave the guild")?;
+        }
+        let (cmd, rec) = StorageCmd::new_user_get_name(target);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap().ok_or("user not found")?;
+        let mut audience = {
+            let (cmd, rec) = StorageCmd::new_guild_member_list(id.into());
+            self.storage.send(cmd).unwrap();
+            rec.await.unwrap()
+        };
+        let (cmd, rec) = StorageCmd::new_guild_member_set(id.into(), target, member);
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            return Ok(());
+        }
+        // leaving a guild is not a privileged action
+        if !leaving {
+            let entry = match member {
+                true => AuditEntry::new(Some(user), "guild_member_add", Some(id.into()))
+                    .with_after(user_id),
+                false => AuditEntry::new(Some(user), "guild_member_remove", Some(id.into()))
+                    .with_before(user_id),
+            };
+            self.audit(entry);
+        }
+        let request = match member {
+            true => ServerEvent::new_guild_member_add(id, user_id),
+            false => ServerEvent::new_guild_member_remove(id, user_id),
+        };
+        if member {
+            audience.push(target);
+        }
+        self.send_to_audience(Some(audience), request);
+        Ok(())
+    }
+
+    async fn on_guild_member_list(
+        &mut self,
+        GuildMemberList { id }: GuildMemberList,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        if !self.is_guild_member(Some(user), id.into()).await {
+            Err("not a member of this guild")?;
+        }
+        let (cmd, rec) = StorageCmd::new_guild_member_list(id.into());
+        self.storage.send(cmd).unwrap();
+        let members = rec.await.unwrap().iter().map(Id::to_u64).collect();
+        let request = ServerEvent::new_guild_member_list(id, members);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_guild_set_op(
+        &mut self,
+        GuildSetOp { id, user_id, op }: GuildSetOp,
+        user: Id,
+    ) -> Result<(), String> {
+        let guild = self.get_guild(id.into()).await?;
+        if !self.owns_guild(user, &guild).await {
+            Err("only the owner can choose the operators of a guild")?;
+        }
+        if guild.get_owner() == Some(user_id.into()) {
+            Err("the owner is always an operator")?;
+        }
+        let (cmd, rec) = StorageCmd::new_guild_set_op(id.into(), user_id.into(), op);
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("the user is not a member of this guild")?;
+        }
+        let entry = AuditEntry::new(Some(user), "guild_set_op", Some(user_id.into()))
+            .with_before(format!("{id} {}", !op))
+            .with_after(format!("{id} {op}"));
+        self.audit(entry);
+        let request = ServerEvent::new_guild_set_op(id, user_id, op);
+        self.send_to_guild(id.into(), request).await;
+        Ok(())
     }
 
     async fn on_user_list(&mut self, _: UserList, address: Addr) {
@@ -255,6 +2609,392 @@ impl GatewayProc {
         let command = SessionCmd::new_send(address, request);
         self.sessions.send(command).unwrap();
     }
+
+    async fn on_lockout_list(
+        &mut self,
+        _: LockoutList,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        if !self.has_server_perm(user, Permissions::MANAGE_USERS).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_lockout_list();
+        self.storage.send(cmd).unwrap();
+        let lockouts = rec
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|lockout| {
+                let (user, host) = match lockout.get_target() {
+                    LockTarget::User(id) => (Some(id.to_u64()), None),
+                    LockTarget::Host(host) => (None, Some(host.clone())),
+                };
+                server::Lockout {
+                    id: lockout.get_id().to_u64(),
+                    user,
+                    host,
+                    failures: lockout.get_failures(),
+                    until: lockout.get_until(),
+                }
+            })
+            .collect();
+        let request = ServerEvent::new_lockout_list(lockouts);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_invite_create(
+        &mut self,
+        InviteCreate {
+            max_uses,
+            expires_in,
+            guild_id,
+        }: InviteCreate,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        let guild = guild_id.map(Id::from_u64);
+        if guild == Some(Guild::DEFAULT) {
+            Err("every user is a member of the default guild")?;
+        }
+        if let Some(guild) = guild {
+            self.get_guild(guild).await?;
+        }
+        if !self.can_manage_invites(user, guild).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        let expires = expires_in
+            .map(|seconds| seconds_after(now, seconds).ok_or("duration out of range"))
+            .transpose()?;
+        let mut invite = Invite::new(user, max_uses, expires);
+        if let Some(guild) = guild {
+            invite = invite.for_guild(guild);
+        }
+        let entry = AuditEntry::new(Some(user), "invite_create", guild);
+        let entry = entry.with_after(invite.get_code());
+        let request = ServerEvent::new_invite_create(invite_payload(&invite));
+        let command = StorageCmd::new_invite_create(invite);
+        self.storage.send(command).unwrap();
+        self.audit(entry);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    /// registration invites are managed server-wide, the others in their guild.
+    async fn can_manage_invites(&mut self, user: Id, guild: Option<Id>) -> bool {
+        let guild = guild.unwrap_or(Guild::DEFAULT);
+        self.has_guild_perm(user, guild, Permissions::MANAGE_USERS)
+            .await
+    }
+
+    async fn on_invite_list(
+        &mut self,
+        InviteList { guild_id }: InviteList,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        let guild = guild_id.map(Id::from_u64);
+        if !self.can_manage_invites(user, guild).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_invite_list(guild);
+        self.storage.send(cmd).unwrap();
+        let invites = rec.await.unwrap().iter().map(invite_payload).collect();
+        let request = ServerEvent::new_invite_list(invites);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_invite_revoke(
+        &mut self,
+        InviteRevoke { code }: InviteRevoke,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        let (cmd, rec) = StorageCmd::new_invite_get(code.clone());
+        self.storage.send(cmd).unwrap();
+        let invite = rec.await.unwrap().ok_or("Unknown invite")?;
+        if invite.get_creator() != user && !self.can_manage_invites(user, invite.get_guild()).await
+        {
+            Err("missing the 'manage users' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_invite_revoke(code.clone());
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("Unknown invite")?;
+        }
+        let entry = AuditEntry::new(Some(user), "invite_revoke", invite.get_guild());
+        self.audit(entry.with_before(&code));
+        let request = ServerEvent::new_invite_revoke(code);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_invite_use(
+        &mut self,
+        InviteUse { code }: InviteUse,
+        user: Id,
+    ) -> Result<(), String> {
+        let (cmd, rec) = StorageCmd::new_invite_get(code.clone());
+        self.storage.send(cmd).unwrap();
+        let invite = rec.await.unwrap().ok_or("Unknown invite")?;
+        let guild = invite.get_guild().ok_or("not a guild invite")?;
+        if self.is_guild_member(Some(user), guild).await {
+            Err("already a member of this guild")?;
+        }
+        let (cmd, rec) = StorageCmd::new_invite_use(code, Some(guild));
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("Invalid invite")?;
+        }
+        let (cmd, rec) = StorageCmd::new_guild_member_set(guild, user, true);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap();
+        let request = ServerEvent::new_guild_member_add(guild.to_u64(), user.to_u64());
+        self.send_to_guild(guild, request).await;
+        Ok(())
+    }
+
+    /// moderators need the 'manage users' permission and may act neither on themselves nor on
+    /// server operators.
+    async fn check_moderation(&mut self, user: Id, target: Id) -> Result<(), String> {
+        if !self.has_server_perm(user, Permissions::MANAGE_USERS).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        if user == target {
+            Err("can not moderate oneself")?;
+        }
+        let (cmd, rec) = StorageCmd::new_user_get_name(target);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap().ok_or("Unknown user")?;
+        if self
+            .has_server_perm(target, Permissions::ADMINISTRATOR)
+            .await
+        {
+            Err("can not moderate a server operator")?;
+        }
+        Ok(())
+    }
+
+    async fn timed_out_until(&mut self, user: Id) -> Option<i64> {
+        let (cmd, rec) = StorageCmd::new_timeout_get(user);
+        self.storage.send(cmd).unwrap();
+        rec.await.unwrap()
+    }
+
+    async fn on_ban_create(
+        &mut self,
+        BanCreate {
+            id,
+            reason,
+            duration,
+            purge_secs,
+        }: BanCreate,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        self.check_moderation(user, id.into()).await?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let until = duration
+            .map(|seconds| seconds_after(now, seconds).ok_or("duration out of range"))
+            .transpose()?;
+        let purge_since = purge_secs
+            .map(|seconds| seconds_before(now, seconds).ok_or("duration out of range"))
+            .transpose()?;
+        let ban = Ban::new(id.into(), user, reason.clone().unwrap_or_default(), until);
+        let payload = ban_payload(&ban);
+        let entry =
+            AuditEntry::new(Some(user), "ban_create", Some(id.into())).with_after(ban.get_reason());
+        let (cmd, rec) = StorageCmd::new_ban_create(ban, purge_since);
+        self.storage.send(cmd).unwrap();
+        let trashed = rec.await.unwrap();
+        self.audit(entry);
+        for (channel_id, message_id) in trashed {
+            let request = ServerEvent::new_message_delete(channel_id.into(), message_id.into());
+            self.send_to_channel(channel_id, request).await;
+        }
+        let request = ServerEvent::new_kicked(reason, Some(payload.clone()));
+        let command = SessionCmd::new_kick(id.into(), request);
+        self.sessions.send(command).unwrap();
+        let request = ServerEvent::new_ban_create(payload);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_ban_remove(
+        &mut self,
+        BanRemove { id }: BanRemove,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        if !self.has_server_perm(user, Permissions::MANAGE_USERS).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_ban_remove(id.into());
+        self.storage.send(cmd).unwrap();
+        if !rec.await.unwrap() {
+            Err("not banned")?;
+        }
+        self.audit(AuditEntry::new(Some(user), "ban_remove", Some(id.into())));
+        let request = ServerEvent::new_ban_remove(id);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_ban_list(&mut self, _: BanList, user: Id, address: Addr) -> Result<(), String> {
+        if !self.has_server_perm(user, Permissions::MANAGE_USERS).await {
+            Err("missing the 'manage users' permission")?;
+        }
+        let (cmd, rec) = StorageCmd::new_ban_list();
+        self.storage.send(cmd).unwrap();
+        let bans = rec.await.unwrap().iter().map(ban_payload).collect();
+        let request = ServerEvent::new_ban_list(bans);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_user_kick(
+        &mut self,
+        UserKick { id, reason }: UserKick,
+        user: Id,
+    ) -> Result<(), String> {
+        self.check_moderation(user, id.into()).await?;
+        let entry = AuditEntry::new(Some(user), "user_kick", Some(id.into()));
+        let entry = match &reason {
+            Some(reason) => entry.with_after(reason),
+            None => entry,
+        };
+        let request = ServerEvent::new_kicked(reason, None);
+        let command = SessionCmd::new_kick(id.into(), request);
+        self.sessions.send(command).unwrap();
+        self.audit(entry);
+        Ok(())
+    }
+
+    /// both the timed out user and the moderator are told when the timeout ends.
+    async fn on_user_timeout(
+        &mut self,
+        UserTimeout { id, duration }: UserTimeout,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        self.check_moderation(user, id.into()).await?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let until = match duration {
+            0 => None,
+            _ => Some(seconds_after(now, duration).ok_or("duration out of range")?),
+        };
+        let command = StorageCmd::new_timeout_set(id.into(), until);
+        self.storage.send(command).unwrap();
+        let entry = AuditEntry::new(Some(user), "user_timeout", Some(id.into()));
+        self.audit(match until {
+            Some(until) => entry.with_after(until),
+            None => entry,
+        });
+        let request = ServerEvent::new_user_timeout(id, until);
+        let command = SessionCmd::new_send_to(vec![id.into()], request);
+        self.sessions.send(command).unwrap();
+        let request = ServerEvent::new_user_timeout(id, until);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+
+    async fn on_audit_log_list(
+        &mut self,
+        AuditLogList {
+            before,
+            limit,
+            actor_id,
+            action,
+            target_id,
+        }: AuditLogList,
+        user: Id,
+        address: Addr,
+    ) -> Result<(), String> {
+        if !self.has_server_perm(user, Permissions::ADMINISTRATOR).await {
+            Err("missing the 'administrator' permission")?;
+        }
+        let filter = AuditFilter {
+            actor: actor_id.map(Id::from_u64),
+            action,
+            target: target_id.map(Id::from_u64),
+        };
+        let before = before.map(Id::from_u64);
+        let limit = (limit.unwrap_or(50) as usize).min(MAX_AUDIT_PAGE);
+        let (cmd, rec) = StorageCmd::new_audit_list(filter, before, limit);
+        self.storage.send(cmd).unwrap();
+        let entries = rec.await.unwrap().into_iter().map(audit_payload).collect();
+        let request = ServerEvent::new_audit_log_list(entries);
+        let command = SessionCmd::new_send(address, request);
+        self.sessions.send(command).unwrap();
+        Ok(())
+    }
+}
+
+/// only carries the profile fields, never the credentials of the user.
+fn profile_payload(profile: Profile) -> server::Profile {
+    server::Profile {
+        display_name: profile.display_name,
+        avatar: profile.avatar,
+        bio: profile.bio,
+        custom_status: profile.custom_status,
+    }
+}
+
+fn audit_payload((id, entry): (Id, AuditEntry)) -> server::AuditEntry {
+    server::AuditEntry {
+        id: id.to_u64(),
+        timestamp: id.timestamp(),
+        actor_id: entry.get_actor().map(|id| id.to_u64()),
+        action: entry.get_action().to_string(),
+        target_id: entry.get_target().map(|id| id.to_u64()),
+        before: entry.get_before().map(str::to_string),
+        after: entry.get_after().map(str::to_string),
+    }
+}
+
+fn seconds_to_millis(seconds: u64) -> Option<i64> {
+    i64::try_from(seconds).ok()?.checked_mul(1000)
+}
+
+/// the timestamp some client supplied seconds after `now`, `None` if it does not fit.
+fn seconds_after(now: i64, seconds: u64) -> Option<i64> {
+    now.checked_add(seconds_to_millis(seconds)?)
+}
+
+fn seconds_before(now: i64, seconds: u64) -> Option<i64> {
+    now.checked_sub(seconds_to_millis(seconds)?)
+}
+
+fn ban_payload(ban: &Ban) -> server::Ban {
+    server::Ban {
+        user_id: ban.get_user().to_u64(),
+        moderator_id: ban.get_moderator().to_u64(),
+        reason: ban.get_reason().to_string(),
+        created: ban.get_created(),
+        until: ban.get_until(),
+    }
+}
+
+fn invite_payload(invite: &Invite) -> server::Invite {
+    server::Invite {
+        code: invite.get_code().to_string(),
+        creator: invite.get_creator().to_u64(),
+        max_uses: invite.get_max_uses(),
+        uses: invite.get_uses(),
+        expires: invite.get_expires(),
+        guild_id: invite.get_guild().map(|id| id.to_u64()),
+    }
 }
 
 #[telecomande::async_trait]
@@ -273,11 +3013,75 @@ impl Processor for GatewayProc {
                     println!("[session/info] failed to parse command");
                 }
             }
-            GatewayCmd::ClosedConnection(address) => self
-                .sessions
-                .send(SessionCmd::RemoveSession(address))
-                .unwrap(),
+            GatewayCmd::ClosedConnection(address) => {
+                self.limiter.forget(&address);
+                self.sessions
+                    .send(SessionCmd::RemoveSession(address))
+                    .unwrap()
+            }
+            GatewayCmd::Purge => self.on_purge().await,
+            GatewayCmd::ExpireTyping => self.on_expire_typing().await,
         }
         Ok(())
     }
 }
+
+/// the channel a request is about, whose access is restricted if it is private or direct.
+fn channel_of(request: &ClientRequest) -> Option<u64> {
+    use ClientRequest as CR;
+    match request {
+        CR::ChannelDelete(ChannelDelete { id })
+        | CR::ChannelGetParent(ChannelGetParent { id })
+        | CR::ChannelGetInfo(ChannelGetInfo { id })
+        | CR::ChannelSetTopic(ChannelSetTopic { id, .. })
+        | CR::ChannelSetDescription(ChannelSetDescription { id, .. })
+        | CR::ChannelSetKind(ChannelSetKind { id, .. })
+        | CR::ChannelSetParent(ChannelSetParent { id, .. })
+        | CR::ChannelGetName(ChannelGetName { id })
+        | CR::ChannelSetName(ChannelSetName { id, .. })
+        | CR::ChannelGetSlowMode(ChannelGetSlowMode { id })
+        | CR::ChannelSetSlowMode(ChannelSetSlowMode { id, .. })
+        | CR::ChannelListPins(ChannelListPins { id })
+        | CR::ChannelSetPinLimit(ChannelSetPinLimit { id, .. })
+        | CR::ChannelSetPrivate(ChannelSetPrivate { id, .. })
+        | CR::ChannelMemberAdd(ChannelMemberAdd { id, .. })
+        | CR::ChannelMemberRemove(ChannelMemberRemove { id, .. })
+        | CR::ChannelMemberList(ChannelMemberList { id }) => Some(*id),
+
+        CR::MessageList(MessageList { channel_id })
+        | CR::MessageCreate(MessageCreate { channel_id, .. })
+        | CR::TypingStart(TypingStart { channel_id })
+        | CR::MessageDelete(MessageDelete { channel_id, .. })
+        | CR::MessageRestore(MessageRestore { channel_id, .. })
+        | CR::MessagePin(MessagePin { channel_id, .. })
+        | CR::MessageUnpin(MessageUnpin { channel_id, .. })
+        | CR::MessageGetContent(MessageGetContent { channel_id, .. })
+        | CR::MessageSetContent(MessageSetContent { channel_id, .. })
+        | CR::MessageGetHistory(MessageGetHistory { channel_id, .. })
+        | CR::ReactionAdd(ReactionAdd { channel_id, .. })
+        | CR::ReactionRemove(ReactionRemove { channel_id, .. })
+        | CR::ReactionListUsers(ReactionListUsers { channel_id, .. })
+        | CR::ThreadMessageList(ThreadMessageList { channel_id, .. })
+        | CR::ThreadSubscribe(ThreadSubscribe { channel_id, .. })
+        | CR::ThreadUnsubscribe(ThreadUnsubscribe { channel_id, .. })
+        | CR::ChannelAck(ChannelAck { channel_id, .. })
+        | CR::ChannelSetRoleOverwrite(ChannelSetRoleOverwrite { channel_id, .. })
+        | CR::ChannelSetUserOverwrite(ChannelSetUserOverwrite { channel_id, .. })
+        | CR::ChannelListOverwrites(ChannelListOverwrites { channel_id }) => Some(*channel_id),
+
+        CR::MessageSearch(MessageSearch { channel_id, .. })
+        | CR::PermissionsGet(PermissionsGet { channel_id }) => *channel_id,
+
+        _ => None,
+    }
+}
+
+#[test]
+fn test_seconds_out_of_range() {
+    let now = chrono::Utc::now().timestamp_millis();
+    assert_eq!(seconds_after(now, 60), Some(now + 60_000));
+    assert_eq!(seconds_before(now, 60), Some(now - 60_000));
+    assert_eq!(seconds_after(now, u64::MAX), None);
+    assert_eq!(seconds_after(now, i64::MAX as u64 / 1000), None);
+    assert_eq!(seconds_before(now, u64::MAX), None);
+}
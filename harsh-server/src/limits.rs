@@ -0,0 +1,371 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use harsh_common::ClientRequest;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::LimitsConfig, Addr, Id};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Ping,
+    Authenticate,
+    Query,
+    Message,
+    Channel,
+    User,
+}
+
+impl Category {
+    pub fn of(request: &ClientRequest) -> Self {
+        use ClientRequest as CR;
+        match request {
+            CR::Ping(_) => Self::Ping,
+            CR::Authenticate(_) => Self::Authenticate,
+
+            CR::ChannelList(_)
+            | CR::ChannelGetParent(_)
+            | CR::ChannelGetInfo(_)
+            | CR::ChannelGetName(_)
+            | CR::ChannelGetSlowMode(_)
+            | CR::ChannelListPins(_)
+            | CR::ChannelMemberList(_)
+            | CR::MessageList(_)
+            | CR::MessageGetContent(_)
+            | CR::MessageGetHistory(_)
+            | CR::MessageSearch(_)
+            | CR::ReactionListUsers(_)
+            | CR::ThreadMessageList(_)
+            | CR::ThreadSubscribe(_)
+            | CR::ThreadUnsubscribe(_)
+            | CR::MentionsList(_)
+            | CR::ReadStateList(_)
+            | CR::DirectList(_)
+            | CR::UserList(_)
+            | CR::UserGetName(_)
+            | CR::UserFindByName(_)
+            | CR::ProfileGet(_)
+            | CR::PresenceGet(_)
+            | CR::TypingStart(_)
+            | CR::LockoutList(_)
+            | CR::BanList(_)
+            | CR::AuditLogList(_)
+            | CR::InviteList(_)
+            | CR::RoleList(_)
+            | CR::UserListRoles(_)
+            | CR::ChannelListOverwrites(_)
+            | CR::PermissionsGet(_)
+            | CR::GuildList(_)
+            | CR::GuildMemberList(_) => Self::Query,
+
+            CR::MessageCreate(_)
+            | CR::MessageDelete(_)
+            | CR::MessageRestore(_)
+            | CR::ReactionAdd(_)
+            | CR::ReactionRemove(_)
+            | CR::ChannelAck(_)
+            | CR::MessageSetContent(_) => Self::Message,
+
+            CR::ChannelCreate(_)
+            | CR::CategoryCreate(_)
+            | CR::ChannelSetParent(_)
+            | CR::ChannelReorder(_)
+            | CR::ChannelSetTopic(_)
+            | CR::ChannelSetDescription(_)
+            | CR::ChannelSetKind(_)
+            | CR::ChannelDelete(_)
+            | CR::ChannelRestore(_)
+            | CR::ChannelSetName(_)
+            | CR::ChannelSetSlowMode(_)
+            | CR::ChannelSetPinLimit(_)
+            | CR::ChannelSetPrivate(_)
+            | CR::ChannelMemberAdd(_)
+            | CR::ChannelMemberRemove(_)
+            | CR::DirectOpen(_)
+            | CR::MessagePin(_)
+            | CR::MessageUnpin(_)
+            | CR::MessageReindex(_)
+            | CR::ChannelSetRoleOverwrite(_)
+            | CR::ChannelSetUserOverwrite(_) => Self::Channel,
+
+            CR::UserCreate(_)
+            | CR::UserDelete(_)
+            | CR::UserSetName(_)
+            | CR::UserSetPass(_)
+            | CR::ProfileUpdate(_)
+            | CR::PresenceSet(_)
+            | CR::InviteCreate(_)
+            | CR::InviteRevoke(_)
+            | CR::InviteUse(_)
+            | CR::BanCreate(_)
+            | CR::BanRemove(_)
+            | CR::UserKick(_)
+            | CR::UserTimeout(_)
+            | CR::RoleCreate(_)
+            | CR::RoleUpdate(_)
+            | CR::RoleDelete(_)
+            | CR::RoleAssign(_)
+            | CR::RoleUnassign(_)
+            | CR::GuildCreate(_)
+            | CR::GuildDelete(_)
+            | CR::GuildMemberAdd(_)
+            | CR::GuildMemberRemove(_)
+            | CR::GuildSetOp(_) => Self::User,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ping => "ping",
+            Self::Authenticate => "authenticate",
+            Self::Query => "query",
+            Self::Message => "message",
+            Self::Channel => "channel",
+            Self::User => "user",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    Addr(Addr),
+    User(Id),
+}
+
+/// a token bucket holding up to `burst` tokens, regaining one every `refill_ms` milliseconds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Limit {
+    pub burst: u32,
+    pub refill_ms: u64,
+}
+
+impl Limit {
+    pub const fn new(burst: u32, refill_ms: u64) -> Self {
+        Self { burst, refill_ms }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(limit: &Limit, now: Instant) -> Self {
+        let tokens = limit.burst as f64;
+        Self { tokens, last: now }
+    }
+
+    fn refill(&mut self, limit: &Limit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last).as_millis() as f64;
+        let gained = elapsed / limit.refill_ms.max(1) as f64;
+        self.tokens = (self.tokens + gained).min(limit.burst as f64);
+        self.last = now;
+    }
+
+    /// returns the delay before a token becomes available, if none is.
+    fn retry_after(&self, limit: &Limit) -> Option<Duration> {
+        if self.tokens >= 1.0 {
+            return None;
+        }
+        let missing = 1.0 - self.tokens;
+        let ms = (missing * limit.refill_ms as f64).ceil() as u64;
+        Some(Duration::from_millis(ms))
+    }
+
+    fn is_full(&self, limit: &Limit) -> bool {
+        self.tokens >= limit.burst as f64
+    }
+}
+
+impl LimitsConfig {
+    pub fn get(&self, category: Category) -> Limit {
+        match category {
+            Category::Ping => self.ping,
+            Category::Authenticate => self.authenticate,
+            Category::Query => self.query,
+            Category::Message => self.message,
+            Category::Channel => self.channel,
+            Category::User => self.user,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Limiter {
+    config: LimitsConfig,
+    buckets: HashMap<(Key, Category), Bucket>,
+    /// time of the last message of each author in a channel, along with the slow mode it was sent under.
+    last_messages: HashMap<(Id, Key), (Instant, Duration)>,
+}
+
+impl Limiter {
+    pub fn new(config: LimitsConfig) -> Self {
+        let buckets = HashMap::new();
+        let last_messages = HashMap::new();
+        Self {
+            config,
+            buckets,
+            last_messages,
+        }
+    }
+
+    /// consumes a token from the buckets of the connection and of the user if every one of them has one left,
+    /// otherwise returns the delay after which the request would be accepted.
+    pub fn check(
+        &mut self,
+        address: &Addr,
+        user: Option<Id>,
+        category: Category,
+    ) -> Result<(), Duration> {
+        self.check_at(address, user, category, Instant::now())
+    }
+
+    fn check_at(
+        &mut self,
+        address: &Addr,
+        user: Option<Id>,
+        category: Category,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let limit = self.config.get(category);
+        let mut keys = vec![Key::Addr(address.clone())];
+        keys.extend(user.map(Key::User));
+
+        let mut retry_after = None;
+        for key in &keys {
+            let bucket = self
+                .buckets
+                .entry((key.clone(), category))
+                .or_insert_with(|| Bucket::new(&limit, now));
+            bucket.refill(&limit, now);
+            retry_after = retry_after.max(bucket.retry_after(&limit));
+        }
+        if let Some(delay) = retry_after {
+            return Err(delay);
+        }
+
+        for key in keys {
+            if let Some(bucket) = self.buckets.get_mut(&(key, category)) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+
+    /// enforces the slow mode of a channel, `delay` being the minimum time between two messages of a same author.
+    pub fn check_slow_mode(
+        &mut self,
+        channel: Id,
+        key: Key,
+        delay: Duration,
+    ) -> Result<(), Duration> {
+        self.check_slow_mode_at(channel, key, delay, Instant::now())
+    }
+
+    fn check_slow_mode_at(
+        &mut self,
+        channel: Id,
+        key: Key,
+        delay: Duration,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let entry = (channel, key);
+        if let Some((last, _)) = self.last_messages.get(&entry) {
+            let elapsed = now.saturating_duration_since(*last);
+            if elapsed < delay {
+                return Err(delay - elapsed);
+            }
+        }
+        // messages older than the slow mode they were sent under no longer restrict anyone
+        self.last_messages
+            .retain(|_, (last, delay)| now.saturating_duration_since(*last) < *delay);
+        self.last_messages.insert(entry, (now, delay));
+        Ok(())
+    }
+
+    /// drops the state of a closed connection and of buckets that have fully recovered.
+    pub fn forget(&mut self, address: &Addr) {
+        let now = Instant::now();
+        let config = &self.config;
+        self.buckets.retain(|(key, category), bucket| {
+            let limit = config.get(*category);
+            bucket.refill(&limit, now);
+            key != &Key::Addr(address.clone()) && !bucket.is_full(&limit)
+        });
+        self.last_messages
+            .retain(|(_, key), _| key != &Key::Addr(address.clone()));
+    }
+}
+
+#[cfg(test)]
+fn test_addr() -> Addr {
+    Addr::new("127.0.0.1:4000".parse().unwrap())
+}
+
+#[test]
+fn test_bucket_exhaustion() {
+    let mut limiter = Limiter::new(LimitsConfig::default());
+    let address = test_addr();
+    let now = Instant::now();
+    let burst = LimitsConfig::default().message.burst;
+    for _ in 0..burst {
+        assert!(limiter
+            .check_at(&address, None, Category::Message, now)
+            .is_ok());
+    }
+    let delay = limiter
+        .check_at(&address, None, Category::Message, now)
+        .unwrap_err();
+    assert!(delay > Duration::ZERO);
+    let later = now + delay;
+    assert!(limiter
+        .check_at(&address, None, Category::Message, later)
+        .is_ok());
+}
+
+#[test]
+fn test_user_shared_between_connections() {
+    let mut limiter = Limiter::new(LimitsConfig::default());
+    let user = Some(Id::from_u64(1));
+    let now = Instant::now();
+    let burst = LimitsConfig::default().message.burst;
+    for port in 0..burst {
+        let address = Addr::new(format!("127.0.0.1:{}", 5000 + port).parse().unwrap());
+        assert!(limiter
+            .check_at(&address, user, Category::Message, now)
+            .is_ok());
+    }
+    assert!(limiter
+        .check_at(&test_addr(), user, Category::Message, now)
+        .is_err());
+}
+
+#[test]
+fn test_slow_mode() {
+    let mut limiter = Limiter::new(LimitsConfig::default());
+    let channel = Id::from_u64(1);
+    let key = Key::User(Id::from_u64(2));
+    let delay = Duration::from_secs(10);
+    let now = Instant::now();
+    assert!(limiter
+        .check_slow_mode_at(channel, key.clone(), delay, now)
+        .is_ok());
+    let remaining = limiter
+        .check_slow_mode_at(channel, key.clone(), delay, now + Duration::from_secs(4))
+        .unwrap_err();
+    assert_eq!(remaining, Duration::from_secs(6));
+    assert!(limiter
+        .check_slow_mode_at(channel, key, delay, now + delay)
+        .is_ok());
+
+    // expired entries are evicted on the next insertion
+    let other = Key::User(Id::from_u64(3));
+    assert!(limiter
+        .check_slow_mode_at(channel, other, delay, now + delay * 3)
+        .is_ok());
+    assert_eq!(limiter.last_messages.len(), 1);
+}
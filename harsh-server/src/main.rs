@@ -1,29 +1,63 @@
+use std::time::Duration;
+
 use telecomande::{Executor, SimpleExecutor};
 use tokio::net::TcpListener;
 
 const ADDRESS: &str = "localhost:42000";
 const DB_PATH: &str = "./db.test";
+const CONFIG_PATH: &str = "./config.json";
 
 #[tokio::main]
 async fn main() {
     println!("[main/info] starting server ...");
 
-    let sessions = SimpleExecutor::new(SessionProc::default()).spawn();
+    let config = Config::load(CONFIG_PATH);
+    println!("[main/info] loaded config");
+
+    let sessions = SimpleExecutor::new(SessionProc::new(&config.presence)).spawn();
     println!("[main/info] spawned sessions");
 
     let storage = SimpleExecutor::new(StorageProc::new(DB_PATH)).spawn();
     println!("[main/info] spawned storage");
 
-    let security = SimpleExecutor::new(SecurityProc::new(storage.remote())).spawn();
+    let security =
+        SimpleExecutor::new(SecurityProc::new(storage.remote(), config.auth.clone())).spawn();
 
     let gateway = SimpleExecutor::new(GatewayProc::new(
         sessions.remote(),
         storage.remote(),
         security.remote(),
+        &config,
     ))
     .spawn();
     println!("[main/info] spawned gateway");
 
+    let purger = gateway.remote();
+    let interval = Duration::from_secs(config.trash.purge_interval_secs.max(1));
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            purger.send(GatewayCmd::Purge).unwrap();
+        }
+    });
+
+    let expirer = gateway.remote();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            expirer.send(GatewayCmd::ExpireTyping).unwrap();
+        }
+    });
+
+    let checker = sessions.remote();
+    let interval = Duration::from_secs(config.presence.check_interval_secs.max(1));
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            checker.send(SessionCmd::new_check_idle()).unwrap();
+        }
+    });
+
     let listener = TcpListener::bind(ADDRESS).await.unwrap();
     println!("[main/info] listening on '{ADDRESS}' ...");
     let client_handler = sessions.remote();
@@ -39,6 +73,13 @@ async fn main() {
     }
 }
 
+mod config;
+pub use config::Config;
+
+mod limits;
+
+mod typing;
+
 mod utils;
 pub use utils::{Addr, Id};
 
@@ -52,4 +93,4 @@ mod storage;
 pub use storage::{StorageCmd, StorageProc};
 
 mod security;
-pub use security::{SecurityCmd, SecurityProc};
+pub use security::{AuthOutcome, SecurityCmd, SecurityProc};
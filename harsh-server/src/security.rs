@@ -1,15 +1,33 @@
+use std::time::{Duration, Instant};
+
 use telecomande::{Processor, Remote};
 use tokio::sync::oneshot::{self, Receiver, Sender};
 
-use crate::{storage::Perm, Id, StorageCmd, StorageProc};
+use crate::{
+    config::AuthConfig,
+    storage::{Ban, LockTarget, Lockout, Perm, Permissions, Scope},
+    Addr, Id, StorageCmd, StorageProc,
+};
 
 #[derive(Debug)]
 pub enum SecurityCmd {
     Verify(Id, Perm, Sender<bool>),
-    Authenticate(Id, String, Sender<bool>),
+    Resolve(Option<Id>, Scope, Sender<Permissions>),
+    /// `None` for logins naming no user, which still count as failures against the host.
+    Authenticate(Option<Id>, String, Addr, Sender<AuthOutcome>),
     StorePass(Id, String),
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum AuthOutcome {
+    Accepted,
+    Rejected,
+    /// too many failures, the user or host has to wait before attempting again.
+    Throttled(Duration),
+    /// the user is banned, whatever the password.
+    Banned(Ban),
+}
+
 impl SecurityCmd {
     pub fn new_verify(user_id: Id, permission: Perm) -> (Self, Receiver<bool>) {
         let (sender, receiver) = oneshot::channel();
@@ -17,9 +35,19 @@ impl SecurityCmd {
         (command, receiver)
     }
 
-    pub fn new_authenticate(user_id: Id, pass: String) -> (Self, Receiver<bool>) {
+    pub fn new_resolve(user_id: Option<Id>, scope: Scope) -> (Self, Receiver<Permissions>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::Resolve(user_id, scope, sender);
+        (command, receiver)
+    }
+
+    pub fn new_authenticate(
+        user_id: Option<Id>,
+        pass: String,
+        address: Addr,
+    ) -> (Self, Receiver<AuthOutcome>) {
         let (sender, receiver) = oneshot::channel();
-        let command = Self::Authenticate(user_id, pass, sender);
+        let command = Self::Authenticate(user_id, pass, address, sender);
         (command, receiver)
     }
 
@@ -30,37 +58,43 @@ impl SecurityCmd {
 
 pub struct SecurityProc {
     storage: Remote<StorageProc>,
+    attempts: Attempts,
 }
 
 impl SecurityProc {
-    pub fn new(storage: Remote<StorageProc>) -> Self {
-        Self { storage }
+    pub fn new(storage: Remote<StorageProc>, config: AuthConfig) -> Self {
+        let attempts = Attempts::new(config);
+        Self { storage, attempts }
     }
 
     async fn handle_command(&mut self, command: SecurityCmd) {
         match command {
-            SecurityCmd::Verify(user, perm, sender) => {
-                let (cmd, req) = StorageCmd::new_perm_server_get_op();
+            // not even server operators may read direct channels they do not take part in
+            SecurityCmd::Verify(user, Perm::ViewChannel(channel_id), sender) => {
+                let (cmd, rec) = StorageCmd::new_channel_audience(channel_id);
                 self.storage.send(cmd).unwrap();
-                let serv_ops = req.await.unwrap();
-                let is_serv_op = serv_ops.into_iter().any(|i| i == user);
-                let result = match (is_serv_op, perm) {
-                    (true, _) => true,
-                    (false, Perm::OpChannel(chan_id)) => {
-                        let (cmd, req) = StorageCmd::new_perm_channel_get_op(chan_id);
-                        self.storage.send(cmd).unwrap();
-                        let channel_ops = req.await.unwrap();
-                        channel_ops.into_iter().any(|i| i == user)
-                    }
-                    _ => false,
-                };
+                let audience = rec.await.unwrap();
+                let result = audience.map(|a| a.contains(&user)).unwrap_or(true);
                 sender.send(result).unwrap();
             }
-            SecurityCmd::Authenticate(user, pass, sender) => {
-                let (cmd, rec) = StorageCmd::new_user_get_pass(user);
-                self.storage.send(cmd).unwrap();
-                let stored = rec.await.unwrap();
-                let result = stored.map(|stored| stored == hash(pass)).unwrap_or(false);
+            SecurityCmd::Verify(user, Perm::Server(required), sender) => {
+                let permissions = self.resolve(Some(user), Scope::Server).await;
+                sender.send(permissions.contains(required)).unwrap();
+            }
+            SecurityCmd::Verify(user, Perm::Guild(guild, required), sender) => {
+                let permissions = self.resolve(Some(user), Scope::Guild(guild)).await;
+                sender.send(permissions.contains(required)).unwrap();
+            }
+            SecurityCmd::Verify(user, Perm::Channel(channel_id, required), sender) => {
+                let permissions = self.resolve(Some(user), Scope::Channel(channel_id)).await;
+                sender.send(permissions.contains(required)).unwrap();
+            }
+            SecurityCmd::Resolve(user, scope, sender) => {
+                let permissions = self.resolve(user, scope).await;
+                sender.send(permissions).unwrap();
+            }
+            SecurityCmd::Authenticate(user, pass, address, sender) => {
+                let result = self.authenticate(user, pass, address).await;
                 sender.send(result).unwrap();
             }
             SecurityCmd::StorePass(user, pass) => {
@@ -70,6 +104,56 @@ impl SecurityProc {
             }
         }
     }
+
+    async fn resolve(&mut self, user: Option<Id>, scope: Scope) -> Permissions {
+        let (cmd, rec) = StorageCmd::new_perm_get_grants(user, scope);
+        self.storage.send(cmd).unwrap();
+        permissions::resolve(&rec.await.unwrap())
+    }
+
+    async fn authenticate(&mut self, user: Option<Id>, pass: String, address: Addr) -> AuthOutcome {
+        let now = Instant::now();
+        let targets = match user {
+            Some(user) => Attempts::targets(user, address.host()).to_vec(),
+            None => vec![LockTarget::Host(address.host())],
+        };
+        if let Some(remaining) = self.attempts.blocked(&targets, now) {
+            return AuthOutcome::Throttled(remaining);
+        }
+
+        let stored = match user {
+            Some(user) => {
+                let (cmd, rec) = StorageCmd::new_user_get_pass(user);
+                self.storage.send(cmd).unwrap();
+                rec.await.unwrap()
+            }
+            None => None,
+        };
+        let accepted = stored.is_some_and(|stored| stored == hash(pass));
+        if let Some(user) = user.filter(|_| accepted) {
+            self.attempts.succeed(&targets[0]);
+            // only those knowing the password learn about the ban
+            let (cmd, rec) = StorageCmd::new_ban_get(user);
+            self.storage.send(cmd).unwrap();
+            return match rec.await.unwrap() {
+                Some(ban) => AuthOutcome::Banned(ban),
+                None => AuthOutcome::Accepted,
+            };
+        }
+
+        for Locked {
+            target,
+            failures,
+            duration,
+        } in self.attempts.fail(&targets, now)
+        {
+            println!("[security/warn] locking out '{target:?}' after {failures} failures");
+            let until = chrono::Utc::now().timestamp_millis() + duration.as_millis() as i64;
+            let command = StorageCmd::new_lockout_create(Lockout::new(target, failures, until));
+            self.storage.send(command).unwrap();
+        }
+        AuthOutcome::Rejected
+    }
 }
 
 const SALT: &str = ":)";
@@ -97,3 +181,8 @@ impl Processor for SecurityProc {
         Ok(())
     }
 }
+
+mod attempts;
+use attempts::{Attempts, Locked};
+
+mod permissions;
@@ -0,0 +1,153 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{config::AuthConfig, storage::LockTarget, Id};
+
+#[derive(Debug)]
+struct Failures {
+    count: u32,
+    last: Instant,
+    blocked_until: Instant,
+}
+
+/// a lockout triggered by a failed attempt.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Locked {
+    pub target: LockTarget,
+    pub failures: u32,
+    pub duration: Duration,
+}
+
+/// tracks failed authentications of each user and host to slow down password guessing.
+#[derive(Debug)]
+pub struct Attempts {
+    config: AuthConfig,
+    failures: HashMap<LockTarget, Failures>,
+}
+
+impl Attempts {
+    pub fn new(config: AuthConfig) -> Self {
+        let failures = HashMap::new();
+        Self { config, failures }
+    }
+
+    pub fn targets(user: Id, host: String) -> [LockTarget; 2] {
+        [LockTarget::User(user), LockTarget::Host(host)]
+    }
+
+    fn is_stale(&self, failures: &Failures, now: Instant) -> bool {
+        let window = Duration::from_secs(self.config.failure_window_secs);
+        now.saturating_duration_since(failures.last) > window && failures.blocked_until <= now
+    }
+
+    /// returns the delay before any of the targets may attempt to authenticate again.
+    pub fn blocked(&self, targets: &[LockTarget], now: Instant) -> Option<Duration> {
+        targets
+            .iter()
+            .filter_map(|target| self.failures.get(target))
+            .filter(|failures| !self.is_stale(failures, now))
+            .map(|failures| failures.blocked_until.saturating_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+            .max()
+    }
+
+    /// records a failure for each target, returning the lockouts it caused.
+    pub fn fail(&mut self, targets: &[LockTarget], now: Instant) -> Vec<Locked> {
+        let stale = self
+            .failures
+            .iter()
+            .filter(|(_, failures)| self.is_stale(failures, now))
+            .map(|(target, _)| target.clone())
+            .collect::<Vec<_>>();
+        for target in stale {
+            self.failures.remove(&target);
+        }
+
+        let mut locked = Vec::new();
+        for target in targets {
+            let failures = self.failures.entry(target.clone()).or_insert(Failures {
+                count: 0,
+                last: now,
+                blocked_until: now,
+            });
+            failures.count += 1;
+            failures.last = now;
+
+            let threshold = match target {
+                LockTarget::User(_) => self.config.user_lockout_threshold,
+                LockTarget::Host(_) => self.config.host_lockout_threshold,
+            };
+            let delay = if failures.count >= threshold {
+                let duration = Duration::from_secs(self.config.lockout_duration_secs);
+                locked.push(Locked {
+                    target: target.clone(),
+                    failures: failures.count,
+                    duration,
+                });
+                duration
+            } else {
+                backoff(&self.config, failures.count)
+            };
+            failures.blocked_until = now + delay;
+        }
+        locked
+    }
+
+    pub fn succeed(&mut self, target: &LockTarget) {
+        self.failures.remove(target);
+    }
+}
+
+fn backoff(config: &AuthConfig, count: u32) -> Duration {
+    let factor = 2_u64.saturating_pow(count.saturating_sub(1));
+    let ms = config.backoff_base_ms.saturating_mul(factor);
+    Duration::from_millis(ms.min(config.backoff_max_ms))
+}
+
+#[test]
+fn test_backoff() {
+    let config = AuthConfig::default();
+    let base = config.backoff_base_ms;
+    assert_eq!(backoff(&config, 1), Duration::from_millis(base));
+    assert_eq!(backoff(&config, 3), Duration::from_millis(base * 4));
+    assert_eq!(
+        backoff(&config, 60),
+        Duration::from_millis(config.backoff_max_ms)
+    );
+}
+
+#[test]
+fn test_lockout() {
+    let config = AuthConfig::default();
+    let threshold = config.user_lockout_threshold;
+    let mut attempts = Attempts::new(config.clone());
+    let targets = Attempts::targets(Id::from_u64(1), "127.0.0.1".into());
+    let mut now = Instant::now();
+    assert_eq!(attempts.blocked(&targets, now), None);
+
+    for _ in 1..threshold {
+        assert!(attempts.fail(&targets, now).is_empty());
+        assert!(attempts.blocked(&targets, now).is_some());
+        now += Duration::from_millis(config.backoff_max_ms);
+        assert_eq!(attempts.blocked(&targets, now), None);
+    }
+
+    let locked = attempts.fail(&targets, now);
+    assert_eq!(
+        locked,
+        vec![Locked {
+            target: LockTarget::User(Id::from_u64(1)),
+            failures: threshold,
+            duration: Duration::from_secs(config.lockout_duration_secs),
+        }]
+    );
+    let other = Attempts::targets(Id::from_u64(2), "127.0.0.1".into());
+    assert!(attempts.blocked(&targets, now).is_some());
+    assert!(attempts.blocked(&other, now).is_some());
+
+    attempts.succeed(&targets[0]);
+    now += Duration::from_millis(config.backoff_max_ms);
+    assert_eq!(attempts.blocked(&targets, now), None);
+}
@@ -0,0 +1,81 @@
+use crate::storage::{Grants, Overwrite, Permissions};
+
+/// computes the permissions of a user: those of their roles, then the overwrites of the channel for
+/// the everyone role, for their other roles together, and for the user itself.
+/// operators of the server and of the guild have every permission.
+/// timed out users may not speak, whatever they were granted.
+pub fn resolve(grants: &Grants) -> Permissions {
+    let permissions = granted(grants);
+    if grants.timed_out {
+        return permissions.difference(Permissions::speech());
+    }
+    permissions
+}
+
+fn granted(grants: &Grants) -> Permissions {
+    let base = grants
+        .roles
+        .iter()
+        .fold(Permissions::empty(), |all, role| all.union(*role));
+    if grants.server_op || grants.guild_op || base.contains(Permissions::ADMINISTRATOR) {
+        return Permissions::all();
+    }
+
+    let mut permissions = base;
+    if let Some(overwrite) = grants.everyone_overwrite {
+        permissions = overwrite.apply(permissions);
+    }
+    let roles = grants
+        .role_overwrites
+        .iter()
+        .fold(Overwrite::default(), |all, overwrite| Overwrite {
+            allow: all.allow.union(overwrite.allow),
+            deny: all.deny.union(overwrite.deny),
+        });
+    permissions = roles.apply(permissions);
+    if let Some(overwrite) = grants.user_overwrite {
+        permissions = overwrite.apply(permissions);
+    }
+    if grants.channel_op {
+        permissions = permissions.union(Permissions::channel_op());
+    }
+    permissions
+}
+
+#[test]
+fn test_resolve() {
+    let send = Permissions::SEND_MESSAGES;
+    let manage = Permissions::MANAGE_MESSAGES;
+    let mut grants = Grants {
+        roles: vec![send, manage],
+        ..Default::default()
+    };
+    assert_eq!(resolve(&grants), send.union(manage));
+
+    // roles are overridden by the user overwrite
+    grants.role_overwrites = vec![Overwrite {
+        allow: Permissions::empty(),
+        deny: send,
+    }];
+    assert_eq!(resolve(&grants), manage);
+    grants.user_overwrite = Some(Overwrite {
+        allow: send,
+        deny: manage,
+    });
+    assert_eq!(resolve(&grants), send);
+
+    // server-wide permissions can not be granted in a channel
+    grants.user_overwrite = Some(Overwrite {
+        allow: Permissions::ADMINISTRATOR,
+        deny: Permissions::empty(),
+    });
+    assert!(!resolve(&grants).contains(Permissions::ADMINISTRATOR));
+
+    grants.guild_op = true;
+    assert_eq!(resolve(&grants), Permissions::all());
+
+    // a timeout even mutes operators
+    grants.timed_out = true;
+    assert!(!resolve(&grants).contains(send));
+    assert!(resolve(&grants).contains(manage));
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
 
 use harsh_common::ServerEvent;
 use telecomande::{Processor, Remote};
@@ -12,15 +16,24 @@ use tokio::{
     task::JoinHandle,
 };
 
-use crate::{gateway, Addr, Id};
+use crate::{config::PresenceConfig, gateway, Addr, Id};
 #[derive(Debug)]
 pub enum SessionCmd {
     AddSession(TcpStream, SocketAddr, Remote<gateway::GatewayProc>),
     RemoveSession(Addr),
     Send(Addr, String),
     Broadcast(String),
+    BroadcastExcept(Id, String),
+    SendTo(Vec<Id>, String),
     GetUser(Addr, Sender<Option<Id>>),
     SetUser(Addr, Option<Id>),
+    Logout(Id),
+    Kick(Id, String),
+    Touch(Addr),
+    SetStatus(Id, Presence),
+    GetPresences(Vec<Id>, Sender<Vec<Presence>>),
+    /// shows as idle the users whose sessions have all been inactive for too long.
+    CheckIdle,
 }
 
 impl SessionCmd {
@@ -46,6 +59,18 @@ impl SessionCmd {
         Self::Broadcast(content)
     }
 
+    /// sends to every session not authenticated as this user.
+    pub fn new_broadcast_except(user: Id, request: ServerEvent) -> Self {
+        let content = request.serialize();
+        Self::BroadcastExcept(user, content)
+    }
+
+    /// sends to every session authenticated as one of these users.
+    pub fn new_send_to(users: Vec<Id>, request: ServerEvent) -> Self {
+        let content = request.serialize();
+        Self::SendTo(users, content)
+    }
+
     pub fn new_get_user(address: Addr) -> (Self, Receiver<Option<Id>>) {
         let (sender, receiver) = oneshot::channel();
         let command = Self::GetUser(address, sender);
@@ -55,14 +80,128 @@ impl SessionCmd {
     pub fn new_set_user(address: Addr, user: Option<Id>) -> Self {
         Self::SetUser(address, user)
     }
+
+    /// sets every session authenticated as this user back to anonymous.
+    pub fn new_logout(user: Id) -> Self {
+        Self::Logout(user)
+    }
+
+    /// closes every session authenticated as this user, after sending it the event.
+    pub fn new_kick(user: Id, request: ServerEvent) -> Self {
+        let content = request.serialize();
+        Self::Kick(user, content)
+    }
+
+    /// marks the session as active.
+    pub fn new_touch(address: Addr) -> Self {
+        Self::Touch(address)
+    }
+
+    pub fn new_set_status(user: Id, status: Presence) -> Self {
+        Self::SetStatus(user, status)
+    }
+
+    /// the receiver gets the presence of each user, in order.
+    pub fn new_get_presences(users: Vec<Id>) -> (Self, Receiver<Vec<Presence>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::GetPresences(users, sender);
+        (command, receiver)
+    }
+
+    pub fn new_check_idle() -> Self {
+        Self::CheckIdle
+    }
+}
+
+/// what other users see of the activity of a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    Online,
+    Idle,
+    DoNotDisturb,
+    Offline,
+}
+
+impl Presence {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Online => "online",
+            Self::Idle => "idle",
+            Self::DoNotDisturb => "dnd",
+            Self::Offline => "offline",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "online" => Some(Self::Online),
+            "idle" => Some(Self::Idle),
+            "dnd" => Some(Self::DoNotDisturb),
+            "offline" => Some(Self::Offline),
+            _ => None,
+        }
+    }
+
+    /// the presence shown for a user given the status it chose, `active` telling whether one of
+    /// its sessions was recently used, `None` if it has none.
+    pub fn shown(chosen: Self, active: Option<bool>) -> Self {
+        match (chosen, active) {
+            (_, None) => Self::Offline,
+            (Self::Online, Some(false)) => Self::Idle,
+            (chosen, Some(_)) => chosen,
+        }
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct SessionProc {
     clients: HashMap<Addr, Client>,
+    /// statuses chosen by users, online ones being left out.
+    statuses: HashMap<Id, Presence>,
+    /// last presence announced for each user, offline ones being left out.
+    presences: HashMap<Id, Presence>,
+    idle_after: Duration,
 }
 
 impl SessionProc {
+    pub fn new(config: &PresenceConfig) -> Self {
+        Self {
+            clients: HashMap::new(),
+            statuses: HashMap::new(),
+            presences: HashMap::new(),
+            idle_after: Duration::from_secs(config.idle_after_secs),
+        }
+    }
+
+    fn presence(&self, user: Id) -> Presence {
+        let active = self
+            .clients
+            .values()
+            .filter(|client| client.get_user() == Some(user))
+            .map(|client| client.get_last_active().elapsed() < self.idle_after)
+            .reduce(|a, b| a || b);
+        let chosen = self.statuses.get(&user).copied();
+        Presence::shown(chosen.unwrap_or(Presence::Online), active)
+    }
+
+    /// announces the presence of the user to everyone if it changed.
+    async fn refresh_presence(&mut self, user: Id) {
+        let presence = self.presence(user);
+        let previous = match presence {
+            Presence::Offline => self.presences.remove(&user),
+            presence => self.presences.insert(user, presence),
+        };
+        if previous.unwrap_or(Presence::Offline) == presence {
+            return;
+        }
+        let request = ServerEvent::new_presence_update(user.to_u64(), presence.name().into());
+        let content = request.serialize();
+        println!("[session/info] broadcasting '{content}'");
+        for client in self.clients.values_mut() {
+            client.send(&content).await;
+        }
+    }
+
     fn add_client(
         &mut self,
         stream: TcpStream,
@@ -70,8 +209,10 @@ impl SessionProc {
         remote: Remote<gateway::GatewayProc>,
     ) {
         let (reader, writer) = stream.into_split();
-        let handle = tokio::spawn(session(address.clone(), reader, remote));
-        self.clients.insert(address, Client::new(writer, handle));
+        let (closer, closed) = oneshot::channel();
+        let handle = tokio::spawn(session(address.clone(), reader, remote, closed));
+        self.clients
+            .insert(address, Client::new(writer, handle, closer));
     }
 }
 
@@ -91,7 +232,11 @@ impl Processor for SessionProc {
             SessionCmd::RemoveSession(address) => {
                 println!("[sessions/info] closed connection from '{address:?}'");
                 if let Some(client) = self.clients.remove(&address) {
+                    let user = client.get_user();
                     client.unwrap().await.unwrap();
+                    if let Some(user) = user {
+                        self.refresh_presence(user).await;
+                    }
                 }
             }
             SessionCmd::Send(address, content) => {
@@ -106,13 +251,88 @@ impl Processor for SessionProc {
                     client.send(&content).await;
                 }
             }
+            SessionCmd::BroadcastExcept(user, content) => {
+                println!("[session/info] broadcasting '{content}' except to user {user:?}");
+                for client in self.clients.values_mut() {
+                    if client.get_user() != Some(user) {
+                        client.send(&content).await;
+                    }
+                }
+            }
+            SessionCmd::SendTo(users, content) => {
+                println!("[session/info] sending '{content}' to users {users:?}");
+                for client in self.clients.values_mut() {
+                    if client
+                        .get_user()
+                        .map(|u| users.contains(&u))
+                        .unwrap_or(false)
+                    {
+                        client.send(&content).await;
+                    }
+                }
+            }
             SessionCmd::GetUser(address, sender) => {
                 let user = self.clients.get_mut(&address).and_then(|c| c.get_user());
                 sender.send(user).unwrap();
             }
             SessionCmd::SetUser(address, user) => {
                 if let Some(client) = self.clients.get_mut(&address) {
+                    let previous = client.get_user();
                     client.set_user(user);
+                    client.touch();
+                    for user in [previous, user].into_iter().flatten() {
+                        self.refresh_presence(user).await;
+                    }
+                }
+            }
+            SessionCmd::Logout(user) => {
+                for client in self.clients.values_mut() {
+                    if client.get_user() == Some(user) {
+                        client.set_user(None);
+                    }
+                }
+                self.refresh_presence(user).await;
+            }
+            SessionCmd::Kick(user, content) => {
+                let addresses: Vec<_> = self
+                    .clients
+                    .iter()
+                    .filter(|(_, client)| client.get_user() == Some(user))
+                    .map(|(address, _)| address.clone())
+                    .collect();
+                for address in addresses {
+                    if let Some(mut client) = self.clients.remove(&address) {
+                        println!("[sessions/info] kicking '{address:?}'");
+                        client.send(&content).await;
+                        client.close();
+                    }
+                }
+                self.refresh_presence(user).await;
+            }
+            SessionCmd::Touch(address) => {
+                if let Some(client) = self.clients.get_mut(&address) {
+                    client.touch();
+                    if let Some(user) = client.get_user() {
+                        self.refresh_presence(user).await;
+                    }
+                }
+            }
+            SessionCmd::SetStatus(user, status) => {
+                match status {
+                    Presence::Online => self.statuses.remove(&user),
+                    status => self.statuses.insert(user, status),
+                };
+                self.refresh_presence(user).await;
+            }
+            SessionCmd::GetPresences(users, sender) => {
+                let presences = users.into_iter().map(|user| self.presence(user)).collect();
+                sender.send(presences).unwrap();
+            }
+            SessionCmd::CheckIdle => {
+                let users: HashSet<_> =
+                    self.clients.values().filter_map(Client::get_user).collect();
+                for user in users {
+                    self.refresh_presence(user).await;
                 }
             }
         };
@@ -124,16 +344,21 @@ impl Processor for SessionProc {
 pub struct Client {
     writer: OwnedWriteHalf,
     handle: JoinHandle<()>,
+    closer: Sender<()>,
     user: Option<Id>,
+    last_active: Instant,
 }
 
 impl Client {
-    pub fn new(writer: OwnedWriteHalf, handle: JoinHandle<()>) -> Self {
+    pub fn new(writer: OwnedWriteHalf, handle: JoinHandle<()>, closer: Sender<()>) -> Self {
         let user = None;
+        let last_active = Instant::now();
         Self {
             handle,
+            closer,
             user,
             writer,
+            last_active,
         }
     }
 
@@ -142,11 +367,18 @@ impl Client {
             writer,
             handle,
             user,
+            ..
         } = self;
         drop((writer, user));
         handle
     }
 
+    /// stops reading from the connection, which closes once the client is dropped.
+    pub fn close(self) {
+        // the session may have ended on its own already
+        let _ = self.closer.send(());
+    }
+
     pub async fn send(&mut self, message: &str) {
         self.writer.write_all(message.as_bytes()).await.unwrap();
         self.writer.write_all(b"\n").await.unwrap();
@@ -157,16 +389,30 @@ impl Client {
     pub fn get_user(&self) -> Option<Id> {
         self.user
     }
+    pub fn touch(&mut self) {
+        self.last_active = Instant::now();
+    }
+    pub fn get_last_active(&self) -> Instant {
+        self.last_active
+    }
 }
 
-async fn session(address: Addr, reader: OwnedReadHalf, remote: Remote<gateway::GatewayProc>) {
+async fn session(
+    address: Addr,
+    reader: OwnedReadHalf,
+    remote: Remote<gateway::GatewayProc>,
+    mut closed: Receiver<()>,
+) {
     let mut reader = BufReader::new(reader);
     loop {
         let mut line = String::new();
-        match reader.read_line(&mut line).await {
-            Err(error) => eprintln!("[session/error] {error}"),
-            Ok(0) => break,
-            _ => (),
+        tokio::select! {
+            result = reader.read_line(&mut line) => match result {
+                Err(error) => eprintln!("[session/error] {error}"),
+                Ok(0) => break,
+                _ => (),
+            },
+            _ = &mut closed => break,
         }
         remote
             .send(gateway::GatewayCmd::Request(address.clone(), line.clone()))
@@ -181,6 +427,7 @@ async fn session(address: Addr, reader: OwnedReadHalf, remote: Remote<gateway::G
 pub trait SessionExt {
     fn send(&self, cmd: SessionCmd);
 
+    #[allow(dead_code)]
     async fn is_logged(&self, address: Addr) -> bool {
         self.get_user(address).await.is_some()
     }
@@ -197,3 +444,16 @@ impl SessionExt for Remote<SessionProc> {
         self.send(cmd).unwrap();
     }
 }
+
+#[test]
+fn test_presence() {
+    use Presence::*;
+    assert_eq!(Presence::shown(Online, Some(true)), Online);
+    assert_eq!(Presence::shown(Online, Some(false)), Idle);
+    assert_eq!(Presence::shown(DoNotDisturb, Some(false)), DoNotDisturb);
+    assert_eq!(Presence::shown(Offline, Some(true)), Offline);
+    assert_eq!(Presence::shown(DoNotDisturb, None), Offline);
+    for presence in [Online, Idle, DoNotDisturb, Offline] {
+        assert_eq!(Presence::from_name(presence.name()), Some(presence));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use sled::Db;
 use telecomande::Processor;
 use tokio::sync::oneshot::{self, Receiver, Sender};
@@ -6,46 +8,221 @@ use crate::Id;
 
 #[derive(Debug)]
 pub enum StorageCmd {
-    ChannelList(Sender<Vec<Id>>),
-    ChannelCreate(String, Sender<Id>),
+    ChannelList(Option<Id>, Sender<Vec<Id>>),
+    ChannelCreate(String, ChannelKind, Id, Option<Id>, Sender<Id>),
+    ChannelTree(Option<Id>, Id, Sender<Tree>),
+    ChannelSetParent(Id, Option<Id>, Sender<bool>),
+    ChannelReorder(Vec<Id>),
+    ChannelGet(Id, Sender<Option<Channel>>),
+    ChannelSetPrivate(Id, bool),
+    ChannelSetTopic(Id, String),
+    ChannelSetDescription(Id, String),
+    ChannelSetKind(Id, ChannelKind, Sender<bool>),
+    ChannelAudience(Id, Sender<Option<Vec<Id>>>),
+    ChannelMemberAdd(Id, Id),
+    ChannelMemberRemove(Id, Id),
+    ChannelMemberList(Id, Sender<Vec<Id>>),
+    DirectOpen(Vec<Id>, Sender<(Id, bool)>),
+    DirectList(Id, Sender<Vec<Id>>),
     ChannelDelete(Id),
+    ChannelRestore(Id, Sender<bool>),
     ChannelGetName(Id, Sender<Option<String>>),
     ChannelSetName(Id, String),
+    ChannelGetSlowMode(Id, Sender<Option<u64>>),
+    ChannelSetSlowMode(Id, u64),
+    ChannelGetPins(Id, Sender<Vec<Id>>),
+    ChannelGetPinLimit(Id, Sender<Option<u32>>),
+    ChannelSetPinLimit(Id, Option<u32>),
+    MessagePin(Id, Id, Sender<bool>),
+    MessageUnpin(Id, Id, Sender<bool>),
     MessageList(Id, Sender<Vec<Id>>),
-    MessageCreate(Id, String, Sender<Id>),
+    MessageCreate(Id, Option<Id>, String, Option<Id>, Option<Id>, Sender<Id>),
     MessageDelete(Id, Id),
-    MessageGetContent(Id, Id, Sender<Option<String>>),
-    MessageSetContent(Id, Id, String),
+    MessageRestore(Id, Id, Sender<bool>),
+    MessageGet(Id, Id, Sender<Option<Message>>),
+    MessageSetContent(Id, Id, Option<Id>, String, Sender<Option<u32>>),
+    MessageGetHistory(Id, Id, Sender<Vec<Revision>>),
+    MessageSearch(SearchQuery, Sender<SearchResults>),
+    ReactionAdd(Id, Id, String, Id, Sender<bool>),
+    ReactionRemove(Id, Id, String, Id, Sender<bool>),
+    ReactionList(Id, Id, Sender<BTreeMap<String, Vec<Id>>>),
+    ReactionListUsers(Id, Id, String, Sender<Vec<Id>>),
+    ThreadMessageList(Id, Id, Option<Id>, usize, Sender<Vec<Id>>),
+    ThreadSubscribe(Id, Id, Id),
+    ThreadUnsubscribe(Id, Id, Id),
+    ThreadSubscribers(Id, Id, Sender<Vec<Id>>),
+    MentionList(Id, Option<Id>, usize, Sender<Vec<(Id, Id)>>),
+    ChannelAck(Id, Id, Id),
+    ReadStateList(Id, Sender<Vec<ReadState>>),
+    MessageReindex(Sender<usize>),
     UserList(Sender<Vec<Id>>),
-    UserCreate(String, String, Sender<Id>),
-    UserDelete(Id),
+    UserCreate(String, String, Sender<Option<Id>>),
+    UserDelete(Id, bool, Sender<Vec<(Id, Id)>>),
     UserGetName(Id, Sender<Option<String>>),
-    UserSetName(Id, String),
+    UserSetName(Id, String, Sender<bool>),
+    UserFindByName(String, Sender<Option<Id>>),
     UserGetPass(Id, Sender<Option<String>>),
     UserSetPass(Id, String),
+    UserGetProfile(Id, Sender<Option<Profile>>),
+    UserUpdateProfile(Id, Profile, Sender<Option<Profile>>),
+    UserProfileAudience(Id, Sender<Option<Vec<Id>>>),
     PermServerAddOp(Id),
     PermServerRemoveOp(Id),
     PermServerGetOp(Sender<Vec<Id>>),
     PermChannelAddOp(Id, Id),
     PermChannelRemoveOp(Id, Id),
     PermChannelGetOp(Id, Sender<Vec<Id>>),
+    GuildCreate(String, Id, Sender<Id>),
+    GuildGet(Id, Sender<Option<Guild>>),
+    GuildDelete(Id, Sender<bool>),
+    GuildList(Id, Sender<Vec<Id>>),
+    GuildMemberSet(Id, Id, bool, Sender<bool>),
+    GuildMemberList(Id, Sender<Vec<Id>>),
+    GuildIsMember(Id, Id, Sender<bool>),
+    GuildSetOp(Id, Id, bool, Sender<bool>),
+    RoleCreate(Id, String, Permissions, Sender<Id>),
+    RoleGet(Id, Sender<Option<Role>>),
+    RoleUpdate(Id, String, Permissions, Sender<bool>),
+    RoleDelete(Id, Sender<bool>),
+    RoleList(Id, Sender<Vec<Role>>),
+    RoleAssign(Id, Id, bool, Sender<bool>),
+    UserListRoles(Id, Sender<Vec<Id>>),
+    OverwriteSet(Id, OverwriteTarget, Overwrite),
+    OverwriteList(Id, Sender<Vec<(OverwriteTarget, Overwrite)>>),
+    PermGetGrants(Option<Id>, Scope, Sender<Grants>),
+    LockoutCreate(Lockout),
+    LockoutList(Sender<Vec<Lockout>>),
+    InviteCreate(Invite),
+    InviteGet(String, Sender<Option<Invite>>),
+    InviteList(Option<Id>, Sender<Vec<Invite>>),
+    InviteRevoke(String, Sender<bool>),
+    InviteUse(String, Option<Id>, Sender<bool>),
+    BanCreate(Ban, Option<i64>, Sender<Vec<(Id, Id)>>),
+    BanGet(Id, Sender<Option<Ban>>),
+    BanRemove(Id, Sender<bool>),
+    BanList(Sender<Vec<Ban>>),
+    TimeoutSet(Id, Option<i64>),
+    TimeoutGet(Id, Sender<Option<i64>>),
+    AuditAppend(AuditEntry),
+    AuditList(
+        AuditFilter,
+        Option<Id>,
+        usize,
+        Sender<Vec<(Id, AuditEntry)>>,
+    ),
+    Purge(i64, Sender<Purged>),
 }
 
 impl StorageCmd {
-    pub fn new_channel_list() -> (Self, Receiver<Vec<Id>>) {
+    /// the receiver gets the channels of the default guild the viewer is allowed in, direct ones
+    /// excepted, in tree order.
+    pub fn new_channel_list(viewer: Option<Id>) -> (Self, Receiver<Vec<Id>>) {
         let (s, r) = oneshot::channel();
-        (Self::ChannelList(s), r)
+        (Self::ChannelList(viewer, s), r)
     }
 
     pub fn new_channel_create(name: impl ToString) -> (Self, Receiver<Id>) {
-        let (s, r) = oneshot::channel();
-        (Self::ChannelCreate(name.to_string(), s), r)
+        Self::new_channel_create_by(name, ChannelKind::Text, Guild::DEFAULT, None)
+    }
+
+    pub fn new_channel_create_by(
+        name: impl ToString,
+        kind: ChannelKind,
+        guild: Id,
+        creator: Option<Id>,
+    ) -> (Self, Receiver<Id>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ChannelCreate(name.to_string(), kind, guild, creator, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_channel_tree(viewer: Option<Id>, guild: Id) -> (Self, Receiver<Tree>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ChannelTree(viewer, guild, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets whether the parent is a category of the same guild the channel can be
+    /// moved to.
+    pub fn new_channel_set_parent(id: Id, parent: Option<Id>) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ChannelSetParent(id, parent, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_channel_reorder(ids: Vec<Id>) -> Self {
+        Self::ChannelReorder(ids)
+    }
+
+    pub fn new_channel_get(id: Id) -> (Self, Receiver<Option<Channel>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ChannelGet(id, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_channel_set_private(id: Id, private: bool) -> Self {
+        Self::ChannelSetPrivate(id, private)
+    }
+
+    pub fn new_channel_set_topic(id: Id, topic: String) -> Self {
+        Self::ChannelSetTopic(id, topic)
+    }
+
+    pub fn new_channel_set_description(id: Id, description: String) -> Self {
+        Self::ChannelSetDescription(id, description)
+    }
+
+    /// the receiver gets whether the kind could be set, categories staying categories.
+    pub fn new_channel_set_kind(id: Id, kind: ChannelKind) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ChannelSetKind(id, kind, sender);
+        (cmd, receiver)
     }
 
+    /// the receiver gets `None` when the channel is open to everyone.
+    pub fn new_channel_audience(id: Id) -> (Self, Receiver<Option<Vec<Id>>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ChannelAudience(id, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_channel_member_add(id: Id, user: Id) -> Self {
+        Self::ChannelMemberAdd(id, user)
+    }
+
+    pub fn new_channel_member_remove(id: Id, user: Id) -> Self {
+        Self::ChannelMemberRemove(id, user)
+    }
+
+    pub fn new_channel_member_list(id: Id) -> (Self, Receiver<Vec<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ChannelMemberList(id, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets the id of the channel and whether it was just created.
+    pub fn new_direct_open(participants: Vec<Id>) -> (Self, Receiver<(Id, bool)>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::DirectOpen(participants, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_direct_list(user: Id) -> (Self, Receiver<Vec<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::DirectList(user, sender);
+        (cmd, receiver)
+    }
+
+    /// moves the channel to the trash, from where it can be restored until it is purged.
     pub fn new_channel_delete(id: Id) -> Self {
         Self::ChannelDelete(id)
     }
 
+    pub fn new_channel_restore(id: Id) -> (Self, Receiver<bool>) {
+        let (s, r) = oneshot::channel();
+        (Self::ChannelRestore(id, s), r)
+    }
+
     pub fn new_channel_get_name(id: Id) -> (Self, Receiver<Option<String>>) {
         let (s, r) = oneshot::channel();
         (Self::ChannelGetName(id, s), r)
@@ -55,30 +232,204 @@ impl StorageCmd {
         Self::ChannelSetName(id, name)
     }
 
+    pub fn new_channel_get_slow_mode(id: Id) -> (Self, Receiver<Option<u64>>) {
+        let (s, r) = oneshot::channel();
+        (Self::ChannelGetSlowMode(id, s), r)
+    }
+
+    pub fn new_channel_set_slow_mode(id: Id, seconds: u64) -> Self {
+        Self::ChannelSetSlowMode(id, seconds)
+    }
+
+    /// the receiver gets the pinned messages that were not deleted, in pin order.
+    pub fn new_channel_get_pins(id: Id) -> (Self, Receiver<Vec<Id>>) {
+        let (s, r) = oneshot::channel();
+        (Self::ChannelGetPins(id, s), r)
+    }
+
+    pub fn new_channel_get_pin_limit(id: Id) -> (Self, Receiver<Option<u32>>) {
+        let (s, r) = oneshot::channel();
+        (Self::ChannelGetPinLimit(id, s), r)
+    }
+
+    pub fn new_channel_set_pin_limit(id: Id, limit: Option<u32>) -> Self {
+        Self::ChannelSetPinLimit(id, limit)
+    }
+
+    /// the receiver gets whether the message was not already pinned.
+    pub fn new_message_pin(channel_id: Id, id: Id) -> (Self, Receiver<bool>) {
+        let (s, r) = oneshot::channel();
+        (Self::MessagePin(channel_id, id, s), r)
+    }
+
+    /// the receiver gets whether the message was pinned.
+    pub fn new_message_unpin(channel_id: Id, id: Id) -> (Self, Receiver<bool>) {
+        let (s, r) = oneshot::channel();
+        (Self::MessageUnpin(channel_id, id, s), r)
+    }
+
     pub fn new_message_list(channel_id: Id) -> (Self, Receiver<Vec<Id>>) {
         let (sender, receiver) = oneshot::channel();
         let cmd = Self::MessageList(channel_id, sender);
         (cmd, receiver)
     }
 
-    pub fn new_message_create(channel_id: Id, content: String) -> (Self, Receiver<Id>) {
+    pub fn new_message_create(
+        channel_id: Id,
+        author: Option<Id>,
+        content: String,
+    ) -> (Self, Receiver<Id>) {
+        Self::new_message_create_linked(channel_id, author, content, None, None)
+    }
+
+    /// creates a message answering `reply_to`, or posted in the thread of `thread`.
+    pub fn new_message_create_linked(
+        channel_id: Id,
+        author: Option<Id>,
+        content: String,
+        reply_to: Option<Id>,
+        thread: Option<Id>,
+    ) -> (Self, Receiver<Id>) {
         let (sender, receiver) = oneshot::channel();
-        let cmd = Self::MessageCreate(channel_id, content, sender);
+        let cmd = Self::MessageCreate(channel_id, author, content, reply_to, thread, sender);
         (cmd, receiver)
     }
 
+    /// moves the message to the trash, from where it can be restored until it is purged.
     pub fn new_message_delete(channel_id: Id, id: Id) -> Self {
         Self::MessageDelete(channel_id, id)
     }
 
-    pub fn new_message_get_content(channel_id: Id, id: Id) -> (Self, Receiver<Option<String>>) {
+    pub fn new_message_restore(channel_id: Id, id: Id) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::MessageRestore(channel_id, id, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_message_get(channel_id: Id, id: Id) -> (Self, Receiver<Option<Message>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::MessageGet(channel_id, id, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets the number of edits of the message, or `None` if it does not exist.
+    pub fn new_message_set_content(
+        channel_id: Id,
+        id: Id,
+        editor: Option<Id>,
+        content: String,
+    ) -> (Self, Receiver<Option<u32>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::MessageSetContent(channel_id, id, editor, content, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_message_get_history(channel_id: Id, id: Id) -> (Self, Receiver<Vec<Revision>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::MessageGetHistory(channel_id, id, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_message_search(query: SearchQuery) -> (Self, Receiver<SearchResults>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::MessageSearch(query, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets whether the reaction was not already there.
+    pub fn new_reaction_add(
+        channel_id: Id,
+        id: Id,
+        emoji: String,
+        user: Id,
+    ) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ReactionAdd(channel_id, id, emoji, user, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets whether the reaction was there.
+    pub fn new_reaction_remove(
+        channel_id: Id,
+        id: Id,
+        emoji: String,
+        user: Id,
+    ) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ReactionRemove(channel_id, id, emoji, user, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_reaction_list(
+        channel_id: Id,
+        id: Id,
+    ) -> (Self, Receiver<BTreeMap<String, Vec<Id>>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ReactionList(channel_id, id, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_reaction_list_users(
+        channel_id: Id,
+        id: Id,
+        emoji: String,
+    ) -> (Self, Receiver<Vec<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ReactionListUsers(channel_id, id, emoji, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets at most `limit` messages of the thread, the lasts posted before `before`.
+    pub fn new_thread_message_list(
+        channel_id: Id,
+        parent: Id,
+        before: Option<Id>,
+        limit: usize,
+    ) -> (Self, Receiver<Vec<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ThreadMessageList(channel_id, parent, before, limit, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_thread_subscribe(channel_id: Id, parent: Id, user: Id) -> Self {
+        Self::ThreadSubscribe(channel_id, parent, user)
+    }
+
+    pub fn new_thread_unsubscribe(channel_id: Id, parent: Id, user: Id) -> Self {
+        Self::ThreadUnsubscribe(channel_id, parent, user)
+    }
+
+    pub fn new_thread_subscribers(channel_id: Id, parent: Id) -> (Self, Receiver<Vec<Id>>) {
         let (sender, receiver) = oneshot::channel();
-        let cmd = Self::MessageGetContent(channel_id, id, sender);
+        let cmd = Self::ThreadSubscribers(channel_id, parent, sender);
         (cmd, receiver)
     }
 
-    pub fn new_message_set_content(channel_id: Id, id: Id, content: String) -> Self {
-        Self::MessageSetContent(channel_id, id, content)
+    /// the receiver gets the channel and id of the mentioning messages.
+    pub fn new_mention_list(
+        user: Id,
+        after: Option<Id>,
+        limit: usize,
+    ) -> (Self, Receiver<Vec<(Id, Id)>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::MentionList(user, after, limit, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_channel_ack(user: Id, channel_id: Id, id: Id) -> Self {
+        Self::ChannelAck(user, channel_id, id)
+    }
+
+    pub fn new_read_state_list(user: Id) -> (Self, Receiver<Vec<ReadState>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::ReadStateList(user, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_message_reindex() -> (Self, Receiver<usize>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::MessageReindex(sender);
+        (cmd, receiver)
     }
 
     pub fn new_user_list() -> (Self, Receiver<Vec<Id>>) {
@@ -87,14 +438,19 @@ impl StorageCmd {
         (cmd, receiver)
     }
 
-    pub fn new_user_create(name: String, pass: String) -> (Self, Receiver<Id>) {
+    /// the receiver gets `None` if the name is already taken.
+    pub fn new_user_create(name: String, pass: String) -> (Self, Receiver<Option<Id>>) {
         let (sender, receiver) = oneshot::channel();
         let cmd = Self::UserCreate(name, pass, sender);
         (cmd, receiver)
     }
 
-    pub fn new_user_delete(id: Id) -> Self {
-        Self::UserDelete(id)
+    /// removes the user along with its name and operator entries, and its messages if `purge` is set.
+    /// the receiver gets the channel and id of each removed message.
+    pub fn new_user_delete(id: Id, purge: bool) -> (Self, Receiver<Vec<(Id, Id)>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::UserDelete(id, purge, sender);
+        (cmd, receiver)
     }
 
     pub fn new_user_get_name(id: Id) -> (Self, Receiver<Option<String>>) {
@@ -103,8 +459,17 @@ impl StorageCmd {
         (cmd, receiver)
     }
 
-    pub fn new_user_set_name(id: Id, name: String) -> Self {
-        Self::UserSetName(id, name)
+    /// the receiver gets `false` if the name is already taken by another user.
+    pub fn new_user_set_name(id: Id, name: String) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::UserSetName(id, name, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_user_find_by_name(name: String) -> (Self, Receiver<Option<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::UserFindByName(name, sender);
+        (cmd, receiver)
     }
 
     pub fn new_user_get_pass(id: Id) -> (Self, Receiver<Option<String>>) {
@@ -117,6 +482,27 @@ impl StorageCmd {
         Self::UserSetPass(id, pass)
     }
 
+    pub fn new_user_get_profile(id: Id) -> (Self, Receiver<Option<Profile>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::UserGetProfile(id, sender);
+        (cmd, receiver)
+    }
+
+    /// sets the fields given in `update`, the receiver gets the resulting profile, `None` if the
+    /// user does not exist.
+    pub fn new_user_update_profile(id: Id, update: Profile) -> (Self, Receiver<Option<Profile>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::UserUpdateProfile(id, update, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets the users sharing a channel with the user, `None` meaning everyone.
+    pub fn new_user_profile_audience(id: Id) -> (Self, Receiver<Option<Vec<Id>>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::UserProfileAudience(id, sender);
+        (cmd, receiver)
+    }
+
     pub fn new_perm_server_add_op(user_id: Id) -> Self {
         Self::PermServerAddOp(user_id)
     }
@@ -144,6 +530,239 @@ impl StorageCmd {
         let command = Self::PermChannelGetOp(channel_id, sender);
         (command, receiver)
     }
+
+    pub fn new_guild_create(name: String, owner: Id) -> (Self, Receiver<Id>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildCreate(name, owner, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_guild_get(id: Id) -> (Self, Receiver<Option<Guild>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildGet(id, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets `false` for unknown guilds and the default one.
+    pub fn new_guild_delete(id: Id) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildDelete(id, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets the guilds of the user, the default one first.
+    pub fn new_guild_list(user: Id) -> (Self, Receiver<Vec<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildList(user, sender);
+        (cmd, receiver)
+    }
+
+    /// adds the user to the guild if `member` or removes it, the receiver gets whether anything
+    /// changed.
+    pub fn new_guild_member_set(id: Id, user: Id, member: bool) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildMemberSet(id, user, member, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_guild_member_list(id: Id) -> (Self, Receiver<Vec<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildMemberList(id, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_guild_is_member(id: Id, user: Id) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildIsMember(id, user, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets `false` if the user is not a member of the guild.
+    pub fn new_guild_set_op(id: Id, user: Id, op: bool) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::GuildSetOp(id, user, op, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_role_create(
+        guild: Id,
+        name: String,
+        permissions: Permissions,
+    ) -> (Self, Receiver<Id>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::RoleCreate(guild, name, permissions, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_role_get(id: Id) -> (Self, Receiver<Option<Role>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::RoleGet(id, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets `false` if the role does not exist.
+    pub fn new_role_update(
+        id: Id,
+        name: String,
+        permissions: Permissions,
+    ) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::RoleUpdate(id, name, permissions, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_role_delete(id: Id) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::RoleDelete(id, sender);
+        (cmd, receiver)
+    }
+
+    /// the receiver gets the roles of the guild, its everyone role first.
+    pub fn new_role_list(guild: Id) -> (Self, Receiver<Vec<Role>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::RoleList(guild, sender);
+        (cmd, receiver)
+    }
+
+    /// assigns the role if `assigned` or unassigns it, the receiver gets whether anything changed.
+    pub fn new_role_assign(role: Id, user: Id, assigned: bool) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::RoleAssign(role, user, assigned, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_user_list_roles(user: Id) -> (Self, Receiver<Vec<Id>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::UserListRoles(user, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_overwrite_set(
+        channel_id: Id,
+        target: OverwriteTarget,
+        overwrite: Overwrite,
+    ) -> Self {
+        Self::OverwriteSet(channel_id, target, overwrite)
+    }
+
+    pub fn new_overwrite_list(
+        channel_id: Id,
+    ) -> (Self, Receiver<Vec<(OverwriteTarget, Overwrite)>>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::OverwriteList(channel_id, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_perm_get_grants(user: Option<Id>, scope: Scope) -> (Self, Receiver<Grants>) {
+        let (sender, receiver) = oneshot::channel();
+        let cmd = Self::PermGetGrants(user, scope, sender);
+        (cmd, receiver)
+    }
+
+    pub fn new_lockout_create(lockout: Lockout) -> Self {
+        Self::LockoutCreate(lockout)
+    }
+
+    pub fn new_lockout_list() -> (Self, Receiver<Vec<Lockout>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::LockoutList(sender);
+        (command, receiver)
+    }
+
+    pub fn new_invite_create(invite: Invite) -> Self {
+        Self::InviteCreate(invite)
+    }
+
+    pub fn new_invite_get(code: String) -> (Self, Receiver<Option<Invite>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::InviteGet(code, sender);
+        (command, receiver)
+    }
+
+    /// the receiver gets the invites to the guild, or the registration ones if `None`.
+    pub fn new_invite_list(guild: Option<Id>) -> (Self, Receiver<Vec<Invite>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::InviteList(guild, sender);
+        (command, receiver)
+    }
+
+    /// the receiver gets `false` if no invite had this code.
+    pub fn new_invite_revoke(code: String) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::InviteRevoke(code, sender);
+        (command, receiver)
+    }
+
+    /// counts a use of the invite, the receiver gets `false` if it was not valid or led to another
+    /// guild, `None` standing for registration.
+    pub fn new_invite_use(code: String, guild: Option<Id>) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::InviteUse(code, guild, sender);
+        (command, receiver)
+    }
+
+    /// bans the user, trashing the messages it posted since `purge_since`, in milliseconds since
+    /// the epoch. the receiver gets the channel and id of each trashed message.
+    pub fn new_ban_create(ban: Ban, purge_since: Option<i64>) -> (Self, Receiver<Vec<(Id, Id)>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::BanCreate(ban, purge_since, sender);
+        (command, receiver)
+    }
+
+    /// the receiver gets the ban of the user, if it is still active.
+    pub fn new_ban_get(user: Id) -> (Self, Receiver<Option<Ban>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::BanGet(user, sender);
+        (command, receiver)
+    }
+
+    /// the receiver gets `false` if the user was not banned.
+    pub fn new_ban_remove(user: Id) -> (Self, Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::BanRemove(user, sender);
+        (command, receiver)
+    }
+
+    /// the receiver gets the active bans.
+    pub fn new_ban_list() -> (Self, Receiver<Vec<Ban>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::BanList(sender);
+        (command, receiver)
+    }
+
+    /// mutes the user until `until`, in milliseconds since the epoch, or lifts its timeout if `None`.
+    pub fn new_timeout_set(user: Id, until: Option<i64>) -> Self {
+        Self::TimeoutSet(user, until)
+    }
+
+    /// the receiver gets the end of the timeout of the user, if it is still active.
+    pub fn new_timeout_get(user: Id) -> (Self, Receiver<Option<i64>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::TimeoutGet(user, sender);
+        (command, receiver)
+    }
+
+    pub fn new_audit_append(entry: AuditEntry) -> Self {
+        Self::AuditAppend(entry)
+    }
+
+    /// the receiver gets the matching entries stored before `before`, newest first, with their ids.
+    pub fn new_audit_list(
+        filter: AuditFilter,
+        before: Option<Id>,
+        limit: usize,
+    ) -> (Self, Receiver<Vec<(Id, AuditEntry)>>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::AuditList(filter, before, limit, sender);
+        (command, receiver)
+    }
+
+    /// removes for good the items deleted before `before`, in milliseconds since the epoch.
+    pub fn new_purge(before: i64) -> (Self, Receiver<Purged>) {
+        let (sender, receiver) = oneshot::channel();
+        let command = Self::Purge(before, sender);
+        (command, receiver)
+    }
 }
 
 pub struct StorageProc {
@@ -157,7 +776,22 @@ impl StorageProc {
     {
         let path = path.to_string();
         let base = sled::open(path).unwrap();
-        Self { base }
+        let result = Self { base };
+        result.index_user_names();
+        result.migrate_guilds();
+        result
+    }
+
+    /// indexes the names of users created before names were unique, the oldest user keeping a shared name.
+    fn index_user_names(&self) {
+        for id in self.list("/users/") {
+            if let Some(user) = self.get::<_, User>(format!("/users/{id}")) {
+                let path = name_path(user.get_name());
+                if self.get::<_, Id>(&path).is_none() {
+                    self.set(path, id);
+                }
+            }
+        }
     }
 
     fn get<S, T>(&self, path: S) -> Option<T>
@@ -186,6 +820,27 @@ impl StorageProc {
         list(db, path)
     }
 
+    fn values<T>(&self, path: impl ToString) -> Vec<T>
+    where
+        T: SerDeser,
+    {
+        let path = path.to_string();
+        println!("[storage/info] reading entries in '{path}'");
+        self.base
+            .scan_prefix(path)
+            .filter_map(|result| T::deser(&result.ok()?.1))
+            .collect()
+    }
+
+    fn keys(&self, path: impl ToString) -> Vec<String> {
+        let path = path.to_string();
+        println!("[storage/info] listing keys in '{path}'");
+        self.base
+            .scan_prefix(path)
+            .filter_map(|result| String::from_utf8(result.ok()?.0.to_vec()).ok())
+            .collect()
+    }
+
     // firsts (x)
     // lasts (x)
     // from (id, x)
@@ -203,38 +858,134 @@ impl StorageProc {
             //
             // Channel
             //
-            ChannelList(sender) => self.on_channel_list(sender),
-            ChannelCreate(name, sender) => self.on_channel_create(name, sender),
-            ChannelDelete(id) => self.on_channel_remove(id),
+            ChannelList(viewer, sender) => self.on_channel_list(viewer, sender),
+            ChannelCreate(name, kind, guild, creator, sender) => {
+                self.on_channel_create(name, kind, guild, creator, sender)
+            }
+            ChannelGet(id, sender) => {
+                let channel = self.get::<_, Channel>(format!("/channels/{id}"));
+                sender.send(channel).unwrap()
+            }
+            ChannelSetPrivate(id, private) => self.on_channel_set_private(id, private),
+            ChannelSetTopic(id, topic) => self.on_channel_set_topic(id, topic),
+            ChannelSetDescription(id, description) => {
+                self.on_channel_set_description(id, description)
+            }
+            ChannelSetKind(id, kind, sender) => self.on_channel_set_kind(id, kind, sender),
+            ChannelAudience(id, sender) => sender.send(self.channel_audience(id)).unwrap(),
+            ChannelMemberAdd(id, user) => self.add_member(id, user),
+            ChannelMemberRemove(id, user) => self.remove_member(id, user),
+            ChannelMemberList(id, sender) => sender.send(self.members(id)).unwrap(),
+            DirectOpen(participants, sender) => {
+                sender.send(self.open_direct(participants)).unwrap()
+            }
+            DirectList(user, sender) => sender.send(self.direct_channels(user)).unwrap(),
+            ChannelDelete(id) => self.trash_channel(id),
+            ChannelRestore(id, sender) => sender.send(self.restore_channel(id)).unwrap(),
             ChannelGetName(id, sender) => self.on_channel_get_name(id, sender),
             ChannelSetName(id, name) => self.on_channel_set_name(id, name),
-            // ChannelGetParent / Set
+            ChannelGetSlowMode(id, sender) => self.on_channel_get_slow_mode(id, sender),
+            ChannelSetSlowMode(id, seconds) => self.on_channel_set_slow_mode(id, seconds),
+            ChannelGetPins(id, sender) => self.on_channel_get_pins(id, sender),
+            ChannelGetPinLimit(id, sender) => self.on_channel_get_pin_limit(id, sender),
+            ChannelSetPinLimit(id, limit) => self.on_channel_set_pin_limit(id, limit),
+            MessagePin(channel_id, id, sender) => sender.send(self.pin(channel_id, id)).unwrap(),
+            MessageUnpin(channel_id, id, sender) => {
+                sender.send(self.unpin(channel_id, id)).unwrap()
+            }
+            ChannelTree(viewer, guild, sender) => {
+                sender.send(self.channel_tree(viewer, guild)).unwrap()
+            }
+            ChannelSetParent(id, parent, sender) => {
+                sender.send(self.set_parent(id, parent)).unwrap()
+            }
+            ChannelReorder(ids) => self.reorder(ids),
 
             //
             // User
             //
             MessageList(channel_id, sender) => self.on_message_list(channel_id, sender),
-            MessageCreate(channel_id, content, sender) => {
-                self.on_message_create(channel_id, content, sender)
+            MessageCreate(channel_id, author, content, reply_to, thread, sender) => {
+                self.on_message_create(channel_id, author, content, reply_to, thread, sender)
+            }
+            MessageDelete(channel_id, id) => self.trash_message(channel_id, id),
+            MessageRestore(channel_id, id, sender) => {
+                sender.send(self.restore_message(channel_id, id)).unwrap()
+            }
+            MessageGet(channel_id, id, sender) => self.on_message_get(channel_id, id, sender),
+            MessageSetContent(channel_id, id, editor, content, sender) => {
+                self.on_message_set_content(channel_id, id, editor, content, sender)
+            }
+            MessageGetHistory(channel_id, id, sender) => {
+                self.on_message_get_history(channel_id, id, sender)
+            }
+            MessageSearch(query, sender) => sender.send(self.search(query)).unwrap(),
+            MessageReindex(sender) => sender.send(self.reindex()).unwrap(),
+
+            //
+            // Reactions
+            //
+            ReactionAdd(channel_id, id, emoji, user, sender) => {
+                let added = self.add_reaction(channel_id, id, &emoji, user);
+                sender.send(added).unwrap();
+            }
+            ReactionRemove(channel_id, id, emoji, user, sender) => {
+                let removed = self.remove_reaction(channel_id, id, &emoji, user);
+                sender.send(removed).unwrap();
+            }
+            ReactionList(channel_id, id, sender) => {
+                sender.send(self.reactions(channel_id, id)).unwrap()
+            }
+            ReactionListUsers(channel_id, id, emoji, sender) => sender
+                .send(self.reaction_users(channel_id, id, &emoji))
+                .unwrap(),
+
+            //
+            // Threads
+            //
+            ThreadMessageList(channel_id, parent, before, limit, sender) => {
+                let messages = self.thread_messages(channel_id, parent, before, limit);
+                sender.send(messages).unwrap();
+            }
+            ThreadSubscribe(channel_id, parent, user) => {
+                self.subscribe_thread(channel_id, parent, user)
             }
-            MessageDelete(channel_id, id) => self.on_message_delete(channel_id, id),
-            MessageGetContent(channel_id, id, sender) => {
-                self.on_message_get_content(channel_id, id, sender)
+            ThreadUnsubscribe(channel_id, parent, user) => {
+                self.unsubscribe_thread(channel_id, parent, user)
             }
-            MessageSetContent(channel_id, id, content) => {
-                self.on_message_set_content(channel_id, id, content)
+            ThreadSubscribers(channel_id, parent, sender) => sender
+                .send(self.thread_subscribers(channel_id, parent))
+                .unwrap(),
+
+            //
+            // Mentions
+            //
+            MentionList(user, after, limit, sender) => {
+                sender.send(self.mentions(user, after, limit)).unwrap()
             }
 
+            //
+            // Read state
+            //
+            ChannelAck(user, channel_id, id) => self.ack(user, channel_id, id),
+            ReadStateList(user, sender) => sender.send(self.read_states(user)).unwrap(),
+
             //
             // User
             //
             UserList(sender) => self.on_user_list(sender),
             UserCreate(name, pass, sender) => self.on_user_create(name, pass, sender),
-            UserDelete(id) => self.on_user_delete(id),
+            UserDelete(id, purge, sender) => self.on_user_delete(id, purge, sender),
             UserGetName(id, sender) => self.on_user_get_name(id, sender),
-            UserSetName(id, name) => self.on_user_set_name(id, name),
+            UserSetName(id, name, sender) => self.on_user_set_name(id, name, sender),
+            UserFindByName(name, sender) => self.on_user_find_by_name(name, sender),
             UserGetPass(id, sender) => self.on_user_get_pass(id, sender),
             UserSetPass(id, pass) => self.on_user_set_pass(id, pass),
+            UserGetProfile(id, sender) => sender.send(self.get_profile(id)).unwrap(),
+            UserUpdateProfile(id, update, sender) => {
+                sender.send(self.update_profile(id, update)).unwrap()
+            }
+            UserProfileAudience(id, sender) => sender.send(self.profile_audience(id)).unwrap(),
 
             //
             // Perms
@@ -255,31 +1006,119 @@ impl StorageProc {
                 let result = self.list(format!("/op/channels/{channel_id}/"));
                 sender.send(result).unwrap();
             }
+
+            //
+            // Guilds
+            //
+            GuildCreate(name, owner, sender) => {
+                sender.send(self.create_guild(name, owner)).unwrap()
+            }
+            GuildGet(id, sender) => sender.send(self.get_guild(id)).unwrap(),
+            GuildDelete(id, sender) => sender.send(self.delete_guild(id)).unwrap(),
+            GuildList(user, sender) => sender.send(self.user_guilds(user)).unwrap(),
+            GuildMemberSet(id, user, member, sender) => sender
+                .send(self.set_guild_member(id, user, member))
+                .unwrap(),
+            GuildMemberList(id, sender) => sender.send(self.guild_members(id)).unwrap(),
+            GuildIsMember(id, user, sender) => sender.send(self.is_guild_member(id, user)).unwrap(),
+            GuildSetOp(id, user, op, sender) => {
+                sender.send(self.set_guild_op(id, user, op)).unwrap()
+            }
+
+            //
+            // Roles
+            //
+            RoleCreate(guild, name, permissions, sender) => sender
+                .send(self.create_role(guild, name, permissions))
+                .unwrap(),
+            RoleGet(id, sender) => sender.send(self.get_role(id)).unwrap(),
+            RoleUpdate(id, name, permissions, sender) => sender
+                .send(self.update_role(id, name, permissions))
+                .unwrap(),
+            RoleDelete(id, sender) => sender.send(self.delete_role(id)).unwrap(),
+            RoleList(guild, sender) => sender.send(self.roles(guild)).unwrap(),
+            RoleAssign(role, user, assigned, sender) => {
+                sender.send(self.assign_role(role, user, assigned)).unwrap()
+            }
+            UserListRoles(user, sender) => sender.send(self.user_roles(user)).unwrap(),
+            OverwriteSet(channel_id, target, overwrite) => {
+                self.set_overwrite(channel_id, target, overwrite)
+            }
+            OverwriteList(channel_id, sender) => sender.send(self.overwrites(channel_id)).unwrap(),
+            PermGetGrants(user, scope, sender) => sender.send(self.grants(user, scope)).unwrap(),
+
+            //
+            // Lockouts
+            //
+            LockoutCreate(lockout) => self.on_lockout_create(lockout),
+            LockoutList(sender) => self.on_lockout_list(sender),
+
+            //
+            // Invites
+            //
+            InviteCreate(invite) => self.on_invite_create(invite),
+            InviteGet(code, sender) => self.on_invite_get(code, sender),
+            InviteList(guild, sender) => self.on_invite_list(guild, sender),
+            InviteRevoke(code, sender) => self.on_invite_revoke(code, sender),
+            InviteUse(code, guild, sender) => self.on_invite_use(code, guild, sender),
+
+            //
+            // Moderation
+            //
+            BanCreate(ban, purge_since, sender) => {
+                sender.send(self.create_ban(ban, purge_since)).unwrap()
+            }
+            BanGet(user, sender) => sender.send(self.get_ban(user)).unwrap(),
+            BanRemove(user, sender) => sender.send(self.remove_ban(user)).unwrap(),
+            BanList(sender) => sender.send(self.bans()).unwrap(),
+            TimeoutSet(user, until) => self.set_timeout(user, until),
+            TimeoutGet(user, sender) => sender.send(self.get_timeout(user)).unwrap(),
+
+            //
+            // Audit
+            //
+            AuditAppend(entry) => self.append_audit(entry),
+            AuditList(filter, before, limit, sender) => {
+                sender.send(self.audit_log(&filter, before, limit)).unwrap()
+            }
+
+            //
+            // Trash
+            //
+            Purge(before, sender) => sender.send(self.purge(before)).unwrap(),
         };
     }
 
     //
     // Channels
     //
-    fn on_channel_list(&mut self, sender: Sender<Vec<Id>>) {
-        let results = self.list("/channels/");
+    /// direct channels are left out, they are only listed to their participants.
+    fn on_channel_list(&mut self, viewer: Option<Id>, sender: Sender<Vec<Id>>) {
+        let results = self
+            .channel_tree(viewer, Guild::DEFAULT)
+            .into_iter()
+            .flat_map(|(id, children)| std::iter::once(id).chain(children))
+            .collect();
         sender.send(results).unwrap();
     }
 
-    fn on_channel_create(&mut self, name: String, sender: Sender<Id>) {
-        let item = Channel::new(name);
+    fn on_channel_create(
+        &mut self,
+        name: String,
+        kind: ChannelKind,
+        guild: Id,
+        creator: Option<Id>,
+        sender: Sender<Id>,
+    ) {
+        let mut item = Channel::new(name);
+        item.set_kind(kind);
+        item.set_guild(guild);
+        item.set_creator(creator);
         let id = item.get_id();
         self.set(format!("/channels/{id}"), item);
         sender.send(id).unwrap();
     }
 
-    fn on_channel_remove(&mut self, id: Id) {
-        for message_id in self.list(format!("/messages/{id}/")) {
-            self.remove(format!("/messages/{id}/{message_id}"))
-        }
-        self.remove(format!("/channels/{id}"))
-    }
-
     fn on_channel_get_name(&mut self, id: Id, sender: Sender<Option<String>>) {
         let channel = self.get::<_, Channel>(format!("/channels/{id}"));
         let name = channel.map(|channel| channel.get_name().to_string());
@@ -294,37 +1133,205 @@ impl StorageProc {
         }
     }
 
+    fn on_channel_get_slow_mode(&mut self, id: Id, sender: Sender<Option<u64>>) {
+        let channel = self.get::<_, Channel>(format!("/channels/{id}"));
+        let seconds = channel.map(|channel| channel.get_slow_mode());
+        sender.send(seconds).unwrap();
+    }
+
+    fn on_channel_set_slow_mode(&mut self, id: Id, seconds: u64) {
+        let path = format!("/channels/{id}");
+        if let Some(mut channel) = self.get::<_, Channel>(&path) {
+            channel.set_slow_mode(seconds);
+            self.set(path, channel);
+        }
+    }
+
+    fn on_channel_set_private(&mut self, id: Id, private: bool) {
+        let path = format!("/channels/{id}");
+        if let Some(mut channel) = self.get::<_, Channel>(&path) {
+            channel.set_private(private);
+            self.set(path, channel);
+        }
+    }
+
+    fn on_channel_set_topic(&mut self, id: Id, topic: String) {
+        let path = format!("/channels/{id}");
+        if let Some(mut channel) = self.get::<_, Channel>(&path) {
+            channel.set_topic(topic);
+            self.set(path, channel);
+        }
+    }
+
+    fn on_channel_set_description(&mut self, id: Id, description: String) {
+        let path = format!("/channels/{id}");
+        if let Some(mut channel) = self.get::<_, Channel>(&path) {
+            channel.set_description(description);
+            self.set(path, channel);
+        }
+    }
+
+    /// channels can not be turned into categories nor the reverse, as only the former hold messages.
+    fn on_channel_set_kind(&mut self, id: Id, kind: ChannelKind, sender: Sender<bool>) {
+        let path = format!("/channels/{id}");
+        let result = match self.get::<_, Channel>(&path) {
+            Some(mut channel)
+                if !channel.is_direct()
+                    && channel.is_category() == (kind == ChannelKind::Category) =>
+            {
+                channel.set_kind(kind);
+                self.set(path, channel);
+                true
+            }
+            _ => false,
+        };
+        sender.send(result).unwrap();
+    }
+
+    fn on_channel_get_pins(&mut self, id: Id, sender: Sender<Vec<Id>>) {
+        let channel = self.get::<_, Channel>(format!("/channels/{id}"));
+        let mut pins = channel.map(|c| c.get_pins().to_vec()).unwrap_or_default();
+        pins.retain(|message| {
+            self.base
+                .contains_key(format!("/messages/{id}/{message}"))
+                .unwrap()
+        });
+        sender.send(pins).unwrap();
+    }
+
+    fn on_channel_get_pin_limit(&mut self, id: Id, sender: Sender<Option<u32>>) {
+        let channel = self.get::<_, Channel>(format!("/channels/{id}"));
+        let limit = channel.and_then(|channel| channel.get_pin_limit());
+        sender.send(limit).unwrap();
+    }
+
+    fn on_channel_set_pin_limit(&mut self, id: Id, limit: Option<u32>) {
+        let path = format!("/channels/{id}");
+        if let Some(mut channel) = self.get::<_, Channel>(&path) {
+            channel.set_pin_limit(limit);
+            self.set(path, channel);
+        }
+    }
+
+    fn pin(&mut self, channel_id: Id, id: Id) -> bool {
+        let path = format!("/channels/{channel_id}");
+        let Some(mut channel) = self.get::<_, Channel>(&path) else {
+            return false;
+        };
+        let pinned = channel.pin(id);
+        self.set(path, channel);
+        pinned
+    }
+
+    fn unpin(&mut self, channel_id: Id, id: Id) -> bool {
+        let path = format!("/channels/{channel_id}");
+        let Some(mut channel) = self.get::<_, Channel>(&path) else {
+            return false;
+        };
+        let unpinned = channel.unpin(id);
+        self.set(path, channel);
+        unpinned
+    }
+
     //
     // Messages
     //
+    /// messages posted in threads are left out, they are listed with their thread.
     fn on_message_list(&mut self, channel_id: Id, sender: Sender<Vec<Id>>) {
-        let items = self.list(format!("/messages/{channel_id}/"));
+        let threaded = self.threaded_messages(channel_id);
+        let mut items = self.list(format!("/messages/{channel_id}/"));
+        items.retain(|id| !threaded.contains(id));
         sender.send(items).unwrap();
     }
 
-    fn on_message_create(&mut self, channel_id: Id, content: String, sender: Sender<Id>) {
-        let message = Message::new(content);
+    fn on_message_create(
+        &mut self,
+        channel_id: Id,
+        author: Option<Id>,
+        content: String,
+        reply_to: Option<Id>,
+        thread: Option<Id>,
+        sender: Sender<Id>,
+    ) {
+        let mut message = Message::new(content, author);
+        message.set_reply_to(reply_to);
+        message.set_thread(thread);
+        let mentions = self.resolve_mentions(channel_id, message.get_content());
+        message.set_mentions(mentions);
         let id = message.get_id();
+        self.index_message(channel_id, &message);
+        self.index_mentions(channel_id, &message);
+        self.attach_to_thread(channel_id, &message);
+        if let Some(parent) = thread {
+            let parent_author = self
+                .get::<_, Message>(format!("/messages/{channel_id}/{parent}"))
+                .and_then(|parent| parent.get_author());
+            for user in author.into_iter().chain(parent_author) {
+                self.subscribe_thread(channel_id, parent, user);
+            }
+        }
         self.set(format!("/messages/{channel_id}/{id}"), message);
         sender.send(id).unwrap();
     }
 
-    fn on_message_delete(&mut self, channel_id: Id, id: Id) {
-        self.remove(format!("/messages/{channel_id}/{id}"));
+    fn remove_message(&mut self, channel_id: Id, id: Id) {
+        let path = format!("/messages/{channel_id}/{id}");
+        if let Some(message) = self.get::<_, Message>(&path) {
+            self.unindex_message(channel_id, &message);
+            self.unindex_mentions(&message);
+            self.detach_from_thread(channel_id, &message);
+        }
+        self.unpin(channel_id, id);
+        self.remove_message_entries(channel_id, id);
+        self.remove(path);
     }
 
-    fn on_message_get_content(&mut self, channel_id: Id, id: Id, sender: Sender<Option<String>>) {
+    /// removes the revisions and reactions stored under a message.
+    fn remove_message_entries(&mut self, channel_id: Id, id: Id) {
+        for key in self.keys(format!("/messages/{channel_id}/{id}/")) {
+            self.remove(key);
+        }
+    }
+
+    fn on_message_get(&mut self, channel_id: Id, id: Id, sender: Sender<Option<Message>>) {
         let message = self.get::<_, Message>(format!("/messages/{channel_id}/{id}"));
-        let content = message.map(|m| m.get_content().to_string());
-        sender.send(content).unwrap()
+        sender.send(message).unwrap()
     }
 
-    fn on_message_set_content(&mut self, channel_id: Id, id: Id, content: String) {
+    fn on_message_set_content(
+        &mut self,
+        channel_id: Id,
+        id: Id,
+        editor: Option<Id>,
+        content: String,
+        sender: Sender<Option<u32>>,
+    ) {
         let path = format!("/messages/{channel_id}/{id}");
-        if let Some(mut message) = self.get::<_, Message>(&path) {
-            message.set_content(content);
+        let message = self.get::<_, Message>(&path);
+        let edits = message.map(|mut message| {
+            if message.get_edits() == 0 {
+                let original = Revision::original(&message);
+                self.set(revision_path(channel_id, id, 0), original);
+            }
+            self.unindex_message(channel_id, &message);
+            self.unindex_mentions(&message);
+            message.set_content(content.clone());
+            let mentions = self.resolve_mentions(channel_id, &content);
+            message.set_mentions(mentions);
+            self.index_message(channel_id, &message);
+            self.index_mentions(channel_id, &message);
+            let edits = message.get_edits();
             self.set(path, message);
-        }
+            let revision = Revision::new(content, editor);
+            self.set(revision_path(channel_id, id, edits), revision);
+            edits
+        });
+        sender.send(edits).unwrap();
+    }
+
+    fn on_message_get_history(&mut self, channel_id: Id, id: Id, sender: Sender<Vec<Revision>>) {
+        let revisions = self.values(format!("/messages/{channel_id}/{id}/revisions/"));
+        sender.send(revisions).unwrap();
     }
 
     //
@@ -336,15 +1343,66 @@ impl StorageProc {
         sender.send(users).unwrap();
     }
 
-    fn on_user_create(&mut self, name: String, pass: String, sender: Sender<Id>) {
+    fn on_user_create(&mut self, name: String, pass: String, sender: Sender<Option<Id>>) {
+        let name_path = name_path(&name);
+        if self.get::<_, Id>(&name_path).is_some() {
+            return sender.send(None).unwrap();
+        }
         let user = User::new(name, pass);
         let id = user.get_id();
         self.set(format!("/users/{id}"), user);
-        sender.send(id).unwrap();
+        self.set(name_path, id);
+        sender.send(Some(id)).unwrap();
     }
 
-    fn on_user_delete(&mut self, id: Id) {
-        self.remove(format!("/users/{id}"));
+    fn on_user_delete(&mut self, id: Id, purge: bool, sender: Sender<Vec<(Id, Id)>>) {
+        let path = format!("/users/{id}");
+        if let Some(user) = self.get::<_, User>(&path) {
+            let name_path = name_path(user.get_name());
+            if self.get::<_, Id>(&name_path) == Some(id) {
+                self.remove(name_path);
+            }
+        }
+        self.remove(path);
+
+        self.remove(format!("/op/serv/{id}"));
+        self.remove_user_guilds(id);
+        let suffix = format!("/{id}");
+        for key in self.keys("/op/channels/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+        for key in self.keys(format!("/mentions/{id}/")) {
+            self.remove(key);
+        }
+        for key in self.keys(format!("/read/{id}/")) {
+            self.remove(key);
+        }
+        for key in self.keys(format!("/direct/users/{id}/")) {
+            self.remove(key);
+        }
+        for key in self.keys("/members/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+        self.remove_user_grants(id);
+
+        let mut removed = Vec::new();
+        if purge {
+            for channel_id in self.list("/channels/") {
+                for message_id in self.list(format!("/messages/{channel_id}/")) {
+                    let path = format!("/messages/{channel_id}/{message_id}");
+                    let message = self.get::<_, Message>(&path);
+                    if message.and_then(|m| m.get_author()) == Some(id) {
+                        self.remove_message(channel_id, message_id);
+                        removed.push((channel_id, message_id));
+                    }
+                }
+            }
+        }
+        sender.send(removed).unwrap();
     }
 
     fn on_user_get_name(&mut self, id: Id, sender: Sender<Option<String>>) {
@@ -353,12 +1411,30 @@ impl StorageProc {
         sender.send(name).unwrap();
     }
 
-    fn on_user_set_name(&mut self, id: Id, name: String) {
+    fn on_user_set_name(&mut self, id: Id, name: String, sender: Sender<bool>) {
         let path = format!("/users/{id}");
-        if let Some(mut user) = self.get::<_, User>(&path) {
-            user.set_name(name);
-            self.set(path, user);
+        let new_path = name_path(&name);
+        let available = match self.get::<_, Id>(&new_path) {
+            Some(owner) => owner == id,
+            None => true,
+        };
+        let user = self.get::<_, User>(&path);
+        let (Some(mut user), true) = (user, available) else {
+            return sender.send(false).unwrap();
+        };
+        let old_path = name_path(user.get_name());
+        if self.get::<_, Id>(&old_path) == Some(id) {
+            self.remove(old_path);
         }
+        user.set_name(name);
+        self.set(path, user);
+        self.set(new_path, id);
+        sender.send(true).unwrap();
+    }
+
+    fn on_user_find_by_name(&mut self, name: String, sender: Sender<Option<Id>>) {
+        let id = self.get::<_, Id>(name_path(&name));
+        sender.send(id).unwrap();
     }
 
     fn on_user_get_pass(&mut self, id: Id, sender: Sender<Option<String>>) {
@@ -374,6 +1450,68 @@ impl StorageProc {
             self.set(path, user);
         }
     }
+
+    //
+    // Lockouts
+    //
+
+    fn on_lockout_create(&mut self, lockout: Lockout) {
+        let id = lockout.get_id();
+        self.set(format!("/lockouts/{id}"), lockout);
+    }
+
+    fn on_lockout_list(&mut self, sender: Sender<Vec<Lockout>>) {
+        let lockouts = self
+            .list("/lockouts/")
+            .into_iter()
+            .filter_map(|id| self.get(format!("/lockouts/{id}")))
+            .collect();
+        sender.send(lockouts).unwrap();
+    }
+
+    //
+    // Invites
+    //
+
+    fn on_invite_create(&mut self, invite: Invite) {
+        let code = invite.get_code().to_string();
+        self.set(format!("/invites/{code}"), invite);
+    }
+
+    fn on_invite_get(&mut self, code: String, sender: Sender<Option<Invite>>) {
+        let invite = self.get(format!("/invites/{code}"));
+        sender.send(invite).unwrap();
+    }
+
+    fn on_invite_list(&mut self, guild: Option<Id>, sender: Sender<Vec<Invite>>) {
+        let invites = self
+            .values::<Invite>("/invites/")
+            .into_iter()
+            .filter(|invite| invite.get_guild() == guild)
+            .collect();
+        sender.send(invites).unwrap();
+    }
+
+    fn on_invite_revoke(&mut self, code: String, sender: Sender<bool>) {
+        let path = format!("/invites/{code}");
+        let existed = self.get::<_, Invite>(&path).is_some();
+        self.remove(path);
+        sender.send(existed).unwrap();
+    }
+
+    fn on_invite_use(&mut self, code: String, guild: Option<Id>, sender: Sender<bool>) {
+        let path = format!("/invites/{code}");
+        let now = chrono::Utc::now().timestamp_millis();
+        let result = match self.get::<_, Invite>(&path) {
+            Some(mut invite) if invite.is_valid(now) && invite.get_guild() == guild => {
+                invite.add_use();
+                self.set(path, invite);
+                true
+            }
+            _ => false,
+        };
+        sender.send(result).unwrap();
+    }
 }
 
 #[telecomande::async_trait]
@@ -388,8 +1526,59 @@ impl Processor for StorageProc {
     }
 }
 
+mod search;
+pub use search::{SearchQuery, SearchResults, MAX_SEARCH_LIMIT};
+
+mod trash;
+pub use trash::Purged;
+
+mod threads;
+
+mod reactions;
+pub use reactions::is_valid_emoji;
+
+mod mentions;
+pub use mentions::mentions_everyone;
+
+mod direct;
+
+mod profiles;
+pub use profiles::is_valid_profile;
+
+mod guilds;
+
+mod moderation;
+
+mod audit;
+pub use audit::{AuditFilter, MAX_AUDIT_PAGE};
+
+mod categories;
+pub use categories::Tree;
+
+mod members;
+
+mod roles;
+pub use roles::Grants;
+
+mod read_state;
+pub use read_state::ReadState;
+
 mod models;
-pub use models::{Channel, Message, Perm, SerDeser, User};
+pub use models::{
+    AuditEntry, Ban, Channel, ChannelKind, Guild, Invite, LockTarget, Lockout, Message, Overwrite,
+    OverwriteTarget, Perm, Permissions, Profile, Revision, Role, Scope, SerDeser, User,
+};
+
+/// path of a revision of a message, the original content being the revision 0.
+fn revision_path(channel_id: Id, message_id: Id, index: u32) -> String {
+    format!("/messages/{channel_id}/{message_id}/revisions/{index:010}")
+}
+
+/// path of the entry indexing a user by its name, which is unique regardless of case.
+fn name_path(name: &str) -> String {
+    let name = name.to_lowercase();
+    format!("/names/users/{name}")
+}
 
 fn list(db: &Db, path: String) -> Vec<Id> {
     let len = path.len();
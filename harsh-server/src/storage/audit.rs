@@ -0,0 +1,99 @@
+use super::{AuditEntry, SerDeser, StorageProc};
+use crate::Id;
+
+pub const MAX_AUDIT_PAGE: usize = 100;
+
+const AUDIT_PREFIX: &str = "/audit/";
+
+fn audit_path(id: Id) -> String {
+    format!("{AUDIT_PREFIX}{id}")
+}
+
+fn parse_id(key: &[u8]) -> Option<Id> {
+    let key = std::str::from_utf8(key).ok()?;
+    Id::from_string(key.strip_prefix(AUDIT_PREFIX)?)
+}
+
+/// criteria an audit entry has to meet to be listed, `None` accepting anything.
+#[derive(Debug, Default)]
+pub struct AuditFilter {
+    pub actor: Option<Id>,
+    pub action: Option<String>,
+    pub target: Option<Id>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.actor
+            .is_none_or(|actor| entry.get_actor() == Some(actor))
+            && self
+                .action
+                .as_ref()
+                .is_none_or(|action| entry.get_action() == action)
+            && self
+                .target
+                .is_none_or(|target| entry.get_target() == Some(target))
+    }
+}
+
+impl StorageProc {
+    /// entries are never overwritten nor removed, each one getting an id above the previous ones
+    /// so that the log keeps the order of the changes.
+    pub(super) fn append_audit(&mut self, entry: AuditEntry) {
+        let mut id = Id::from_now();
+        let last = self.base.scan_prefix(AUDIT_PREFIX).keys().next_back();
+        if let Some(last) = last.and_then(|key| parse_id(&key.ok()?)) {
+            id = id.max(Id::from_u64(last.to_u64() + 1));
+        }
+        self.set(audit_path(id), entry);
+    }
+
+    pub(super) fn audit_log(
+        &self,
+        filter: &AuditFilter,
+        before: Option<Id>,
+        limit: usize,
+    ) -> Vec<(Id, AuditEntry)> {
+        // ids taken from the clock share their length, so keys sort like them and are walked
+        // from the newest one without loading the rest of the log
+        let entries = match before {
+            Some(before) => self
+                .base
+                .range(AUDIT_PREFIX.to_string()..audit_path(before)),
+            None => self.base.scan_prefix(AUDIT_PREFIX),
+        };
+        entries
+            .rev()
+            .filter_map(|result| {
+                let (key, value) = result.ok()?;
+                Some((parse_id(&key)?, AuditEntry::deser(&value)?))
+            })
+            .filter(|(_, entry)| filter.matches(entry))
+            .take(limit.clamp(1, MAX_AUDIT_PAGE))
+            .collect()
+    }
+}
+
+#[test]
+fn test_filter() {
+    let actor = Id::from_u64(1);
+    let entry = AuditEntry::new(Some(actor), "channel_delete", Some(Id::from_u64(2)));
+    assert!(AuditFilter::default().matches(&entry));
+    let filter = AuditFilter {
+        actor: Some(actor),
+        action: Some("channel_delete".into()),
+        ..Default::default()
+    };
+    assert!(filter.matches(&entry));
+    let filter = AuditFilter {
+        target: Some(actor),
+        ..Default::default()
+    };
+    assert!(!filter.matches(&entry));
+    let anonymous = AuditEntry::new(None, "channel_create", None);
+    let filter = AuditFilter {
+        actor: Some(actor),
+        ..Default::default()
+    };
+    assert!(!filter.matches(&anonymous));
+}
@@ -0,0 +1,76 @@
+use super::{Channel, StorageProc};
+use crate::Id;
+
+/// top level channels in order, each with the channels of its category.
+pub type Tree = Vec<(Id, Vec<Id>)>;
+
+impl StorageProc {
+    /// the channels of a guild a viewer is allowed in, in order, each with the channels of its
+    /// category. channels whose category is deleted or hidden are listed at the top level.
+    pub(super) fn channel_tree(&self, viewer: Option<Id>, guild: Id) -> Tree {
+        let mut channels: Vec<_> = self
+            .list("/channels/")
+            .into_iter()
+            .filter_map(|id| self.get::<_, Channel>(format!("/channels/{id}")))
+            .filter(|channel| !channel.is_direct() && channel.get_guild() == guild)
+            .filter(|channel| self.can_see(channel.get_id(), viewer))
+            .collect();
+        channels.sort_by_key(|channel| (channel.get_position(), channel.get_id()));
+
+        let categories: Vec<_> = channels
+            .iter()
+            .filter(|channel| channel.is_category())
+            .map(Channel::get_id)
+            .collect();
+        let mut tree = Tree::new();
+        for channel in &channels {
+            match channel.get_parent() {
+                Some(parent) if categories.contains(&parent) => {}
+                _ => tree.push((channel.get_id(), Vec::new())),
+            }
+        }
+        for channel in &channels {
+            let Some(parent) = channel.get_parent() else {
+                continue;
+            };
+            if let Some((_, children)) = tree.iter_mut().find(|(id, _)| id == &parent) {
+                children.push(channel.get_id());
+            }
+        }
+        tree
+    }
+
+    /// categories can not be nested nor hold direct channels or those of another guild, returns
+    /// whether the parent was set.
+    pub(super) fn set_parent(&mut self, id: Id, parent: Option<Id>) -> bool {
+        let path = format!("/channels/{id}");
+        let Some(mut channel) = self.get::<_, Channel>(&path) else {
+            return false;
+        };
+        if channel.is_category() || channel.is_direct() {
+            return false;
+        }
+        if let Some(parent) = parent {
+            let parent = self.get::<_, Channel>(format!("/channels/{parent}"));
+            let valid = parent
+                .map(|parent| parent.is_category() && parent.get_guild() == channel.get_guild());
+            if !valid.unwrap_or(false) {
+                return false;
+            }
+        }
+        channel.set_parent(parent);
+        self.set(path, channel);
+        true
+    }
+
+    /// ranks the given channels in order, the others keeping their position.
+    pub(super) fn reorder(&mut self, ids: Vec<Id>) {
+        for (position, id) in ids.into_iter().enumerate() {
+            let path = format!("/channels/{id}");
+            if let Some(mut channel) = self.get::<_, Channel>(&path) {
+                channel.set_position(position as u32);
+                self.set(path, channel);
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeSet;
+
+use super::{Channel, StorageProc};
+use crate::Id;
+
+/// identifies the direct channel of a group of users, whatever the order they are given in.
+fn group_path(participants: &[Id]) -> String {
+    let key = participants
+        .iter()
+        .map(Id::to_string)
+        .collect::<Vec<_>>()
+        .join("-");
+    format!("/direct/groups/{key}")
+}
+
+fn participation_path(user: Id, channel_id: Id) -> String {
+    format!("/direct/users/{user}/{channel_id}")
+}
+
+impl StorageProc {
+    /// returns the direct channel between the given users, and whether it had to be created.
+    pub(super) fn open_direct(&mut self, participants: Vec<Id>) -> (Id, bool) {
+        let participants = participants
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let path = group_path(&participants);
+        if let Some(id) = self.get::<_, Id>(&path) {
+            return (id, false);
+        }
+        let channel = Channel::new_direct(participants.clone());
+        let id = channel.get_id();
+        self.set(format!("/channels/{id}"), channel);
+        self.set(path, id);
+        for user in participants {
+            self.set(participation_path(user, id), true);
+        }
+        (id, true)
+    }
+
+    /// direct channels a user takes part in, deleted ones included.
+    pub(super) fn direct_channels(&self, user: Id) -> Vec<Id> {
+        self.list(format!("/direct/users/{user}/"))
+    }
+
+    /// drops the indexes of a purged direct channel.
+    pub(super) fn remove_direct(&mut self, channel: &Channel) {
+        if !channel.is_direct() {
+            return;
+        }
+        let id = channel.get_id();
+        self.remove(group_path(channel.get_participants()));
+        for user in channel.get_participants() {
+            self.remove(participation_path(*user, id));
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::{Channel, Guild, Invite, StorageProc};
+use crate::Id;
+
+fn guild_path(id: Id) -> String {
+    format!("/guilds/{id}")
+}
+
+fn guild_member_path(guild: Id, user: Id) -> String {
+    format!("/guild-members/{guild}/{user}")
+}
+
+fn guild_op_path(guild: Id, user: Id) -> String {
+    format!("/op/guilds/{guild}/{user}")
+}
+
+impl StorageProc {
+    /// creates the default guild, which holds the channels and roles created before guilds.
+    pub(super) fn migrate_guilds(&self) {
+        if self.get_guild(Guild::DEFAULT).is_none() {
+            println!("[storage/info] creating the default guild");
+            self.set(guild_path(Guild::DEFAULT), Guild::default_guild());
+        }
+    }
+
+    pub(super) fn get_guild(&self, id: Id) -> Option<Guild> {
+        self.get(guild_path(id))
+    }
+
+    pub(super) fn create_guild(&mut self, name: String, owner: Id) -> Id {
+        let guild = Guild::new(name, owner);
+        let id = guild.get_id();
+        self.set(guild_path(id), guild);
+        self.set(guild_member_path(id, owner), true);
+        id
+    }
+
+    /// drops a guild with its members, roles and invites, and moves its channels to the trash.
+    /// the default guild can not be deleted.
+    pub(super) fn delete_guild(&mut self, id: Id) -> bool {
+        if id == Guild::DEFAULT || self.get_guild(id).is_none() {
+            return false;
+        }
+        for channel_id in self.list("/channels/") {
+            if self.guild_of(channel_id) == Some(id) {
+                self.trash_channel(channel_id);
+            }
+        }
+        for role in self.roles(id).into_iter().skip(1) {
+            self.delete_role(role.get_id());
+        }
+        self.remove(format!("/roles/{id}"));
+        for key in self.keys(format!("/guild-members/{id}/")) {
+            self.remove(key);
+        }
+        for key in self.keys(format!("/op/guilds/{id}/")) {
+            self.remove(key);
+        }
+        for invite in self.values::<Invite>("/invites/") {
+            if invite.get_guild() == Some(id) {
+                self.remove(format!("/invites/{}", invite.get_code()));
+            }
+        }
+        self.remove(guild_path(id));
+        true
+    }
+
+    /// guilds a user is a member of, the default one first.
+    pub(super) fn user_guilds(&self, user: Id) -> Vec<Id> {
+        let mut guilds = vec![Guild::DEFAULT];
+        guilds.extend(
+            self.list("/guilds/")
+                .into_iter()
+                .filter(|id| id != &Guild::DEFAULT && self.is_guild_member(*id, user)),
+        );
+        guilds
+    }
+
+    /// every user is a member of the default guild.
+    pub(super) fn is_guild_member(&self, guild: Id, user: Id) -> bool {
+        if guild == Guild::DEFAULT {
+            return self.base.contains_key(format!("/users/{user}")).unwrap();
+        }
+        self.base
+            .contains_key(guild_member_path(guild, user))
+            .unwrap()
+    }
+
+    pub(super) fn guild_members(&self, guild: Id) -> Vec<Id> {
+        if guild == Guild::DEFAULT {
+            return self.list("/users/");
+        }
+        self.list(format!("/guild-members/{guild}/"))
+    }
+
+    /// returns whether the membership changed, the default guild being open to every user.
+    pub(super) fn set_guild_member(&mut self, guild: Id, user: Id, member: bool) -> bool {
+        if guild == Guild::DEFAULT || self.get_guild(guild).is_none() {
+            return false;
+        }
+        if self.is_guild_member(guild, user) == member {
+            return false;
+        }
+        if member {
+            self.set(guild_member_path(guild, user), true);
+            return true;
+        }
+        self.remove(guild_member_path(guild, user));
+        self.remove(guild_op_path(guild, user));
+        for role in self.user_roles(user) {
+            let in_guild = self.get_role(role).map(|role| role.get_guild() == guild);
+            if in_guild.unwrap_or(false) {
+                self.assign_role(role, user, false);
+            }
+        }
+        true
+    }
+
+    /// the owner of a guild is always one of its operators.
+    pub(super) fn guild_ops(&self, guild: Id) -> Vec<Id> {
+        let mut ops = self.list(format!("/op/guilds/{guild}/"));
+        if let Some(owner) = self.get_guild(guild).and_then(|guild| guild.get_owner()) {
+            ops.push(owner);
+        }
+        ops
+    }
+
+    pub(super) fn is_guild_op(&self, guild: Id, user: Id) -> bool {
+        self.guild_ops(guild).contains(&user)
+    }
+
+    /// only members can be made operators.
+    pub(super) fn set_guild_op(&mut self, guild: Id, user: Id, op: bool) -> bool {
+        if op && !self.is_guild_member(guild, user) {
+            return false;
+        }
+        match op {
+            true => self.set(guild_op_path(guild, user), true),
+            false => self.remove(guild_op_path(guild, user)),
+        }
+        true
+    }
+
+    pub(super) fn guild_of(&self, channel_id: Id) -> Option<Id> {
+        let channel = self.get::<_, Channel>(format!("/channels/{channel_id}"))?;
+        Some(channel.get_guild())
+    }
+
+    /// drops the memberships of a deleted user.
+    pub(super) fn remove_user_guilds(&mut self, user: Id) {
+        let suffix = format!("/{user}");
+        for key in self.keys("/guild-members/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+        for key in self.keys("/op/guilds/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+    }
+}
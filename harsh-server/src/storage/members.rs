@@ -0,0 +1,74 @@
+use std::collections::BTreeSet;
+
+use super::{Channel, Guild, StorageProc};
+use crate::Id;
+
+fn member_path(channel_id: Id, user: Id) -> String {
+    format!("/members/{channel_id}/{user}")
+}
+
+impl StorageProc {
+    pub(super) fn add_member(&mut self, channel_id: Id, user: Id) {
+        self.set(member_path(channel_id, user), true);
+    }
+
+    pub(super) fn remove_member(&mut self, channel_id: Id, user: Id) {
+        self.remove(member_path(channel_id, user));
+    }
+
+    pub(super) fn members(&self, channel_id: Id) -> Vec<Id> {
+        self.list(format!("/members/{channel_id}/"))
+    }
+
+    /// the users allowed in a channel, `None` when it is open to everyone.
+    /// channels of a guild are open to its members, private ones to their members and operators,
+    /// and direct ones to their participants only. missing and deleted channels are open to nobody.
+    pub(super) fn channel_audience(&self, channel_id: Id) -> Option<Vec<Id>> {
+        let Some(channel) = self.get::<_, Channel>(format!("/channels/{channel_id}")) else {
+            return Some(Vec::new());
+        };
+        if channel.is_direct() {
+            return Some(channel.get_participants().to_vec());
+        }
+        let guild = channel.get_guild();
+        let mut users = match (channel.is_private(), guild == Guild::DEFAULT) {
+            (false, true) => return None,
+            (false, false) => self.guild_members(guild).into_iter().collect(),
+            (true, _) => {
+                let mut users = self
+                    .members(channel_id)
+                    .into_iter()
+                    .collect::<BTreeSet<_>>();
+                users.extend(self.list(format!("/op/channels/{channel_id}/")));
+                if guild != Guild::DEFAULT {
+                    users.retain(|user| self.is_guild_member(guild, *user));
+                }
+                users
+            }
+        };
+        users.extend(self.administrators(guild));
+        Some(users.into_iter().collect())
+    }
+
+    pub(super) fn can_see(&self, channel_id: Id, user: Option<Id>) -> bool {
+        match self.channel_audience(channel_id) {
+            Some(audience) => user.map(|user| audience.contains(&user)).unwrap_or(false),
+            None => true,
+        }
+    }
+
+    /// channels a user is not allowed in.
+    pub(super) fn hidden_channels(&self, user: Option<Id>) -> BTreeSet<Id> {
+        self.list("/channels/")
+            .into_iter()
+            .filter(|id| !self.can_see(*id, user))
+            .collect()
+    }
+
+    /// drops the members of a purged channel.
+    pub(super) fn remove_members(&mut self, channel_id: Id) {
+        for key in self.keys(format!("/members/{channel_id}/")) {
+            self.remove(key);
+        }
+    }
+}
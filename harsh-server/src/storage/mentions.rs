@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+
+use super::{Guild, Message, StorageProc};
+use crate::Id;
+
+const MAX_MENTIONS_PAGE: usize = 100;
+
+/// who a message addresses, written `<@id>`, `<@ops>` or `<@everyone>` in its content.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Mention {
+    User(Id),
+    Operators,
+    Everyone,
+}
+
+pub fn parse(content: &str) -> Vec<Mention> {
+    content
+        .split("<@")
+        .skip(1)
+        .filter_map(|part| {
+            let (target, _) = part.split_once('>')?;
+            match target {
+                "ops" => Some(Mention::Operators),
+                "everyone" => Some(Mention::Everyone),
+                id => Id::from_string(id).map(Mention::User),
+            }
+        })
+        .collect()
+}
+
+/// whether a message addresses every user, which requires a permission.
+pub fn mentions_everyone(content: &str) -> bool {
+    parse(content).contains(&Mention::Everyone)
+}
+
+fn mention_path(user: Id, id: Id) -> String {
+    format!("/mentions/{user}/{id}")
+}
+
+impl StorageProc {
+    /// users mentioned by a message posted in a channel.
+    pub(super) fn resolve_mentions(&self, channel_id: Id, content: &str) -> Vec<Id> {
+        let guild = self.guild_of(channel_id).unwrap_or(Guild::DEFAULT);
+        let mut users = BTreeSet::new();
+        for mention in parse(content) {
+            match mention {
+                Mention::User(user) => {
+                    if self.base.contains_key(format!("/users/{user}")).unwrap() {
+                        users.insert(user);
+                    }
+                }
+                Mention::Operators => {
+                    users.extend(self.list(format!("/op/channels/{channel_id}/")));
+                    users.extend(self.administrators(guild));
+                }
+                Mention::Everyone => users.extend(self.guild_members(guild)),
+            }
+        }
+        users.retain(|user| self.can_see(channel_id, Some(*user)));
+        users.into_iter().collect()
+    }
+
+    pub(super) fn index_mentions(&self, channel_id: Id, message: &Message) {
+        for user in message.get_mentions() {
+            self.set(mention_path(*user, message.get_id()), channel_id);
+        }
+    }
+
+    pub(super) fn unindex_mentions(&self, message: &Message) {
+        for user in message.get_mentions() {
+            self.remove(mention_path(*user, message.get_id()));
+        }
+    }
+
+    /// mentions of a user in messages posted after `after`, oldest first, as channel and message ids.
+    /// mentions in deleted messages or channels are left out.
+    pub(super) fn mentions(&self, user: Id, after: Option<Id>, limit: usize) -> Vec<(Id, Id)> {
+        let mut mentions = self.all_mentions(user, after);
+        mentions.truncate(limit.clamp(1, MAX_MENTIONS_PAGE));
+        mentions
+    }
+
+    pub(super) fn all_mentions(&self, user: Id, after: Option<Id>) -> Vec<(Id, Id)> {
+        self.list(format!("/mentions/{user}/"))
+            .into_iter()
+            .filter(|id| after.map(|after| id > &after).unwrap_or(true))
+            .filter_map(|id| {
+                let channel_id = self.get::<_, Id>(mention_path(user, id))?;
+                let channel = format!("/channels/{channel_id}");
+                let message = format!("/messages/{channel_id}/{id}");
+                let exists = self.base.contains_key(channel).unwrap()
+                    && self.base.contains_key(message).unwrap();
+                exists.then_some((channel_id, id))
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_parse() {
+    let mentions = parse("hi <@12> and <@ops>, <@everyone> <@nobody> <@3");
+    assert_eq!(
+        mentions,
+        vec![
+            Mention::User(Id::from_u64(12)),
+            Mention::Operators,
+            Mention::Everyone
+        ]
+    );
+}
@@ -1,3 +1,4 @@
+use rand::{distributions::Alphanumeric, Rng};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sled::Db;
 
@@ -7,12 +8,99 @@ use crate::Id;
 pub struct Channel {
     id: Id,
     name: String,
+    #[serde(default)]
+    slow_mode: u64,
+    /// pinned messages, in pin order.
+    #[serde(default)]
+    pins: Vec<Id>,
+    #[serde(default)]
+    pin_limit: Option<u32>,
+    /// members of a direct channel, empty for channels open to everyone.
+    #[serde(default)]
+    participants: Vec<Id>,
+    /// whether the channel is restricted to its members.
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    kind: ChannelKind,
+    /// the category holding the channel.
+    #[serde(default)]
+    parent: Option<Id>,
+    /// rank among the channels of a same parent, ties being ordered by id.
+    #[serde(default)]
+    position: u32,
+    #[serde(default)]
+    topic: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    creator: Option<Id>,
+    /// channels created before guilds belong to the default one.
+    #[serde(default = "default_guild")]
+    guild: Id,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelKind {
+    #[default]
+    Text,
+    /// only operators may post.
+    Announcement,
+    /// no one may post.
+    ReadOnly,
+    /// groups other channels, and holds no message.
+    Category,
+}
+
+impl ChannelKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Announcement => "announcement",
+            Self::ReadOnly => "read_only",
+            Self::Category => "category",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Text,
+            Self::Announcement,
+            Self::ReadOnly,
+            Self::Category,
+        ]
+        .into_iter()
+        .find(|kind| kind.name() == name)
+    }
 }
 
 impl Channel {
     pub fn new(name: String) -> Self {
         let id = Id::from_now();
-        Self { id, name }
+        let slow_mode = 0;
+        Self {
+            id,
+            name,
+            slow_mode,
+            pins: Vec::new(),
+            pin_limit: None,
+            participants: Vec::new(),
+            private: false,
+            kind: ChannelKind::Text,
+            parent: None,
+            position: 0,
+            topic: String::new(),
+            description: String::new(),
+            creator: None,
+            guild: Guild::DEFAULT,
+        }
+    }
+
+    /// a nameless channel reserved to its participants.
+    pub fn new_direct(participants: Vec<Id>) -> Self {
+        let mut channel = Self::new(String::new());
+        channel.participants = participants;
+        channel
     }
 
     pub fn get_id(&self) -> Id {
@@ -26,6 +114,173 @@ impl Channel {
     pub fn set_name(&mut self, name: String) {
         self.name = name;
     }
+
+    /// minimum delay in seconds between two messages of a same user, `0` when disabled.
+    pub fn get_slow_mode(&self) -> u64 {
+        self.slow_mode
+    }
+
+    pub fn set_slow_mode(&mut self, seconds: u64) {
+        self.slow_mode = seconds;
+    }
+
+    pub fn get_pins(&self) -> &[Id] {
+        &self.pins
+    }
+
+    /// returns whether the message was not already pinned.
+    pub fn pin(&mut self, message: Id) -> bool {
+        let pinned = self.pins.contains(&message);
+        if !pinned {
+            self.pins.push(message);
+        }
+        !pinned
+    }
+
+    /// returns whether the message was pinned.
+    pub fn unpin(&mut self, message: Id) -> bool {
+        let count = self.pins.len();
+        self.pins.retain(|id| id != &message);
+        self.pins.len() != count
+    }
+
+    /// maximum number of pinned messages, `None` when the server default applies.
+    pub fn get_pin_limit(&self) -> Option<u32> {
+        self.pin_limit
+    }
+
+    pub fn set_pin_limit(&mut self, limit: Option<u32>) {
+        self.pin_limit = limit;
+    }
+
+    pub fn is_direct(&self) -> bool {
+        !self.participants.is_empty()
+    }
+
+    pub fn get_participants(&self) -> &[Id] {
+        &self.participants
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    pub fn set_private(&mut self, private: bool) {
+        self.private = private;
+    }
+
+    pub fn get_kind(&self) -> ChannelKind {
+        self.kind
+    }
+
+    pub fn set_kind(&mut self, kind: ChannelKind) {
+        self.kind = kind;
+    }
+
+    pub fn is_category(&self) -> bool {
+        self.kind == ChannelKind::Category
+    }
+
+    pub fn get_parent(&self) -> Option<Id> {
+        self.parent
+    }
+
+    pub fn set_parent(&mut self, parent: Option<Id>) {
+        self.parent = parent;
+    }
+
+    pub fn get_position(&self) -> u32 {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: u32) {
+        self.position = position;
+    }
+
+    pub fn get_topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub fn set_topic(&mut self, topic: String) {
+        self.topic = topic;
+    }
+
+    pub fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn set_description(&mut self, description: String) {
+        self.description = description;
+    }
+
+    /// creation date in milliseconds, as encoded in the id.
+    pub fn get_created(&self) -> i64 {
+        self.id.timestamp()
+    }
+
+    /// `None` for channels created anonymously or before creators were recorded.
+    pub fn get_creator(&self) -> Option<Id> {
+        self.creator
+    }
+
+    pub fn set_creator(&mut self, creator: Option<Id>) {
+        self.creator = creator;
+    }
+
+    pub fn get_guild(&self) -> Id {
+        self.guild
+    }
+
+    pub fn set_guild(&mut self, guild: Id) {
+        self.guild = guild;
+    }
+}
+
+fn default_guild() -> Id {
+    Guild::DEFAULT
+}
+
+/// a community of users, with its own channels and roles.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Guild {
+    id: Id,
+    name: String,
+    /// `None` for the default guild, which every user is a member of.
+    owner: Option<Id>,
+}
+
+impl Guild {
+    /// the guild holding the data created before guilds, its everyone role sharing its id.
+    pub const DEFAULT: Id = Id::from_u64(0);
+
+    pub fn new(name: String, owner: Id) -> Self {
+        let id = Id::from_now();
+        Self {
+            id,
+            name,
+            owner: Some(owner),
+        }
+    }
+
+    pub fn default_guild() -> Self {
+        Self {
+            id: Self::DEFAULT,
+            name: "default".into(),
+            owner: None,
+        }
+    }
+
+    pub fn get_id(&self) -> Id {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_owner(&self) -> Option<Id> {
+        self.owner
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,12 +288,20 @@ pub struct User {
     id: Id,
     name: String,
     pass: String,
+    #[serde(default)]
+    profile: Profile,
 }
 
 impl User {
     pub fn new(name: String, pass: String) -> Self {
         let id = Id::from_now();
-        Self { id, name, pass }
+        let profile = Profile::default();
+        Self {
+            id,
+            name,
+            pass,
+            profile,
+        }
     }
 
     pub fn get_id(&self) -> Id {
@@ -60,28 +323,173 @@ impl User {
     pub fn set_pass(&mut self, pass: String) {
         self.pass = pass
     }
+
+    pub fn get_profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    pub fn get_profile_mut(&mut self) -> &mut Profile {
+        &mut self.profile
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// what users tell about themselves, kept apart from their credentials.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub display_name: Option<String>,
+    /// reference to an image, such as an url.
+    pub avatar: Option<String>,
+    pub bio: Option<String>,
+    pub custom_status: Option<String>,
+}
+
+impl Profile {
+    /// replaces the fields set in the update, empty ones being cleared.
+    pub fn apply(&mut self, update: Profile) {
+        let fields = [
+            (&mut self.display_name, update.display_name),
+            (&mut self.avatar, update.avatar),
+            (&mut self.bio, update.bio),
+            (&mut self.custom_status, update.custom_status),
+        ];
+        for (field, value) in fields {
+            if let Some(value) = value {
+                *field = Some(value).filter(|value| !value.is_empty());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Message {
     id: Id,
     content: String,
+    #[serde(default)]
+    author: Option<Id>,
+    #[serde(default)]
+    edits: u32,
+    #[serde(default)]
+    reply_to: Option<Id>,
+    /// parent of the thread the message was posted in.
+    #[serde(default)]
+    thread: Option<Id>,
+    /// number of messages in the thread of this message.
+    #[serde(default)]
+    replies: u32,
+    /// date of the last message posted in the thread of this message, in milliseconds since the epoch.
+    #[serde(default)]
+    last_activity: Option<i64>,
+    /// users addressed by the content of the message.
+    #[serde(default)]
+    mentions: Vec<Id>,
 }
 
 impl Message {
-    pub fn new(content: String) -> Self {
+    pub fn new(content: String, author: Option<Id>) -> Self {
         let id = Id::from_now();
-        Self { id, content }
+        let edits = 0;
+        Self {
+            id,
+            content,
+            author,
+            edits,
+            reply_to: None,
+            thread: None,
+            replies: 0,
+            last_activity: None,
+            mentions: Vec::new(),
+        }
     }
 
     pub fn get_id(&self) -> Id {
         self.id
     }
+    pub fn get_author(&self) -> Option<Id> {
+        self.author
+    }
     pub fn get_content(&self) -> &str {
         &self.content
     }
     pub fn set_content(&mut self, content: String) {
         self.content = content;
+        self.edits += 1;
+    }
+    pub fn get_edits(&self) -> u32 {
+        self.edits
+    }
+    pub fn get_reply_to(&self) -> Option<Id> {
+        self.reply_to
+    }
+    pub fn set_reply_to(&mut self, reply_to: Option<Id>) {
+        self.reply_to = reply_to;
+    }
+    pub fn get_thread(&self) -> Option<Id> {
+        self.thread
+    }
+    pub fn set_thread(&mut self, thread: Option<Id>) {
+        self.thread = thread;
+    }
+    pub fn get_replies(&self) -> u32 {
+        self.replies
+    }
+    pub fn get_last_activity(&self) -> Option<i64> {
+        self.last_activity
+    }
+    /// accounts for a message added to the thread of this message.
+    pub fn add_reply(&mut self, timestamp: i64) {
+        self.replies += 1;
+        self.last_activity = self.last_activity.max(Some(timestamp));
+    }
+    pub fn remove_reply(&mut self) {
+        self.replies = self.replies.saturating_sub(1);
+    }
+    pub fn get_mentions(&self) -> &[Id] {
+        &self.mentions
+    }
+    pub fn set_mentions(&mut self, mentions: Vec<Id>) {
+        self.mentions = mentions;
+    }
+}
+
+/// a past or current content of a message.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Revision {
+    content: String,
+    editor: Option<Id>,
+    /// in milliseconds since the epoch.
+    timestamp: i64,
+}
+
+impl Revision {
+    pub fn new(content: String, editor: Option<Id>) -> Self {
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        Self {
+            content,
+            editor,
+            timestamp,
+        }
+    }
+
+    /// the revision of a message as it was created.
+    pub fn original(message: &Message) -> Self {
+        let content = message.get_content().to_string();
+        let editor = message.get_author();
+        let timestamp = message.get_id().timestamp();
+        Self {
+            content,
+            editor,
+            timestamp,
+        }
+    }
+
+    pub fn get_content(&self) -> &str {
+        &self.content
+    }
+    pub fn get_editor(&self) -> Option<Id> {
+        self.editor
+    }
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
     }
 }
 
@@ -117,6 +525,381 @@ where
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub enum Perm {
-    OpServer,
-    OpChannel(Id),
+    /// permissions granted server-wide, by the roles of the default guild.
+    Server(Permissions),
+    /// permissions granted in a guild, regardless of channel overwrites.
+    Guild(Id, Permissions),
+    /// permissions granted in a channel, once its overwrites are applied.
+    Channel(Id, Permissions),
+    /// reading and posting in a channel, restricted for private and direct channels.
+    ViewChannel(Id),
+}
+
+/// where permissions are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Server,
+    Guild(Id),
+    Channel(Id),
+}
+
+/// a set of permissions, one bit each.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Permissions(u64);
+
+impl Permissions {
+    pub const SEND_MESSAGES: Self = Self(1 << 0);
+    /// deleting, pinning and restoring the messages of others.
+    pub const MANAGE_MESSAGES: Self = Self(1 << 1);
+    pub const MANAGE_CHANNELS: Self = Self(1 << 2);
+    /// renaming, deleting, inviting and moderating users.
+    pub const MANAGE_USERS: Self = Self(1 << 3);
+    /// editing roles, their assignments and the overwrites of channels.
+    pub const MANAGE_ROLES: Self = Self(1 << 4);
+    pub const MENTION_EVERYONE: Self = Self(1 << 5);
+    /// every permission, regardless of channel overwrites.
+    pub const ADMINISTRATOR: Self = Self(1 << 6);
+
+    /// permissions that only make sense server-wide, ignored by channel overwrites.
+    const SERVER_ONLY: Self = Self(Self::MANAGE_USERS.0 | Self::ADMINISTRATOR.0);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self((1 << 7) - 1)
+    }
+
+    /// permissions taken away from timed out users.
+    pub const fn speech() -> Self {
+        Self(Self::SEND_MESSAGES.0 | Self::MENTION_EVERYONE.0)
+    }
+
+    /// permissions granted to channel operators in their channels.
+    pub const fn channel_op() -> Self {
+        Self(Self::all().0 & !Self::SERVER_ONLY.0)
+    }
+
+    /// unknown bits are dropped.
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits & Self::all().0)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+/// a named set of permissions, assigned to users.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Role {
+    id: Id,
+    name: String,
+    permissions: Permissions,
+    #[serde(default = "default_guild")]
+    guild: Id,
+}
+
+impl Role {
+    pub fn new(guild: Id, name: String, permissions: Permissions) -> Self {
+        let id = Id::from_now();
+        Self {
+            id,
+            name,
+            permissions,
+            guild,
+        }
+    }
+
+    /// the role every member of a guild implicitly has, identified by the guild id.
+    /// anonymous users get the one of the default guild.
+    pub fn everyone(guild: Id) -> Self {
+        Self {
+            id: guild,
+            name: "everyone".into(),
+            permissions: Permissions::SEND_MESSAGES,
+            guild,
+        }
+    }
+
+    pub fn get_id(&self) -> Id {
+        self.id
+    }
+
+    pub fn get_guild(&self) -> Id {
+        self.guild
+    }
+
+    pub fn is_everyone(&self) -> bool {
+        self.id == self.guild
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn get_permissions(&self) -> Permissions {
+        self.permissions
+    }
+
+    pub fn set_permissions(&mut self, permissions: Permissions) {
+        self.permissions = permissions;
+    }
+}
+
+/// permissions added and removed in a channel for a role or a user.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Overwrite {
+    pub allow: Permissions,
+    pub deny: Permissions,
+}
+
+impl Overwrite {
+    pub fn apply(&self, permissions: Permissions) -> Permissions {
+        let deny = self.deny.difference(Permissions::SERVER_ONLY);
+        let allow = self.allow.difference(Permissions::SERVER_ONLY);
+        permissions.difference(deny).union(allow)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OverwriteTarget {
+    Role(Id),
+    User(Id),
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+pub enum LockTarget {
+    User(Id),
+    Host(String),
+}
+
+/// record of a user or host locked out after too many failed authentications.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Lockout {
+    id: Id,
+    target: LockTarget,
+    failures: u32,
+    until: i64,
+}
+
+impl Lockout {
+    pub fn new(target: LockTarget, failures: u32, until: i64) -> Self {
+        let id = Id::from_now();
+        Self {
+            id,
+            target,
+            failures,
+            until,
+        }
+    }
+
+    pub fn get_id(&self) -> Id {
+        self.id
+    }
+
+    pub fn get_target(&self) -> &LockTarget {
+        &self.target
+    }
+
+    pub fn get_failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// end of the lockout, in milliseconds since the epoch.
+    pub fn get_until(&self) -> i64 {
+        self.until
+    }
+}
+
+/// exclusion of a user from the server, until it expires or gets lifted.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Ban {
+    user: Id,
+    moderator: Id,
+    reason: String,
+    created: i64,
+    until: Option<i64>,
+}
+
+impl Ban {
+    pub fn new(user: Id, moderator: Id, reason: String, until: Option<i64>) -> Self {
+        let created = chrono::Utc::now().timestamp_millis();
+        Self {
+            user,
+            moderator,
+            reason,
+            created,
+            until,
+        }
+    }
+
+    pub fn get_user(&self) -> Id {
+        self.user
+    }
+
+    pub fn get_moderator(&self) -> Id {
+        self.moderator
+    }
+
+    pub fn get_reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// in milliseconds since the epoch.
+    pub fn get_created(&self) -> i64 {
+        self.created
+    }
+
+    /// end of the ban, in milliseconds since the epoch, `None` if it is permanent.
+    pub fn get_until(&self) -> Option<i64> {
+        self.until
+    }
+
+    pub fn is_active(&self, now: i64) -> bool {
+        self.until.map(|until| until > now).unwrap_or(true)
+    }
+}
+
+/// record of a privileged change, identified by the id it is stored under.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// `None` for anonymous users.
+    actor: Option<Id>,
+    action: String,
+    target: Option<Id>,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(actor: Option<Id>, action: &str, target: Option<Id>) -> Self {
+        Self {
+            actor,
+            action: action.into(),
+            target,
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn with_before(mut self, before: impl ToString) -> Self {
+        self.before = Some(before.to_string());
+        self
+    }
+
+    pub fn with_after(mut self, after: impl ToString) -> Self {
+        self.after = Some(after.to_string());
+        self
+    }
+
+    pub fn get_actor(&self) -> Option<Id> {
+        self.actor
+    }
+
+    pub fn get_action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn get_target(&self) -> Option<Id> {
+        self.target
+    }
+
+    pub fn get_before(&self) -> Option<&str> {
+        self.before.as_deref()
+    }
+
+    pub fn get_after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+}
+
+/// code granting the right to register, or to join a guild, a limited number of times or until
+/// it expires.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Invite {
+    code: String,
+    creator: Id,
+    max_uses: Option<u32>,
+    uses: u32,
+    expires: Option<i64>,
+    /// `None` for registration invites.
+    #[serde(default)]
+    guild: Option<Id>,
+}
+
+impl Invite {
+    pub fn new(creator: Id, max_uses: Option<u32>, expires: Option<i64>) -> Self {
+        let code = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(10)
+            .map(char::from)
+            .collect();
+        let uses = 0;
+        Self {
+            code,
+            creator,
+            max_uses,
+            uses,
+            expires,
+            guild: None,
+        }
+    }
+
+    pub fn for_guild(mut self, guild: Id) -> Self {
+        self.guild = Some(guild);
+        self
+    }
+
+    pub fn get_code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn get_creator(&self) -> Id {
+        self.creator
+    }
+
+    pub fn get_max_uses(&self) -> Option<u32> {
+        self.max_uses
+    }
+
+    pub fn get_uses(&self) -> u32 {
+        self.uses
+    }
+
+    /// expiration date, in milliseconds since the epoch.
+    pub fn get_expires(&self) -> Option<i64> {
+        self.expires
+    }
+
+    pub fn get_guild(&self) -> Option<Id> {
+        self.guild
+    }
+
+    pub fn is_valid(&self, now: i64) -> bool {
+        let expired = self.expires.map(|e| e <= now).unwrap_or(false);
+        let exhausted = self.max_uses.map(|m| self.uses >= m).unwrap_or(false);
+        !expired && !exhausted
+    }
+
+    pub fn add_use(&mut self) {
+        self.uses += 1;
+    }
 }
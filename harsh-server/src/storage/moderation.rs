@@ -0,0 +1,75 @@
+use super::{Ban, Message, StorageProc};
+use crate::Id;
+
+fn ban_path(user: Id) -> String {
+    format!("/bans/{user}")
+}
+
+fn timeout_path(user: Id) -> String {
+    format!("/timeouts/{user}")
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+impl StorageProc {
+    /// replaces any previous ban of the user, returns the messages moved to the trash.
+    pub(super) fn create_ban(&mut self, ban: Ban, purge_since: Option<i64>) -> Vec<(Id, Id)> {
+        let user = ban.get_user();
+        self.set(ban_path(user), ban);
+        let Some(since) = purge_since else {
+            return Vec::new();
+        };
+        let mut trashed = Vec::new();
+        for channel_id in self.list("/channels/") {
+            for id in self.list(format!("/messages/{channel_id}/")) {
+                if id.timestamp() < since {
+                    continue;
+                }
+                let message = self.get::<_, Message>(format!("/messages/{channel_id}/{id}"));
+                if message.and_then(|message| message.get_author()) == Some(user) {
+                    self.trash_message(channel_id, id);
+                    trashed.push((channel_id, id));
+                }
+            }
+        }
+        trashed
+    }
+
+    pub(super) fn get_ban(&self, user: Id) -> Option<Ban> {
+        self.get::<_, Ban>(ban_path(user))
+            .filter(|ban| ban.is_active(now()))
+    }
+
+    pub(super) fn remove_ban(&mut self, user: Id) -> bool {
+        let existed = self.get_ban(user).is_some();
+        self.remove(ban_path(user));
+        existed
+    }
+
+    /// expired bans are dropped on the way.
+    pub(super) fn bans(&mut self) -> Vec<Ban> {
+        let now = now();
+        let (active, expired): (Vec<_>, _) = self
+            .values::<Ban>("/bans/")
+            .into_iter()
+            .partition(|ban| ban.is_active(now));
+        for ban in expired {
+            self.remove(ban_path(ban.get_user()));
+        }
+        active
+    }
+
+    pub(super) fn set_timeout(&mut self, user: Id, until: Option<i64>) {
+        match until {
+            Some(until) => self.set(timeout_path(user), until),
+            None => self.remove(timeout_path(user)),
+        }
+    }
+
+    pub(super) fn get_timeout(&self, user: Id) -> Option<i64> {
+        self.get::<_, i64>(timeout_path(user))
+            .filter(|&until| until > now())
+    }
+}
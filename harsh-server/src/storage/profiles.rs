@@ -0,0 +1,52 @@
+use std::collections::BTreeSet;
+
+use super::{Profile, StorageProc, User};
+use crate::Id;
+
+const MAX_DISPLAY_NAME_LENGTH: usize = 32;
+const MAX_AVATAR_LENGTH: usize = 256;
+const MAX_BIO_LENGTH: usize = 190;
+const MAX_CUSTOM_STATUS_LENGTH: usize = 128;
+
+/// whether every field of a profile fits its length limit.
+pub fn is_valid_profile(profile: &Profile) -> bool {
+    let fits = |field: &Option<String>, max: usize| {
+        field
+            .as_ref()
+            .map(|value| value.chars().count() <= max)
+            .unwrap_or(true)
+    };
+    fits(&profile.display_name, MAX_DISPLAY_NAME_LENGTH)
+        && fits(&profile.avatar, MAX_AVATAR_LENGTH)
+        && fits(&profile.bio, MAX_BIO_LENGTH)
+        && fits(&profile.custom_status, MAX_CUSTOM_STATUS_LENGTH)
+}
+
+impl StorageProc {
+    pub(super) fn get_profile(&self, user: Id) -> Option<Profile> {
+        let user = self.get::<_, User>(format!("/users/{user}"))?;
+        Some(user.get_profile().clone())
+    }
+
+    /// returns the updated profile, `None` if the user does not exist.
+    pub(super) fn update_profile(&mut self, user: Id, update: Profile) -> Option<Profile> {
+        let path = format!("/users/{user}");
+        let mut user = self.get::<_, User>(&path)?;
+        user.get_profile_mut().apply(update);
+        let profile = user.get_profile().clone();
+        self.set(path, user);
+        Some(profile)
+    }
+
+    /// users sharing a channel with the user, `None` if one of them is open to everyone.
+    pub(super) fn profile_audience(&self, user: Id) -> Option<Vec<Id>> {
+        let mut users = BTreeSet::from([user]);
+        for channel_id in self.list("/channels/") {
+            if !self.can_see(channel_id, Some(user)) {
+                continue;
+            }
+            users.extend(self.channel_audience(channel_id)?);
+        }
+        Some(users.into_iter().collect())
+    }
+}
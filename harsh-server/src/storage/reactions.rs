@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use super::StorageProc;
+use crate::Id;
+
+const MAX_EMOJI_LENGTH: usize = 32;
+
+/// whether a reaction is a unicode emoji, possibly made of several code points.
+pub fn is_valid_emoji(emoji: &str) -> bool {
+    !emoji.is_empty()
+        && emoji.len() <= MAX_EMOJI_LENGTH
+        && emoji.chars().all(|c| !c.is_ascii() && !c.is_whitespace())
+}
+
+fn reaction_path(channel_id: Id, id: Id, emoji: &str, user: Id) -> String {
+    format!("/messages/{channel_id}/{id}/reactions/{emoji}/{user}")
+}
+
+impl StorageProc {
+    /// returns whether the reaction was not already there.
+    pub(super) fn add_reaction(&mut self, channel_id: Id, id: Id, emoji: &str, user: Id) -> bool {
+        let path = reaction_path(channel_id, id, emoji, user);
+        let exists = self.base.contains_key(&path).unwrap();
+        if !exists {
+            self.set(path, true);
+        }
+        !exists
+    }
+
+    /// returns whether the reaction was there.
+    pub(super) fn remove_reaction(
+        &mut self,
+        channel_id: Id,
+        id: Id,
+        emoji: &str,
+        user: Id,
+    ) -> bool {
+        let path = reaction_path(channel_id, id, emoji, user);
+        let exists = self.base.contains_key(&path).unwrap();
+        self.remove(path);
+        exists
+    }
+
+    pub(super) fn reaction_users(&self, channel_id: Id, id: Id, emoji: &str) -> Vec<Id> {
+        self.list(format!("/messages/{channel_id}/{id}/reactions/{emoji}/"))
+    }
+
+    /// users who reacted to a message, by emoji.
+    pub(super) fn reactions(&self, channel_id: Id, id: Id) -> BTreeMap<String, Vec<Id>> {
+        let prefix = format!("/messages/{channel_id}/{id}/reactions/");
+        let mut reactions = BTreeMap::<_, Vec<_>>::new();
+        for key in self.keys(&prefix) {
+            let Some((emoji, user)) = key[prefix.len()..].rsplit_once('/') else {
+                continue;
+            };
+            if let Some(user) = Id::from_string(user) {
+                reactions.entry(emoji.to_string()).or_default().push(user);
+            }
+        }
+        reactions
+    }
+}
+
+#[test]
+fn test_is_valid_emoji() {
+    assert!(is_valid_emoji("👍"));
+    assert!(is_valid_emoji("👩‍👩‍👧"));
+    assert!(!is_valid_emoji(""));
+    assert!(!is_valid_emoji("a"));
+    assert!(!is_valid_emoji("👍/"));
+}
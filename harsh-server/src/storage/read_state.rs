@@ -0,0 +1,69 @@
+use super::StorageProc;
+use crate::Id;
+
+/// how far a user read a channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadState {
+    pub channel: Id,
+    pub last_read: Option<Id>,
+    /// messages posted after the last read one.
+    pub unread: usize,
+    /// unread messages mentioning the user.
+    pub mentions: usize,
+}
+
+fn read_path(user: Id, channel_id: Id) -> String {
+    format!("/read/{user}/{channel_id}")
+}
+
+impl StorageProc {
+    /// moves the read marker of a user forward, acknowledging an older message does nothing.
+    pub(super) fn ack(&mut self, user: Id, channel_id: Id, id: Id) {
+        let path = read_path(user, channel_id);
+        if self
+            .get::<_, Id>(&path)
+            .map(|last| last < id)
+            .unwrap_or(true)
+        {
+            self.set(path, id);
+        }
+    }
+
+    pub(super) fn read_states(&self, user: Id) -> Vec<ReadState> {
+        let mentions = self.all_mentions(user, None);
+        self.list("/channels/")
+            .into_iter()
+            .filter(|channel| self.can_see(*channel, Some(user)))
+            .map(|channel| {
+                let last_read = self.get::<_, Id>(read_path(user, channel));
+                let is_unread = |id: &Id| last_read.map(|last| id > &last).unwrap_or(true);
+                let threaded = self.threaded_messages(channel);
+                let unread = self
+                    .list(format!("/messages/{channel}/"))
+                    .iter()
+                    .filter(|id| is_unread(id) && !threaded.contains(id))
+                    .count();
+                let mentions = mentions
+                    .iter()
+                    .filter(|(channel_id, id)| channel_id == &channel && is_unread(id))
+                    .count();
+                ReadState {
+                    channel,
+                    last_read,
+                    unread,
+                    mentions,
+                }
+            })
+            .collect()
+    }
+
+    /// drops the read markers of a purged channel.
+    pub(super) fn remove_read_states(&mut self, channel_id: Id) {
+        let suffix = format!("/{channel_id}");
+        for key in self.keys("/read/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+    }
+}
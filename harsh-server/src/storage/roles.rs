@@ -0,0 +1,261 @@
+use std::collections::BTreeSet;
+
+use super::{Guild, Overwrite, OverwriteTarget, Permissions, Role, Scope, StorageProc};
+use crate::Id;
+
+/// everything the permissions of a user depend on, resolved by the security processor.
+#[derive(Debug, Default)]
+pub struct Grants {
+    pub server_op: bool,
+    /// owner or operator of the guild.
+    pub guild_op: bool,
+    pub channel_op: bool,
+    /// permissions of the everyone role then of each role of the user.
+    pub roles: Vec<Permissions>,
+    /// overwrites of the channel for the everyone role and the roles of the user.
+    pub role_overwrites: Vec<Overwrite>,
+    pub everyone_overwrite: Option<Overwrite>,
+    pub user_overwrite: Option<Overwrite>,
+    /// whether the user is muted by a timeout.
+    pub timed_out: bool,
+}
+
+fn role_path(id: Id) -> String {
+    format!("/roles/{id}")
+}
+
+fn assignment_path(user: Id, role: Id) -> String {
+    format!("/assignments/{user}/{role}")
+}
+
+fn overwrite_path(channel_id: Id, target: OverwriteTarget) -> String {
+    match target {
+        OverwriteTarget::Role(id) => format!("/overwrites/{channel_id}/roles/{id}"),
+        OverwriteTarget::User(id) => format!("/overwrites/{channel_id}/users/{id}"),
+    }
+}
+
+impl StorageProc {
+    /// the everyone role of a guild exists even before it is first edited.
+    pub(super) fn get_role(&self, id: Id) -> Option<Role> {
+        let role = self.get::<_, Role>(role_path(id));
+        match (role, self.get_guild(id).is_some()) {
+            (None, true) => Some(Role::everyone(id)),
+            (role, _) => role,
+        }
+    }
+
+    pub(super) fn create_role(&mut self, guild: Id, name: String, permissions: Permissions) -> Id {
+        let role = Role::new(guild, name, permissions);
+        let id = role.get_id();
+        self.set(role_path(id), role);
+        id
+    }
+
+    pub(super) fn update_role(&mut self, id: Id, name: String, permissions: Permissions) -> bool {
+        let Some(mut role) = self.get_role(id) else {
+            return false;
+        };
+        role.set_name(name);
+        role.set_permissions(permissions);
+        self.set(role_path(id), role);
+        true
+    }
+
+    /// everyone roles can not be deleted.
+    pub(super) fn delete_role(&mut self, id: Id) -> bool {
+        match self.get::<_, Role>(role_path(id)) {
+            Some(role) if !role.is_everyone() => (),
+            _ => return false,
+        }
+        let suffix = format!("/{id}");
+        for key in self.keys("/assignments/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+        let suffix = format!("/roles/{id}");
+        for key in self.keys("/overwrites/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+        self.remove(role_path(id));
+        true
+    }
+
+    /// roles of a guild, its everyone role first.
+    pub(super) fn roles(&self, guild: Id) -> Vec<Role> {
+        let Some(everyone) = self.get_role(guild) else {
+            return Vec::new();
+        };
+        let mut roles = vec![everyone];
+        roles.extend(
+            self.list("/roles/")
+                .into_iter()
+                .filter(|id| id != &guild)
+                .filter_map(|id| self.get_role(id))
+                .filter(|role| role.get_guild() == guild),
+        );
+        roles
+    }
+
+    /// returns whether the assignments changed, everyone roles can not be assigned.
+    pub(super) fn assign_role(&mut self, role: Id, user: Id, assigned: bool) -> bool {
+        match self.get_role(role) {
+            Some(role) if !role.is_everyone() => (),
+            _ => return false,
+        }
+        let path = assignment_path(user, role);
+        let exists = self.base.contains_key(&path).unwrap();
+        match (exists, assigned) {
+            (false, true) => self.set(path, true),
+            (true, false) => self.remove(path),
+            _ => return false,
+        }
+        true
+    }
+
+    pub(super) fn user_roles(&self, user: Id) -> Vec<Id> {
+        self.list(format!("/assignments/{user}/"))
+    }
+
+    /// an overwrite that neither allows nor denies anything is removed.
+    pub(super) fn set_overwrite(
+        &mut self,
+        channel_id: Id,
+        target: OverwriteTarget,
+        overwrite: Overwrite,
+    ) {
+        let path = overwrite_path(channel_id, target);
+        if overwrite == Overwrite::default() {
+            self.remove(path);
+        } else {
+            self.set(path, overwrite);
+        }
+    }
+
+    pub(super) fn overwrites(&self, channel_id: Id) -> Vec<(OverwriteTarget, Overwrite)> {
+        let roles = self
+            .list(format!("/overwrites/{channel_id}/roles/"))
+            .into_iter()
+            .map(OverwriteTarget::Role);
+        let users = self
+            .list(format!("/overwrites/{channel_id}/users/"))
+            .into_iter()
+            .map(OverwriteTarget::User);
+        roles
+            .chain(users)
+            .filter_map(|target| {
+                let overwrite = self.get(overwrite_path(channel_id, target))?;
+                Some((target, overwrite))
+            })
+            .collect()
+    }
+
+    /// the permissions of the server are those of the default guild.
+    pub(super) fn grants(&self, user: Option<Id>, scope: Scope) -> Grants {
+        let (guild, channel_id) = match scope {
+            Scope::Server => (Guild::DEFAULT, None),
+            Scope::Guild(guild) => (guild, None),
+            Scope::Channel(channel_id) => {
+                let guild = self.guild_of(channel_id).unwrap_or(Guild::DEFAULT);
+                (guild, Some(channel_id))
+            }
+        };
+        let member = guild == Guild::DEFAULT
+            || user
+                .map(|user| self.is_guild_member(guild, user))
+                .unwrap_or(false);
+        let roles: Vec<_> = user
+            .map(|user| self.user_roles(user))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|id| {
+                let role = self.get_role(*id);
+                role.map(|role| role.get_guild() == guild).unwrap_or(false)
+            })
+            .collect();
+        let mut grants = Grants {
+            server_op: user
+                .map(|user| self.base.contains_key(format!("/op/serv/{user}")).unwrap())
+                .unwrap_or(false),
+            guild_op: user
+                .map(|user| self.is_guild_op(guild, user))
+                .unwrap_or(false),
+            roles: member
+                .then_some(guild)
+                .into_iter()
+                .chain(roles.iter().copied())
+                .filter_map(|id| self.get_role(id))
+                .map(|role| role.get_permissions())
+                .collect(),
+            timed_out: user
+                .map(|user| self.get_timeout(user).is_some())
+                .unwrap_or(false),
+            ..Default::default()
+        };
+        let Some(channel_id) = channel_id else {
+            return grants;
+        };
+        let overwrite = |target| self.get::<_, Overwrite>(overwrite_path(channel_id, target));
+        if member {
+            grants.everyone_overwrite = overwrite(OverwriteTarget::Role(guild));
+        }
+        grants.role_overwrites = roles
+            .iter()
+            .filter_map(|id| overwrite(OverwriteTarget::Role(*id)))
+            .collect();
+        if let Some(user) = user {
+            grants.channel_op = self
+                .base
+                .contains_key(format!("/op/channels/{channel_id}/{user}"))
+                .unwrap();
+            grants.user_overwrite = overwrite(OverwriteTarget::User(user));
+        }
+        grants
+    }
+
+    /// server operators, operators of the guild and its members with an administrator role.
+    pub(super) fn administrators(&self, guild: Id) -> BTreeSet<Id> {
+        let admin_roles = self
+            .roles(guild)
+            .into_iter()
+            .filter(|role| role.get_permissions().contains(Permissions::ADMINISTRATOR))
+            .map(|role| role.get_id())
+            .collect::<BTreeSet<_>>();
+        let mut users = self.list("/op/serv/").into_iter().collect::<BTreeSet<_>>();
+        users.extend(self.guild_ops(guild));
+        for key in self.keys("/assignments/") {
+            let mut parts = key.rsplit('/');
+            let role = parts.next().and_then(Id::from_string);
+            let user = parts.next().and_then(Id::from_string);
+            if let (Some(role), Some(user)) = (role, user) {
+                if admin_roles.contains(&role) {
+                    users.insert(user);
+                }
+            }
+        }
+        users
+    }
+
+    /// drops the roles and overwrites of a deleted user.
+    pub(super) fn remove_user_grants(&mut self, user: Id) {
+        for key in self.keys(format!("/assignments/{user}/")) {
+            self.remove(key);
+        }
+        let suffix = format!("/users/{user}");
+        for key in self.keys("/overwrites/") {
+            if key.ends_with(&suffix) {
+                self.remove(key);
+            }
+        }
+    }
+
+    /// drops the overwrites of a purged channel.
+    pub(super) fn remove_overwrites(&mut self, channel_id: Id) {
+        for key in self.keys(format!("/overwrites/{channel_id}/")) {
+            self.remove(key);
+        }
+    }
+}
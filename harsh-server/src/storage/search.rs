@@ -0,0 +1,241 @@
+use std::collections::BTreeSet;
+
+use super::{Message, StorageProc};
+use crate::Id;
+
+const MAX_TERM_LENGTH: usize = 64;
+pub const MAX_SEARCH_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// words to look for, with double quotes around phrases that must appear as is.
+    pub text: String,
+    pub channel: Option<Id>,
+    pub author: Option<Id>,
+    /// bounds of the creation date, in milliseconds since the epoch.
+    pub after: Option<i64>,
+    pub before: Option<i64>,
+    pub offset: usize,
+    pub limit: usize,
+    /// user the search is made for, messages of channels they can not see are left out.
+    pub viewer: Option<Id>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub channel_id: Id,
+    pub id: Id,
+    pub author: Option<Id>,
+    /// content of the message with matched words surrounded by `**`.
+    pub highlight: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchResults {
+    pub total: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+/// splits a text into lowercase alphanumeric words.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .filter(|word| word.len() <= MAX_TERM_LENGTH)
+        .collect()
+}
+
+/// parses a query into its loose terms and its phrases.
+fn parse(text: &str) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut terms = Vec::new();
+    let mut phrases = Vec::new();
+    for (index, part) in text.split('"').enumerate() {
+        let tokens = tokenize(part);
+        if index % 2 == 1 && tokens.len() > 1 {
+            phrases.push(tokens);
+        } else {
+            terms.extend(tokens);
+        }
+    }
+    (terms, phrases)
+}
+
+fn contains_phrase(tokens: &[String], phrase: &[String]) -> bool {
+    tokens.windows(phrase.len()).any(|window| window == phrase)
+}
+
+fn highlight(content: &str, words: &BTreeSet<String>) -> String {
+    let mut result = String::new();
+    let mut word = String::new();
+    let flush = |result: &mut String, word: &mut String| {
+        if words.contains(&word.to_lowercase()) {
+            result.push_str(&format!("**{word}**"));
+        } else {
+            result.push_str(word);
+        }
+        word.clear();
+    };
+    for char in content.chars() {
+        if char.is_alphanumeric() {
+            word.push(char);
+        } else {
+            flush(&mut result, &mut word);
+            result.push(char);
+        }
+    }
+    flush(&mut result, &mut word);
+    result
+}
+
+fn index_path(term: &str, channel_id: Id, id: Id) -> String {
+    format!("/index/{term}/{channel_id}/{id}")
+}
+
+impl StorageProc {
+    pub(super) fn index_message(&self, channel_id: Id, message: &Message) {
+        let id = message.get_id();
+        let terms = tokenize(message.get_content())
+            .into_iter()
+            .collect::<BTreeSet<_>>();
+        for term in terms {
+            self.base
+                .insert(index_path(&term, channel_id, id), &[])
+                .unwrap();
+        }
+    }
+
+    pub(super) fn unindex_message(&self, channel_id: Id, message: &Message) {
+        let id = message.get_id();
+        for term in tokenize(message.get_content()) {
+            self.base.remove(index_path(&term, channel_id, id)).unwrap();
+        }
+    }
+
+    /// messages whose content contains the term.
+    fn postings(&self, term: &str, channel: Option<Id>) -> BTreeSet<(Id, Id)> {
+        let prefix = match channel {
+            Some(channel_id) => format!("/index/{term}/{channel_id}/"),
+            None => format!("/index/{term}/"),
+        };
+        self.keys(prefix)
+            .iter()
+            .filter_map(|key| {
+                let mut parts = key.rsplit('/');
+                let id = Id::from_string(parts.next()?)?;
+                let channel_id = Id::from_string(parts.next()?)?;
+                Some((channel_id, id))
+            })
+            .collect()
+    }
+
+    fn all_messages(&self, channel: Option<Id>) -> BTreeSet<(Id, Id)> {
+        let channels = match channel {
+            Some(channel_id) => vec![channel_id],
+            None => self.list("/channels/"),
+        };
+        channels
+            .into_iter()
+            .flat_map(|channel_id| {
+                self.list(format!("/messages/{channel_id}/"))
+                    .into_iter()
+                    .map(move |id| (channel_id, id))
+            })
+            .collect()
+    }
+
+    pub(super) fn search(&self, query: SearchQuery) -> SearchResults {
+        let (terms, phrases) = parse(&query.text);
+        let words = terms
+            .iter()
+            .chain(phrases.iter().flatten())
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        let mut candidates: Option<BTreeSet<(Id, Id)>> = None;
+        for word in &words {
+            let postings = self.postings(word, query.channel);
+            let narrowed = match candidates {
+                Some(candidates) => candidates.intersection(&postings).cloned().collect(),
+                None => postings,
+            };
+            candidates = Some(narrowed);
+        }
+        let candidates = candidates.unwrap_or_else(|| self.all_messages(query.channel));
+        let hidden = self.hidden_channels(query.viewer);
+
+        let mut matches = candidates
+            .into_iter()
+            .filter(|(channel_id, _)| !hidden.contains(channel_id))
+            .filter(|(_, id)| {
+                let time = id.timestamp();
+                query.after.map(|after| time >= after).unwrap_or(true)
+                    && query.before.map(|before| time < before).unwrap_or(true)
+            })
+            .filter_map(|(channel_id, id)| {
+                let message = self.get::<_, Message>(format!("/messages/{channel_id}/{id}"))?;
+                if query.author.is_some() && message.get_author() != query.author {
+                    return None;
+                }
+                let tokens = tokenize(message.get_content());
+                if !phrases
+                    .iter()
+                    .all(|phrase| contains_phrase(&tokens, phrase))
+                {
+                    return None;
+                }
+                Some((channel_id, message))
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(_, message)| std::cmp::Reverse(message.get_id()));
+
+        let total = matches.len();
+        let limit = query.limit.clamp(1, MAX_SEARCH_LIMIT);
+        let hits = matches
+            .into_iter()
+            .skip(query.offset)
+            .take(limit)
+            .map(|(channel_id, message)| SearchHit {
+                channel_id,
+                id: message.get_id(),
+                author: message.get_author(),
+                highlight: highlight(message.get_content(), &words),
+            })
+            .collect();
+        SearchResults { total, hits }
+    }
+
+    /// rebuilds the index from every stored message, returns the number of indexed messages.
+    pub(super) fn reindex(&self) -> usize {
+        for key in self.keys("/index/") {
+            self.base.remove(key).unwrap();
+        }
+        let messages = self.all_messages(None);
+        for (channel_id, id) in &messages {
+            if let Some(message) = self.get::<_, Message>(format!("/messages/{channel_id}/{id}")) {
+                self.index_message(*channel_id, &message);
+            }
+        }
+        messages.len()
+    }
+}
+
+#[test]
+fn test_tokenize() {
+    assert_eq!(
+        tokenize("Hello, World! it's 2022"),
+        vec!["hello", "world", "it", "s", "2022"]
+    );
+}
+
+#[test]
+fn test_parse() {
+    let (terms, phrases) = parse(r#"cat "big dog" "bird""#);
+    assert_eq!(terms, vec!["cat", "bird"]);
+    assert_eq!(phrases, vec![vec!["big".to_string(), "dog".to_string()]]);
+}
+
+#[test]
+fn test_highlight() {
+    let words = ["dog".to_string()].into_iter().collect();
+    assert_eq!(highlight("A Dog, a dogma.", &words), "A **Dog**, a dogma.");
+}
@@ -32,7 +32,7 @@ async fn test_channels() {
     let id = rec.await.unwrap();
 
     // query all
-    let (cmd, rec) = StorageCmd::new_channel_list();
+    let (cmd, rec) = StorageCmd::new_channel_list(None);
     remote.send(cmd).unwrap();
     let result = rec.await.unwrap();
     assert_eq!(result.len(), 1);
@@ -51,7 +51,7 @@ async fn test_channels() {
     let id2 = rec.await.unwrap();
 
     // query all
-    let (cmd, rec) = StorageCmd::new_channel_list();
+    let (cmd, rec) = StorageCmd::new_channel_list(None);
     remote.send(cmd).unwrap();
     let result = rec.await.unwrap();
     assert_eq!(result.len(), 2);
@@ -62,3 +62,1233 @@ async fn test_channels() {
     let result = rec.await.unwrap();
     assert_eq!(result.unwrap(), "b-channel".to_string());
 }
+
+#[tokio::test]
+async fn test_channel_info() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-info").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-info")).spawn();
+    let remote = store.remote();
+    let creator = Id::from_u64(1);
+
+    let (cmd, rec) = StorageCmd::new_channel_create_by(
+        "a-channel",
+        ChannelKind::Text,
+        Guild::DEFAULT,
+        Some(creator),
+    );
+    remote.send(cmd).unwrap();
+    let id = rec.await.unwrap();
+    remote
+        .send(StorageCmd::new_channel_set_topic(id, "a topic".into()))
+        .unwrap();
+    remote
+        .send(StorageCmd::new_channel_set_description(
+            id,
+            "a description".into(),
+        ))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_set_kind(id, ChannelKind::Announcement);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_channel_set_kind(id, ChannelKind::Category);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+
+    let (cmd, rec) = StorageCmd::new_channel_get(id);
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap().unwrap();
+    assert_eq!(channel.get_topic(), "a topic");
+    assert_eq!(channel.get_description(), "a description");
+    assert_eq!(channel.get_kind(), ChannelKind::Announcement);
+    assert_eq!(channel.get_creator(), Some(creator));
+    assert_eq!(channel.get_created(), id.timestamp());
+}
+
+#[tokio::test]
+async fn test_categories() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-categories").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-categories")).spawn();
+    let remote = store.remote();
+
+    let (cmd, rec) = StorageCmd::new_channel_create_by(
+        "a-category",
+        ChannelKind::Category,
+        Guild::DEFAULT,
+        None,
+    );
+    remote.send(cmd).unwrap();
+    let category = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let first = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_create("b-channel");
+    remote.send(cmd).unwrap();
+    let second = rec.await.unwrap();
+
+    let (cmd, rec) = StorageCmd::new_channel_set_parent(first, Some(category));
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_channel_set_parent(second, Some(first));
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_channel_set_parent(category, Some(category));
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+
+    remote
+        .send(StorageCmd::new_channel_reorder(vec![second, category]))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_tree(None, Guild::DEFAULT);
+    remote.send(cmd).unwrap();
+    assert_eq!(
+        rec.await.unwrap(),
+        vec![(second, vec![]), (category, vec![first])]
+    );
+    let (cmd, rec) = StorageCmd::new_channel_list(None);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![second, category, first]);
+
+    // channels of a deleted category move to the top level
+    remote
+        .send(StorageCmd::new_channel_delete(category))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_tree(None, Guild::DEFAULT);
+    remote.send(cmd).unwrap();
+    let mut expected = vec![(first, vec![]), (second, vec![])];
+    expected.sort();
+    assert_eq!(rec.await.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_user_names() {
+    use telecomande::{Executor, SimpleExecutor};
+    // cleaning;
+    std::fs::remove_dir_all("/tmp/db-test-users").ok();
+
+    // instantiation
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-users")).spawn();
+    let remote = store.remote();
+
+    // insertion
+    let (cmd, rec) = StorageCmd::new_user_create("Alice".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    let alice = rec.await.unwrap().unwrap();
+
+    // names are unique regardless of case
+    let (cmd, rec) = StorageCmd::new_user_create("alice".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), None);
+
+    let (cmd, rec) = StorageCmd::new_user_create("bob".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    let bob = rec.await.unwrap().unwrap();
+
+    let (cmd, rec) = StorageCmd::new_user_set_name(bob, "ALICE".into());
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+
+    // lookup
+    let (cmd, rec) = StorageCmd::new_user_find_by_name("aLiCe".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(alice));
+
+    // renaming frees the previous name
+    let (cmd, rec) = StorageCmd::new_user_set_name(alice, "carol".into());
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_user_set_name(bob, "alice".into());
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_user_find_by_name("Alice".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(bob));
+
+    // deletion frees the name
+    let (cmd, rec) = StorageCmd::new_user_delete(bob, false);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_user_find_by_name("alice".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_invites() {
+    use telecomande::{Executor, SimpleExecutor};
+    // cleaning;
+    std::fs::remove_dir_all("/tmp/db-test-invites").ok();
+
+    // instantiation
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-invites")).spawn();
+    let remote = store.remote();
+
+    // insertion
+    let creator = Id::from_now();
+    let invite = Invite::new(creator, Some(1), None);
+    let code = invite.get_code().to_string();
+    remote.send(StorageCmd::new_invite_create(invite)).unwrap();
+    let expired = Invite::new(creator, None, Some(0));
+    let expired_code = expired.get_code().to_string();
+    remote.send(StorageCmd::new_invite_create(expired)).unwrap();
+
+    // query all
+    let (cmd, rec) = StorageCmd::new_invite_list(None);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().len(), 2);
+
+    // uses are limited
+    let (cmd, rec) = StorageCmd::new_invite_use(code.clone(), None);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_invite_use(code.clone(), None);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_invite_use(expired_code, None);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+
+    // guild invites are listed apart, and only lead to their guild
+    let guild = Id::from_now();
+    let guild_invite = Invite::new(creator, None, None).for_guild(guild);
+    let guild_code = guild_invite.get_code().to_string();
+    remote
+        .send(StorageCmd::new_invite_create(guild_invite))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_invite_list(None);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().len(), 2);
+    let (cmd, rec) = StorageCmd::new_invite_list(Some(guild));
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().len(), 1);
+    let (cmd, rec) = StorageCmd::new_invite_use(guild_code.clone(), None);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_invite_use(guild_code, Some(guild));
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+
+    // revocation
+    let (cmd, rec) = StorageCmd::new_invite_revoke(code.clone());
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_invite_get(code);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_user_delete_cascade() {
+    use telecomande::{Executor, SimpleExecutor};
+    // cleaning;
+    std::fs::remove_dir_all("/tmp/db-test-user-delete").ok();
+
+    // instantiation
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-user-delete")).spawn();
+    let remote = store.remote();
+
+    // insertion
+    let (cmd, rec) = StorageCmd::new_user_create("alice".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    let alice = rec.await.unwrap().unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    remote
+        .send(StorageCmd::new_perm_server_add_op(alice))
+        .unwrap();
+    remote
+        .send(StorageCmd::new_perm_channel_add_op(channel, alice))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, Some(alice), "hello".into());
+    remote.send(cmd).unwrap();
+    let authored = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, None, "world".into());
+    remote.send(cmd).unwrap();
+    let anonymous = rec.await.unwrap();
+
+    // deletion
+    let (cmd, rec) = StorageCmd::new_user_delete(alice, true);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![(channel, authored)]);
+
+    // cascades
+    let (cmd, rec) = StorageCmd::new_perm_server_get_op();
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_perm_channel_get_op(channel);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_message_list(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![anonymous]);
+}
+
+#[tokio::test]
+async fn test_message_search() {
+    use telecomande::{Executor, SimpleExecutor};
+    // cleaning;
+    std::fs::remove_dir_all("/tmp/db-test-search").ok();
+
+    // instantiation
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-search")).spawn();
+    let remote = store.remote();
+    let search = |text: &str| {
+        let query = SearchQuery {
+            text: text.to_string(),
+            limit: 10,
+            ..Default::default()
+        };
+        let (cmd, rec) = StorageCmd::new_message_search(query);
+        remote.send(cmd).unwrap();
+        rec
+    };
+
+    // insertion
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, None, "the big red dog".into());
+    remote.send(cmd).unwrap();
+    let first = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, None, "a red big Dog".into());
+    remote.send(cmd).unwrap();
+    let second = rec.await.unwrap();
+
+    // terms and phrases
+    let results = search("dog red").await.unwrap();
+    assert_eq!(results.total, 2);
+    let hit = results.hits.iter().find(|hit| hit.id == second).unwrap();
+    assert_eq!(hit.highlight, "a **red** big **Dog**");
+    let results = search(r#""big red""#).await.unwrap();
+    assert_eq!(results.total, 1);
+    assert_eq!(results.hits[0].id, first);
+
+    // edition and deletion
+    let (cmd, rec) = StorageCmd::new_message_set_content(channel, first, None, "a cat".into());
+    remote.send(cmd).unwrap();
+    rec.await.unwrap();
+    assert_eq!(search("dog").await.unwrap().total, 1);
+    assert_eq!(search("cat").await.unwrap().total, 1);
+    remote
+        .send(StorageCmd::new_message_delete(channel, second))
+        .unwrap();
+    assert_eq!(search("dog").await.unwrap().total, 0);
+
+    // rebuild
+    let (cmd, rec) = StorageCmd::new_message_reindex();
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), 1);
+    assert_eq!(search("cat").await.unwrap().total, 1);
+}
+
+#[tokio::test]
+async fn test_message_history() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-history").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-history")).spawn();
+    let remote = store.remote();
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let author = Id::from_u64(1);
+    let editor = Id::from_u64(2);
+    let (cmd, rec) = StorageCmd::new_message_create(channel, Some(author), "first".into());
+    remote.send(cmd).unwrap();
+    let id = rec.await.unwrap();
+
+    // never edited
+    let (cmd, rec) = StorageCmd::new_message_get_history(channel, id);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+
+    // edits append revisions, starting with the original content
+    let (cmd, rec) =
+        StorageCmd::new_message_set_content(channel, id, Some(author), "second".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(1));
+    let (cmd, rec) = StorageCmd::new_message_set_content(channel, id, Some(editor), "third".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(2));
+    let (cmd, rec) = StorageCmd::new_message_get_history(channel, id);
+    remote.send(cmd).unwrap();
+    let revisions = rec.await.unwrap();
+    let contents = revisions
+        .iter()
+        .map(|r| r.get_content())
+        .collect::<Vec<_>>();
+    assert_eq!(contents, vec!["first", "second", "third"]);
+    assert_eq!(revisions[2].get_editor(), Some(editor));
+    let (cmd, rec) = StorageCmd::new_message_get(channel, id);
+    remote.send(cmd).unwrap();
+    let message = rec.await.unwrap().unwrap();
+    assert_eq!(message.get_content(), "third");
+    assert_eq!(message.get_edits(), 2);
+
+    // the message still lists once, and its revisions go with it once purged
+    let (cmd, rec) = StorageCmd::new_message_list(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![id]);
+    remote
+        .send(StorageCmd::new_message_delete(channel, id))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_purge(i64::MAX);
+    remote.send(cmd).unwrap();
+    rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_get_history(channel, id);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+
+    // missing messages are reported
+    let (cmd, rec) = StorageCmd::new_message_set_content(channel, id, None, "gone".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_trash() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-trash").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-trash")).spawn();
+    let remote = store.remote();
+    let list_messages = |channel| {
+        let (cmd, rec) = StorageCmd::new_message_list(channel);
+        remote.send(cmd).unwrap();
+        rec
+    };
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, None, "hello".into());
+    remote.send(cmd).unwrap();
+    let message = rec.await.unwrap();
+
+    // deleted messages leave the listing and can be restored
+    remote
+        .send(StorageCmd::new_message_delete(channel, message))
+        .unwrap();
+    assert!(list_messages(channel).await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_message_restore(channel, message);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    assert_eq!(list_messages(channel).await.unwrap(), vec![message]);
+    let (cmd, rec) = StorageCmd::new_message_restore(channel, message);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+
+    // so do channels
+    remote
+        .send(StorageCmd::new_channel_delete(channel))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_list(None);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_channel_restore(channel);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+
+    // purges only remove items deleted before the given date
+    remote
+        .send(StorageCmd::new_message_delete(channel, message))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_purge(0);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().messages.is_empty());
+    let (cmd, rec) = StorageCmd::new_purge(i64::MAX);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().messages, vec![(channel, message)]);
+    let (cmd, rec) = StorageCmd::new_message_restore(channel, message);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+}
+
+#[tokio::test]
+async fn test_threads() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-threads").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-threads")).spawn();
+    let remote = store.remote();
+    let alice = Id::from_u64(1);
+    let bob = Id::from_u64(2);
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, Some(alice), "question".into());
+    remote.send(cmd).unwrap();
+    let parent = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create_linked(
+        channel,
+        None,
+        "see above".into(),
+        Some(parent),
+        None,
+    );
+    remote.send(cmd).unwrap();
+    let reply = rec.await.unwrap();
+    let mut answers = Vec::new();
+    for content in ["first", "second", "third"] {
+        let (cmd, rec) = StorageCmd::new_message_create_linked(
+            channel,
+            Some(bob),
+            content.into(),
+            None,
+            Some(parent),
+        );
+        remote.send(cmd).unwrap();
+        answers.push(rec.await.unwrap());
+    }
+
+    // thread messages stay out of the channel
+    let (cmd, rec) = StorageCmd::new_message_list(channel);
+    remote.send(cmd).unwrap();
+    let mut listed = rec.await.unwrap();
+    listed.sort();
+    let mut expected = vec![parent, reply];
+    expected.sort();
+    assert_eq!(listed, expected);
+    let (cmd, rec) = StorageCmd::new_message_get(channel, reply);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().unwrap().get_reply_to(), Some(parent));
+
+    // counters of the parent
+    let (cmd, rec) = StorageCmd::new_message_get(channel, parent);
+    remote.send(cmd).unwrap();
+    let message = rec.await.unwrap().unwrap();
+    assert_eq!(message.get_replies(), 3);
+    assert!(message.get_last_activity().is_some());
+
+    // pagination
+    answers.sort();
+    let (cmd, rec) = StorageCmd::new_thread_message_list(channel, parent, None, 2);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), answers[1..].to_vec());
+    let (cmd, rec) = StorageCmd::new_thread_message_list(channel, parent, Some(answers[1]), 2);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), answers[..1].to_vec());
+
+    // posters and the author of the parent are subscribed
+    let (cmd, rec) = StorageCmd::new_thread_subscribers(channel, parent);
+    remote.send(cmd).unwrap();
+    let mut subscribers = rec.await.unwrap();
+    subscribers.sort();
+    assert_eq!(subscribers, vec![alice, bob]);
+    remote
+        .send(StorageCmd::new_thread_unsubscribe(channel, parent, bob))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_thread_subscribers(channel, parent);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![alice]);
+
+    // deleted messages leave the thread
+    remote
+        .send(StorageCmd::new_message_delete(channel, answers[0]))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_message_get(channel, parent);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().unwrap().get_replies(), 2);
+}
+
+#[tokio::test]
+async fn test_reactions() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-reactions").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-reactions")).spawn();
+    let remote = store.remote();
+    let alice = Id::from_u64(1);
+    let bob = Id::from_u64(2);
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, None, "hello".into());
+    remote.send(cmd).unwrap();
+    let message = rec.await.unwrap();
+
+    for (emoji, user, added) in [("👍", alice, true), ("👍", bob, true), ("👍", bob, false)] {
+        let (cmd, rec) = StorageCmd::new_reaction_add(channel, message, emoji.into(), user);
+        remote.send(cmd).unwrap();
+        assert_eq!(rec.await.unwrap(), added);
+    }
+    let (cmd, rec) = StorageCmd::new_reaction_add(channel, message, "🎉".into(), bob);
+    remote.send(cmd).unwrap();
+    rec.await.unwrap();
+
+    let (cmd, rec) = StorageCmd::new_reaction_list(channel, message);
+    remote.send(cmd).unwrap();
+    let reactions = rec.await.unwrap();
+    assert_eq!(reactions["👍"], vec![alice, bob]);
+    assert_eq!(reactions["🎉"], vec![bob]);
+
+    let (cmd, rec) = StorageCmd::new_reaction_remove(channel, message, "👍".into(), alice);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_reaction_list_users(channel, message, "👍".into());
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![bob]);
+
+    // reactions do not show up as messages
+    let (cmd, rec) = StorageCmd::new_message_list(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![message]);
+}
+
+#[tokio::test]
+async fn test_mentions() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-mentions").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-mentions")).spawn();
+    let remote = store.remote();
+    let mentions = |user| {
+        let (cmd, rec) = StorageCmd::new_mention_list(user, None, 10);
+        remote.send(cmd).unwrap();
+        rec
+    };
+
+    let mut users = Vec::new();
+    for name in ["alice", "bob", "carol"] {
+        let (cmd, rec) = StorageCmd::new_user_create(name.into(), "pass".into());
+        remote.send(cmd).unwrap();
+        users.push(rec.await.unwrap().unwrap());
+    }
+    let (alice, bob, carol) = (users[0], users[1], users[2]);
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    remote
+        .send(StorageCmd::new_perm_channel_add_op(channel, carol))
+        .unwrap();
+
+    let mut messages = Vec::new();
+    for content in [format!("hi <@{bob}> and <@424242>"), "ping <@ops>".into()] {
+        let (cmd, rec) = StorageCmd::new_message_create(channel, Some(alice), content);
+        remote.send(cmd).unwrap();
+        messages.push(rec.await.unwrap());
+    }
+    let (cmd, rec) = StorageCmd::new_message_get(channel, messages[0]);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().unwrap().get_mentions(), &[bob]);
+    assert_eq!(mentions(bob).await.unwrap(), vec![(channel, messages[0])]);
+    assert_eq!(mentions(carol).await.unwrap(), vec![(channel, messages[1])]);
+
+    // edits replace the mentions of a message
+    let (cmd, rec) =
+        StorageCmd::new_message_set_content(channel, messages[0], None, "hi <@everyone>".into());
+    remote.send(cmd).unwrap();
+    rec.await.unwrap();
+    assert_eq!(mentions(alice).await.unwrap(), vec![(channel, messages[0])]);
+    let mut both = vec![(channel, messages[0]), (channel, messages[1])];
+    both.sort();
+    assert_eq!(mentions(carol).await.unwrap(), both);
+
+    // mentions in deleted messages are hidden until restored
+    remote
+        .send(StorageCmd::new_message_delete(channel, messages[0]))
+        .unwrap();
+    assert!(mentions(bob).await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_message_restore(channel, messages[0]);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    assert_eq!(mentions(bob).await.unwrap(), vec![(channel, messages[0])]);
+
+    // only mentions after the given message
+    let (cmd, rec) = StorageCmd::new_mention_list(carol, Some(both[0].1), 10);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![both[1]]);
+}
+
+#[tokio::test]
+async fn test_read_state() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-read-state").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-read-state")).spawn();
+    let remote = store.remote();
+    let states = |user| {
+        let (cmd, rec) = StorageCmd::new_read_state_list(user);
+        remote.send(cmd).unwrap();
+        rec
+    };
+
+    let (cmd, rec) = StorageCmd::new_user_create("alice".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    let alice = rec.await.unwrap().unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let mut messages = Vec::new();
+    for content in ["hello".into(), format!("hi <@{alice}>"), "bye".into()] {
+        let (cmd, rec) = StorageCmd::new_message_create(channel, None, content);
+        remote.send(cmd).unwrap();
+        messages.push(rec.await.unwrap());
+    }
+    messages.sort();
+
+    let state = states(alice).await.unwrap().remove(0);
+    assert_eq!(state.last_read, None);
+    assert_eq!((state.unread, state.mentions), (3, 1));
+
+    remote
+        .send(StorageCmd::new_channel_ack(alice, channel, messages[1]))
+        .unwrap();
+    let state = states(alice).await.unwrap().remove(0);
+    assert_eq!(state.last_read, Some(messages[1]));
+    assert_eq!(state.unread, 1);
+
+    // markers never move backward
+    remote
+        .send(StorageCmd::new_channel_ack(alice, channel, messages[0]))
+        .unwrap();
+    let state = states(alice).await.unwrap().remove(0);
+    assert_eq!(state.last_read, Some(messages[1]));
+    remote
+        .send(StorageCmd::new_channel_ack(alice, channel, messages[2]))
+        .unwrap();
+    let state = states(alice).await.unwrap().remove(0);
+    assert_eq!((state.unread, state.mentions), (0, 0));
+}
+
+#[tokio::test]
+async fn test_direct() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-direct").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-direct")).spawn();
+    let remote = store.remote();
+    let (alice, bob, carol) = (Id::from_u64(1), Id::from_u64(2), Id::from_u64(3));
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let public = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_direct_open(vec![bob, alice]);
+    remote.send(cmd).unwrap();
+    let (direct, created) = rec.await.unwrap();
+    assert!(created);
+
+    // the same group gets the same channel
+    let (cmd, rec) = StorageCmd::new_direct_open(vec![alice, bob, alice]);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), (direct, false));
+    let (cmd, rec) = StorageCmd::new_channel_get(direct);
+    remote.send(cmd).unwrap();
+    assert_eq!(
+        rec.await.unwrap().unwrap().get_participants(),
+        &[alice, bob]
+    );
+
+    // only listed to participants
+    let (cmd, rec) = StorageCmd::new_channel_list(None);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![public]);
+    let (cmd, rec) = StorageCmd::new_direct_list(alice);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![direct]);
+    let (cmd, rec) = StorageCmd::new_direct_list(carol);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+
+    // and searched by them only
+    let (cmd, rec) = StorageCmd::new_message_create(direct, Some(alice), "secret".into());
+    remote.send(cmd).unwrap();
+    rec.await.unwrap();
+    for (viewer, total) in [(Some(bob), 1), (Some(carol), 0), (None, 0)] {
+        let query = SearchQuery {
+            text: "secret".into(),
+            limit: 10,
+            viewer,
+            ..Default::default()
+        };
+        let (cmd, rec) = StorageCmd::new_message_search(query);
+        remote.send(cmd).unwrap();
+        assert_eq!(rec.await.unwrap().total, total);
+    }
+}
+
+#[tokio::test]
+async fn test_private_channels() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-private").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-private")).spawn();
+    let remote = store.remote();
+    let (member, op, other) = (Id::from_u64(1), Id::from_u64(2), Id::from_u64(3));
+    let listed = |viewer| {
+        let (cmd, rec) = StorageCmd::new_channel_list(Some(viewer));
+        remote.send(cmd).unwrap();
+        rec
+    };
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    remote
+        .send(StorageCmd::new_perm_channel_add_op(channel, op))
+        .unwrap();
+    remote
+        .send(StorageCmd::new_channel_member_add(channel, member))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_audience(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), None);
+
+    remote
+        .send(StorageCmd::new_channel_set_private(channel, true))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_audience(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(vec![member, op]));
+    assert_eq!(listed(member).await.unwrap(), vec![channel]);
+    assert!(listed(other).await.unwrap().is_empty());
+
+    remote
+        .send(StorageCmd::new_channel_member_remove(channel, member))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_member_list(channel);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    assert!(listed(member).await.unwrap().is_empty());
+    assert_eq!(listed(op).await.unwrap(), vec![channel]);
+
+    // deleted channels are closed to everyone until restored
+    remote
+        .send(StorageCmd::new_channel_delete(channel))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_audience(channel);
+    remote.send(cmd).unwrap();
+    let audience = rec.await.unwrap().unwrap();
+    assert!(!audience.contains(&other));
+    assert!(!audience.contains(&op));
+    let (cmd, rec) = StorageCmd::new_channel_restore(channel);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_channel_audience(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(vec![op]));
+}
+
+#[tokio::test]
+async fn test_roles() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-roles").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-roles")).spawn();
+    let remote = store.remote();
+    let user = Id::from_u64(1);
+    let send = Permissions::SEND_MESSAGES;
+    let manage = Permissions::MANAGE_MESSAGES;
+
+    let (cmd, rec) = StorageCmd::new_role_create(Guild::DEFAULT, "mod".into(), manage);
+    remote.send(cmd).unwrap();
+    let role = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_role_list(Guild::DEFAULT);
+    remote.send(cmd).unwrap();
+    let roles = rec.await.unwrap();
+    assert_eq!(roles.len(), 2);
+    assert_eq!(roles[0], Role::everyone(Guild::DEFAULT));
+    assert_eq!(roles[1].get_name(), "mod");
+
+    let (cmd, rec) = StorageCmd::new_role_assign(role, user, true);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_role_assign(role, user, true);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_user_list_roles(user);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![role]);
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let overwrite = Overwrite {
+        allow: Permissions::empty(),
+        deny: send,
+    };
+    let command = StorageCmd::new_overwrite_set(channel, OverwriteTarget::Role(role), overwrite);
+    remote.send(command).unwrap();
+    let (cmd, rec) = StorageCmd::new_overwrite_list(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(
+        rec.await.unwrap(),
+        vec![(OverwriteTarget::Role(role), overwrite)]
+    );
+
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(user), Scope::Channel(channel));
+    remote.send(cmd).unwrap();
+    let grants = rec.await.unwrap();
+    assert_eq!(grants.roles, vec![send, manage]);
+    assert_eq!(grants.role_overwrites, vec![overwrite]);
+    assert!(!grants.server_op && !grants.channel_op);
+
+    let (cmd, rec) = StorageCmd::new_role_delete(role);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_role_delete(Guild::DEFAULT);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(user), Scope::Channel(channel));
+    remote.send(cmd).unwrap();
+    let grants = rec.await.unwrap();
+    assert_eq!(grants.roles, vec![send]);
+    assert!(grants.role_overwrites.is_empty());
+}
+
+#[tokio::test]
+async fn test_guilds() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-guilds").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-guilds")).spawn();
+    let remote = store.remote();
+    let (cmd, rec) = StorageCmd::new_user_create("owner".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    let owner = rec.await.unwrap().unwrap();
+    let (cmd, rec) = StorageCmd::new_user_create("member".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    let member = rec.await.unwrap().unwrap();
+
+    let (cmd, rec) = StorageCmd::new_guild_create("a-guild".into(), owner);
+    remote.send(cmd).unwrap();
+    let guild = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_guild_list(owner);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![Guild::DEFAULT, guild]);
+    let (cmd, rec) = StorageCmd::new_guild_list(member);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![Guild::DEFAULT]);
+
+    // channels of a guild are only listed in it, and only its members see them
+    let (cmd, rec) =
+        StorageCmd::new_channel_create_by("a-channel", ChannelKind::Text, guild, Some(owner));
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_list(None);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_channel_tree(Some(owner), guild);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![(channel, vec![])]);
+    let (cmd, rec) = StorageCmd::new_channel_audience(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(vec![owner]));
+
+    let (cmd, rec) = StorageCmd::new_guild_member_set(guild, member, true);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_guild_member_set(Guild::DEFAULT, member, false);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+
+    // roles of a guild only count in it
+    let (cmd, rec) = StorageCmd::new_role_create(guild, "mod".into(), Permissions::MANAGE_CHANNELS);
+    remote.send(cmd).unwrap();
+    let role = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_role_assign(role, member, true);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(member), Scope::Guild(guild));
+    remote.send(cmd).unwrap();
+    let grants = rec.await.unwrap();
+    assert_eq!(
+        grants.roles,
+        vec![
+            Role::everyone(guild).get_permissions(),
+            Permissions::MANAGE_CHANNELS
+        ]
+    );
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(member), Scope::Server);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().roles.len(), 1);
+
+    let (cmd, rec) = StorageCmd::new_guild_set_op(guild, member, true);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(member), Scope::Channel(channel));
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().guild_op);
+
+    // leaving a guild drops its roles and operator status
+    let (cmd, rec) = StorageCmd::new_guild_member_set(guild, member, false);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_user_list_roles(member);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(member), Scope::Guild(guild));
+    remote.send(cmd).unwrap();
+    let grants = rec.await.unwrap();
+    assert!(grants.roles.is_empty() && !grants.guild_op);
+
+    let (cmd, rec) = StorageCmd::new_guild_delete(Guild::DEFAULT);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_guild_delete(guild);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_channel_get(channel);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_none());
+    let (cmd, rec) = StorageCmd::new_role_get(role);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_profiles() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-profiles").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-profiles")).spawn();
+    let remote = store.remote();
+    let (cmd, rec) = StorageCmd::new_user_create("alice".into(), "hash".into());
+    remote.send(cmd).unwrap();
+    let alice = rec.await.unwrap().unwrap();
+    let (cmd, rec) = StorageCmd::new_user_create("bob".into(), "hash".into());
+    remote.send(cmd).unwrap();
+    let bob = rec.await.unwrap().unwrap();
+
+    let update = Profile {
+        display_name: Some("Alice".into()),
+        bio: Some("hello".into()),
+        ..Default::default()
+    };
+    let (cmd, rec) = StorageCmd::new_user_update_profile(alice, update);
+    remote.send(cmd).unwrap();
+    rec.await.unwrap().unwrap();
+    // fields left out are kept, empty ones are cleared
+    let update = Profile {
+        bio: Some("".into()),
+        custom_status: Some("away".into()),
+        ..Default::default()
+    };
+    let (cmd, rec) = StorageCmd::new_user_update_profile(alice, update);
+    remote.send(cmd).unwrap();
+    rec.await.unwrap().unwrap();
+    let (cmd, rec) = StorageCmd::new_user_get_profile(alice);
+    remote.send(cmd).unwrap();
+    let expected = Profile {
+        display_name: Some("Alice".into()),
+        custom_status: Some("away".into()),
+        ..Default::default()
+    };
+    assert_eq!(rec.await.unwrap(), Some(expected));
+    let (cmd, rec) = StorageCmd::new_user_update_profile(Id::from_u64(1), Profile::default());
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_none());
+
+    // updates reach the users sharing a channel
+    let audience = |user| {
+        let (cmd, rec) = StorageCmd::new_user_profile_audience(user);
+        remote.send(cmd).unwrap();
+        rec
+    };
+    assert_eq!(audience(alice).await.unwrap(), Some(vec![alice]));
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    remote
+        .send(StorageCmd::new_channel_set_private(channel, true))
+        .unwrap();
+    remote
+        .send(StorageCmd::new_channel_member_add(channel, alice))
+        .unwrap();
+    remote
+        .send(StorageCmd::new_channel_member_add(channel, bob))
+        .unwrap();
+    let mut expected = vec![alice, bob];
+    expected.sort();
+    assert_eq!(audience(alice).await.unwrap(), Some(expected));
+    remote
+        .send(StorageCmd::new_channel_set_private(channel, false))
+        .unwrap();
+    assert_eq!(audience(alice).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_pins() {
+    use telecomande::{Executor, SimpleExecutor};
+    std::fs::remove_dir_all("/tmp/db-test-pins").ok();
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-pins")).spawn();
+    let remote = store.remote();
+    let pins = |channel| {
+        let (cmd, rec) = StorageCmd::new_channel_get_pins(channel);
+        remote.send(cmd).unwrap();
+        rec
+    };
+
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let mut messages = Vec::new();
+    for content in ["first", "second"] {
+        let (cmd, rec) = StorageCmd::new_message_create(channel, None, content.into());
+        remote.send(cmd).unwrap();
+        messages.push(rec.await.unwrap());
+    }
+
+    // pin order is kept, regardless of the order of the messages
+    for (message, pinned) in [
+        (messages[1], true),
+        (messages[0], true),
+        (messages[1], false),
+    ] {
+        let (cmd, rec) = StorageCmd::new_message_pin(channel, message);
+        remote.send(cmd).unwrap();
+        assert_eq!(rec.await.unwrap(), pinned);
+    }
+    assert_eq!(pins(channel).await.unwrap(), vec![messages[1], messages[0]]);
+
+    // deleted messages are hidden until restored
+    remote
+        .send(StorageCmd::new_message_delete(channel, messages[1]))
+        .unwrap();
+    assert_eq!(pins(channel).await.unwrap(), vec![messages[0]]);
+    let (cmd, rec) = StorageCmd::new_message_restore(channel, messages[1]);
+    remote.send(cmd).unwrap();
+    rec.await.unwrap();
+    assert_eq!(pins(channel).await.unwrap(), vec![messages[1], messages[0]]);
+
+    let (cmd, rec) = StorageCmd::new_message_unpin(channel, messages[1]);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    assert_eq!(pins(channel).await.unwrap(), vec![messages[0]]);
+
+    // limit
+    remote
+        .send(StorageCmd::new_channel_set_pin_limit(channel, Some(3)))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_channel_get_pin_limit(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(3));
+}
+
+#[tokio::test]
+async fn test_moderation() {
+    use telecomande::{Executor, SimpleExecutor};
+    // cleaning;
+    std::fs::remove_dir_all("/tmp/db-test-moderation").ok();
+
+    // instantiation
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-moderation")).spawn();
+    let remote = store.remote();
+
+    // insertion
+    let (cmd, rec) = StorageCmd::new_user_create("alice".into(), "pass".into());
+    remote.send(cmd).unwrap();
+    let alice = rec.await.unwrap().unwrap();
+    let moderator = Id::from_now();
+    let (cmd, rec) = StorageCmd::new_channel_create("a-channel");
+    remote.send(cmd).unwrap();
+    let channel = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, Some(alice), "spam".into());
+    remote.send(cmd).unwrap();
+    let spam = rec.await.unwrap();
+    let (cmd, rec) = StorageCmd::new_message_create(channel, None, "hello".into());
+    remote.send(cmd).unwrap();
+    rec.await.unwrap();
+
+    // banning trashes the recent messages of the user
+    let ban = Ban::new(alice, moderator, "spam".into(), None);
+    let (cmd, rec) = StorageCmd::new_ban_create(ban, Some(spam.timestamp()));
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), vec![(channel, spam)]);
+    let (cmd, rec) = StorageCmd::new_message_list(channel);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().len(), 1);
+    let (cmd, rec) = StorageCmd::new_ban_get(alice);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().unwrap().get_reason(), "spam");
+
+    // expired bans are ignored
+    let other = Id::from_now();
+    let expired = Ban::new(other, moderator, String::new(), Some(0));
+    let (cmd, rec) = StorageCmd::new_ban_create(expired, None);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_empty());
+    let (cmd, rec) = StorageCmd::new_ban_get(other);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().is_none());
+    let (cmd, rec) = StorageCmd::new_ban_list();
+    remote.send(cmd).unwrap();
+    let bans = rec.await.unwrap();
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0].get_user(), alice);
+
+    // unbanning
+    let (cmd, rec) = StorageCmd::new_ban_remove(alice);
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap());
+    let (cmd, rec) = StorageCmd::new_ban_remove(alice);
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap());
+
+    // timeouts take the right to speak away until they end
+    let until = chrono::Utc::now().timestamp_millis() + 60_000;
+    remote
+        .send(StorageCmd::new_timeout_set(alice, Some(until)))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_timeout_get(alice);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap(), Some(until));
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(alice), Scope::Channel(channel));
+    remote.send(cmd).unwrap();
+    assert!(rec.await.unwrap().timed_out);
+    remote
+        .send(StorageCmd::new_timeout_set(alice, None))
+        .unwrap();
+    let (cmd, rec) = StorageCmd::new_perm_get_grants(Some(alice), Scope::Channel(channel));
+    remote.send(cmd).unwrap();
+    assert!(!rec.await.unwrap().timed_out);
+}
+
+#[tokio::test]
+async fn test_audit() {
+    use telecomande::{Executor, SimpleExecutor};
+    // cleaning;
+    std::fs::remove_dir_all("/tmp/db-test-audit").ok();
+
+    // instantiation
+    let store = SimpleExecutor::new(StorageProc::new("/tmp/db-test-audit")).spawn();
+    let remote = store.remote();
+
+    // insertion
+    let (admin, channel) = (Id::from_u64(1), Id::from_u64(2));
+    let entries = [
+        AuditEntry::new(Some(admin), "channel_set_name", Some(channel))
+            .with_before("old")
+            .with_after("new"),
+        AuditEntry::new(None, "channel_create", Some(channel)).with_after("new"),
+        AuditEntry::new(Some(admin), "channel_delete", Some(channel)),
+    ];
+    for entry in entries.iter().cloned() {
+        remote.send(StorageCmd::new_audit_append(entry)).unwrap();
+    }
+
+    // listing, newest first
+    let (cmd, rec) = StorageCmd::new_audit_list(AuditFilter::default(), None, 10);
+    remote.send(cmd).unwrap();
+    let listed = rec.await.unwrap();
+    let actions: Vec<_> = listed.iter().map(|(_, e)| e.get_action()).collect();
+    assert_eq!(
+        actions,
+        vec!["channel_delete", "channel_create", "channel_set_name"]
+    );
+    assert_eq!(listed[2].1, entries[0]);
+
+    // filtering
+    let filter = AuditFilter {
+        actor: Some(admin),
+        ..Default::default()
+    };
+    let (cmd, rec) = StorageCmd::new_audit_list(filter, None, 10);
+    remote.send(cmd).unwrap();
+    assert_eq!(rec.await.unwrap().len(), 2);
+
+    // paginating
+    let (cmd, rec) = StorageCmd::new_audit_list(AuditFilter::default(), None, 1);
+    remote.send(cmd).unwrap();
+    let page = rec.await.unwrap();
+    assert_eq!(page.len(), 1);
+    let (cmd, rec) = StorageCmd::new_audit_list(AuditFilter::default(), Some(page[0].0), 10);
+    remote.send(cmd).unwrap();
+    let rest = rec.await.unwrap();
+    assert_eq!(rest.len(), 2);
+    assert_eq!(rest[0].1.get_action(), "channel_create");
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+use super::{Message, StorageProc};
+use crate::Id;
+
+const MAX_THREAD_PAGE: usize = 100;
+
+fn thread_path(channel_id: Id, parent: Id, id: Id) -> String {
+    format!("/threads/{channel_id}/{parent}/{id}")
+}
+
+fn subscription_path(channel_id: Id, parent: Id, user: Id) -> String {
+    format!("/subscriptions/threads/{channel_id}/{parent}/{user}")
+}
+
+impl StorageProc {
+    /// registers a message in the thread it was posted in and updates the counters of the parent.
+    pub(super) fn attach_to_thread(&mut self, channel_id: Id, message: &Message) {
+        let Some(parent) = message.get_thread() else {
+            return;
+        };
+        let id = message.get_id();
+        self.set(thread_path(channel_id, parent, id), true);
+        let path = format!("/messages/{channel_id}/{parent}");
+        if let Some(mut parent) = self.get::<_, Message>(&path) {
+            parent.add_reply(id.timestamp());
+            self.set(path, parent);
+        }
+    }
+
+    pub(super) fn detach_from_thread(&mut self, channel_id: Id, message: &Message) {
+        let Some(parent) = message.get_thread() else {
+            return;
+        };
+        self.remove(thread_path(channel_id, parent, message.get_id()));
+        let path = format!("/messages/{channel_id}/{parent}");
+        if let Some(mut parent) = self.get::<_, Message>(&path) {
+            parent.remove_reply();
+            self.set(path, parent);
+        }
+    }
+
+    /// messages of the channel that were posted in a thread.
+    pub(super) fn threaded_messages(&self, channel_id: Id) -> BTreeSet<Id> {
+        self.keys(format!("/threads/{channel_id}/"))
+            .iter()
+            .filter_map(|key| Id::from_string(key.rsplit('/').next()?))
+            .collect()
+    }
+
+    /// the last `limit` messages of a thread posted before `before`, in chronological order.
+    pub(super) fn thread_messages(
+        &self,
+        channel_id: Id,
+        parent: Id,
+        before: Option<Id>,
+        limit: usize,
+    ) -> Vec<Id> {
+        let mut messages = self.list(format!("/threads/{channel_id}/{parent}/"));
+        if let Some(before) = before {
+            messages.retain(|id| id < &before);
+        }
+        let limit = limit.clamp(1, MAX_THREAD_PAGE);
+        let skipped = messages.len().saturating_sub(limit);
+        messages.split_off(skipped)
+    }
+
+    pub(super) fn subscribe_thread(&mut self, channel_id: Id, parent: Id, user: Id) {
+        self.set(subscription_path(channel_id, parent, user), true);
+    }
+
+    pub(super) fn unsubscribe_thread(&mut self, channel_id: Id, parent: Id, user: Id) {
+        self.remove(subscription_path(channel_id, parent, user));
+    }
+
+    pub(super) fn thread_subscribers(&self, channel_id: Id, parent: Id) -> Vec<Id> {
+        self.list(format!("/subscriptions/threads/{channel_id}/{parent}/"))
+    }
+
+    /// drops what remains of the threads of a purged channel.
+    pub(super) fn remove_threads(&mut self, channel_id: Id) {
+        for key in self.keys(format!("/threads/{channel_id}/")) {
+            self.remove(key);
+        }
+        for key in self.keys(format!("/subscriptions/threads/{channel_id}/")) {
+            self.remove(key);
+        }
+    }
+}
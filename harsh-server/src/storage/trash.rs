@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Channel, Message, StorageProc};
+use crate::Id;
+
+/// a deleted item, kept aside until the end of its grace period.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Tombstone<T> {
+    item: T,
+    /// in milliseconds since the epoch.
+    deleted_at: i64,
+}
+
+impl<T> Tombstone<T> {
+    fn new(item: T) -> Self {
+        let deleted_at = chrono::Utc::now().timestamp_millis();
+        Self { item, deleted_at }
+    }
+}
+
+/// items removed for good by a purge.
+#[derive(Debug, Default)]
+pub struct Purged {
+    pub channels: Vec<Id>,
+    pub messages: Vec<(Id, Id)>,
+}
+
+fn trashed_channel_path(id: Id) -> String {
+    format!("/trash/channels/{id}")
+}
+
+fn trashed_message_path(channel_id: Id, id: Id) -> String {
+    format!("/trash/messages/{channel_id}/{id}")
+}
+
+impl StorageProc {
+    pub(super) fn trash_message(&mut self, channel_id: Id, id: Id) {
+        let path = format!("/messages/{channel_id}/{id}");
+        if let Some(message) = self.get::<_, Message>(&path) {
+            self.unindex_message(channel_id, &message);
+            self.detach_from_thread(channel_id, &message);
+            self.set(
+                trashed_message_path(channel_id, id),
+                Tombstone::new(message),
+            );
+            self.remove(path);
+        }
+    }
+
+    pub(super) fn restore_message(&mut self, channel_id: Id, id: Id) -> bool {
+        let path = trashed_message_path(channel_id, id);
+        let Some(tombstone) = self.get::<_, Tombstone<Message>>(&path) else {
+            return false;
+        };
+        self.index_message(channel_id, &tombstone.item);
+        self.attach_to_thread(channel_id, &tombstone.item);
+        self.set(format!("/messages/{channel_id}/{id}"), tombstone.item);
+        self.remove(path);
+        true
+    }
+
+    /// the messages of a trashed channel stay in place but are left out of the search index.
+    pub(super) fn trash_channel(&mut self, id: Id) {
+        let path = format!("/channels/{id}");
+        if let Some(channel) = self.get::<_, Channel>(&path) {
+            for message_id in self.list(format!("/messages/{id}/")) {
+                if let Some(message) =
+                    self.get::<_, Message>(format!("/messages/{id}/{message_id}"))
+                {
+                    self.unindex_message(id, &message);
+                }
+            }
+            self.set(trashed_channel_path(id), Tombstone::new(channel));
+            self.remove(path);
+        }
+    }
+
+    pub(super) fn restore_channel(&mut self, id: Id) -> bool {
+        let path = trashed_channel_path(id);
+        let Some(tombstone) = self.get::<_, Tombstone<Channel>>(&path) else {
+            return false;
+        };
+        for message_id in self.list(format!("/messages/{id}/")) {
+            if let Some(message) = self.get::<_, Message>(format!("/messages/{id}/{message_id}")) {
+                self.index_message(id, &message);
+            }
+        }
+        self.set(format!("/channels/{id}"), tombstone.item);
+        self.remove(path);
+        true
+    }
+
+    /// removes for good the items deleted before the given date, in milliseconds since the epoch.
+    pub(super) fn purge(&mut self, before: i64) -> Purged {
+        let mut purged = Purged::default();
+
+        for path in self.keys("/trash/messages/") {
+            let mut parts = path.rsplit('/');
+            let id = parts.next().and_then(Id::from_string);
+            let channel_id = parts.next().and_then(Id::from_string);
+            let (Some(channel_id), Some(id)) = (channel_id, id) else {
+                continue;
+            };
+            let tombstone = self.get::<_, Tombstone<Message>>(&path);
+            if tombstone
+                .as_ref()
+                .map(|t| t.deleted_at < before)
+                .unwrap_or(true)
+            {
+                if let Some(tombstone) = tombstone {
+                    self.unindex_mentions(&tombstone.item);
+                }
+                self.unpin(channel_id, id);
+                self.remove_message_entries(channel_id, id);
+                self.remove(path);
+                purged.messages.push((channel_id, id));
+            }
+        }
+
+        for id in self.list("/trash/channels/") {
+            let path = trashed_channel_path(id);
+            let tombstone = self.get::<_, Tombstone<Channel>>(&path);
+            if tombstone
+                .as_ref()
+                .map(|t| t.deleted_at < before)
+                .unwrap_or(true)
+            {
+                for message_id in self.list(format!("/messages/{id}/")) {
+                    self.remove_message(id, message_id);
+                }
+                for message_id in self.list(format!("/trash/messages/{id}/")) {
+                    let path = trashed_message_path(id, message_id);
+                    if let Some(tombstone) = self.get::<_, Tombstone<Message>>(&path) {
+                        self.unindex_mentions(&tombstone.item);
+                    }
+                    self.remove_message_entries(id, message_id);
+                    self.remove(path);
+                }
+                self.remove_threads(id);
+                self.remove_read_states(id);
+                self.remove_members(id);
+                self.remove_overwrites(id);
+                if let Some(tombstone) = tombstone {
+                    self.remove_direct(&tombstone.item);
+                }
+                self.remove(path);
+                purged.channels.push(id);
+            }
+        }
+
+        purged
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::Id;
+
+/// duration after which a user that did not renew its typing is considered to have stopped.
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// users currently typing in each channel, kept in memory only.
+#[derive(Debug, Default)]
+pub struct Typing {
+    active: HashMap<(Id, Id), Instant>,
+}
+
+impl Typing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// renews the typing of the user, returns whether it just started and needs to be announced.
+    pub fn start(&mut self, channel: Id, user: Id) -> bool {
+        self.start_at(channel, user, Instant::now())
+    }
+
+    fn start_at(&mut self, channel: Id, user: Id, now: Instant) -> bool {
+        let previous = self.active.insert((channel, user), now);
+        !matches!(previous, Some(last) if now.duration_since(last) < TYPING_TIMEOUT)
+    }
+
+    /// returns whether the user was typing.
+    pub fn stop(&mut self, channel: Id, user: Id) -> bool {
+        self.active.remove(&(channel, user)).is_some()
+    }
+
+    /// forgets the typings that were not renewed in time, returning their channel and user.
+    pub fn expire(&mut self) -> Vec<(Id, Id)> {
+        self.expire_at(Instant::now())
+    }
+
+    fn expire_at(&mut self, now: Instant) -> Vec<(Id, Id)> {
+        let expired: Vec<_> = self
+            .active
+            .iter()
+            .filter(|(_, &last)| now.duration_since(last) >= TYPING_TIMEOUT)
+            .map(|(&key, _)| key)
+            .collect();
+        for key in &expired {
+            self.active.remove(key);
+        }
+        expired
+    }
+}
+
+#[test]
+fn test_typing() {
+    let mut typing = Typing::new();
+    let (channel, user) = (Id::from_u64(1), Id::from_u64(2));
+    let now = Instant::now();
+    assert!(typing.start_at(channel, user, now));
+    assert!(!typing.start_at(channel, user, now + Duration::from_secs(1)));
+    assert!(typing.start_at(Id::from_u64(3), user, now));
+
+    let renewed = now + Duration::from_secs(1);
+    assert!(typing.expire_at(renewed + TYPING_TIMEOUT / 2).is_empty());
+    let mut expired = typing.expire_at(renewed + TYPING_TIMEOUT);
+    expired.sort_by_key(|(channel, _)| channel.to_u64());
+    assert_eq!(expired, vec![(channel, user), (Id::from_u64(3), user)]);
+    assert!(!typing.stop(channel, user));
+
+    assert!(typing.start_at(channel, user, now));
+    assert!(typing.stop(channel, user));
+}
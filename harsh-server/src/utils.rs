@@ -11,9 +11,25 @@ impl Addr {
         let string = format!("{address:?}");
         Self(string)
     }
+
+    /// the address without its port, shared by every connection from a same machine.
+    pub fn host(&self) -> String {
+        match self.0.parse::<SocketAddr>() {
+            Ok(address) => address.ip().to_string(),
+            Err(_) => self.0.clone(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[test]
+fn test_addr_host() {
+    let address = Addr::new("127.0.0.1:4000".parse().unwrap());
+    assert_eq!(address.host(), "127.0.0.1");
+    let address = Addr::new("[::1]:4000".parse().unwrap());
+    assert_eq!(address.host(), "::1");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Id(u64);
 
 impl Id {
@@ -28,13 +44,18 @@ impl Id {
         Some(Self(inner))
     }
 
-    pub fn from_u64(input: u64) -> Self {
+    pub const fn from_u64(input: u64) -> Self {
         Self(input)
     }
 
     pub fn to_u64(&self) -> u64 {
         self.0
     }
+
+    /// creation date of ids made with [`Id::from_now`], in milliseconds since the epoch.
+    pub fn timestamp(&self) -> i64 {
+        (self.0 / 1000) as i64
+    }
 }
 
 impl From<u64> for Id {